@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::clone_into_array;
+use crate::netframe::datalink::{ArpFrame, HwAddr, Operation};
+
+use super::{ExpertInfo, Group, Severity};
+
+/// Time after which a request is no longer expected to be answered, so that the
+/// requests of hosts that are down do not pile up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub enum ArpAlert {
+    /// Announcement of the sender's own address, not answering any request.
+    Gratuitous { ip: Ipv4Addr, mac: HwAddr },
+    /// A known IP is now claimed by a different MAC.
+    MacChanged {
+        ip: Ipv4Addr,
+        old: HwAddr,
+        new: HwAddr,
+    },
+    /// An IP is claimed alternately by two MACs that have both owned it.
+    DuplicateIp {
+        ip: Ipv4Addr,
+        mac: HwAddr,
+        other: HwAddr,
+    },
+    /// Reply to a request that was never seen.
+    UnsolicitedReply { ip: Ipv4Addr, mac: HwAddr },
+    /// A MAC sent more replies than the storm threshold within the storm window.
+    ReplyStorm { mac: HwAddr, replies: usize },
+}
+
+impl ArpAlert {
+    pub fn expert_info(&self) -> ExpertInfo {
+        let (severity, summary) = match self {
+            Self::Gratuitous { .. } => (Severity::Note, "Gratuitous ARP"),
+            Self::MacChanged { .. } => (Severity::Warning, "MAC address changed for a known IP"),
+            Self::DuplicateIp { .. } => (Severity::Error, "Duplicate IP address in use"),
+            Self::UnsolicitedReply { .. } => (Severity::Warning, "Unsolicited ARP reply"),
+            Self::ReplyStorm { .. } => (Severity::Error, "ARP reply storm"),
+        };
+        ExpertInfo::new(severity, Group::Security, "ARP", summary)
+    }
+}
+
+impl fmt::Display for ArpAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gratuitous { ip, mac } => write!(f, "Gratuitous ARP: {} is at {}", ip, mac),
+            Self::MacChanged { ip, old, new } => {
+                write!(f, "{} moved from {} to {}", ip, old, new)
+            }
+            Self::DuplicateIp { ip, mac, other } => {
+                write!(f, "{} is used by both {} and {}", ip, mac, other)
+            }
+            Self::UnsolicitedReply { ip, mac } => {
+                write!(f, "Unsolicited reply: {} is at {}", ip, mac)
+            }
+            Self::ReplyStorm { mac, replies } => {
+                write!(f, "Reply storm: {} replies from {}", replies, mac)
+            }
+        }
+    }
+}
+
+pub struct ArpBinding {
+    mac: HwAddr,
+    previous: HashSet<HwAddr>,
+    first_seen: Duration,
+    last_seen: Duration,
+}
+
+impl ArpBinding {
+    pub fn mac(&self) -> HwAddr {
+        self.mac
+    }
+
+    /// Other MACs that claimed this IP before the current one.
+    pub fn previous(&self) -> impl Iterator<Item = &HwAddr> {
+        self.previous.iter()
+    }
+
+    pub fn first_seen(&self) -> Duration {
+        self.first_seen
+    }
+
+    pub fn last_seen(&self) -> Duration {
+        self.last_seen
+    }
+}
+
+/// Builds an IP to MAC table from the ARP traffic of a LAN and raises alerts on
+/// the patterns left by ARP poisoning and address conflicts.
+pub struct ArpMonitor {
+    bindings: HashMap<Ipv4Addr, ArpBinding>,
+    // Time of the last request not answered yet, by requester IP and requested IP
+    requests: HashMap<(Ipv4Addr, Ipv4Addr), Duration>,
+    // Requests in the order they were sent, to expire them
+    request_times: VecDeque<(Duration, (Ipv4Addr, Ipv4Addr))>,
+    replies: HashMap<HwAddr, VecDeque<Duration>>,
+    storm_threshold: usize,
+    storm_window: Duration,
+    alerts: Vec<(Duration, ArpAlert)>,
+}
+
+impl Default for ArpMonitor {
+    fn default() -> Self {
+        Self::new(10, Duration::from_secs(1))
+    }
+}
+
+impl ArpMonitor {
+    /// Monitor raising a reply storm alert when a MAC sends more than `storm_threshold`
+    /// replies within `storm_window`.
+    pub fn new(storm_threshold: usize, storm_window: Duration) -> Self {
+        Self {
+            bindings: HashMap::new(),
+            requests: HashMap::new(),
+            request_times: VecDeque::new(),
+            replies: HashMap::new(),
+            storm_threshold,
+            storm_window,
+            alerts: Vec::new(),
+        }
+    }
+
+    /// Inspects an ARP packet captured at `ts`, returning the alerts it raised. Only
+    /// Ethernet/IPv4 ARP is supported; anything else is ignored.
+    pub fn inspect(&mut self, frame: &ArpFrame, ts: Duration) -> Vec<&ArpAlert> {
+        if frame.htype() != 1 || frame.ptype() != 0x0800 || frame.hlen() != 6 || frame.plen() != 4 {
+            return Vec::new();
+        }
+        let sha = HwAddr::from(frame.sha());
+        let spa = Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(frame.spa()));
+        let tha = HwAddr::from(frame.tha());
+        let tpa = Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(frame.tpa()));
+        let mut alerts = Vec::new();
+
+        while let Some(&(sent, key)) = self.request_times.front() {
+            if ts.checked_sub(sent).unwrap_or_default() <= REQUEST_TIMEOUT {
+                break;
+            }
+            self.request_times.pop_front();
+            // Only the last of repeated requests counts
+            if self.requests.get(&key) == Some(&sent) {
+                self.requests.remove(&key);
+            }
+        }
+
+        let is_reply = matches!(frame.oper(), Operation::Reply);
+        let gratuitous = spa == tpa || (is_reply && tha.is_broadcast());
+        if gratuitous && !spa.is_unspecified() {
+            alerts.push(ArpAlert::Gratuitous { ip: spa, mac: sha });
+        }
+
+        if is_reply {
+            if !gratuitous && self.requests.remove(&(tpa, spa)).is_none() {
+                alerts.push(ArpAlert::UnsolicitedReply { ip: spa, mac: sha });
+            }
+            let window = self.storm_window;
+            let replies = self.replies.entry(sha).or_default();
+            replies.push_back(ts);
+            while replies
+                .front()
+                .is_some_and(|&first| ts.checked_sub(first).unwrap_or_default() > window)
+            {
+                replies.pop_front();
+            }
+            // Alert once when the threshold is crossed, not for every reply above it
+            if replies.len() == self.storm_threshold + 1 {
+                alerts.push(ArpAlert::ReplyStorm {
+                    mac: sha,
+                    replies: replies.len(),
+                });
+            }
+        } else if !gratuitous {
+            self.requests.insert((spa, tpa), ts);
+            self.request_times.push_back((ts, (spa, tpa)));
+        }
+
+        // Probes from hosts without an address yet use 0.0.0.0 and teach nothing
+        if !spa.is_unspecified() {
+            match self.bindings.get_mut(&spa) {
+                Some(binding) if binding.mac != sha => {
+                    let old = binding.mac;
+                    if binding.previous.contains(&sha) {
+                        alerts.push(ArpAlert::DuplicateIp {
+                            ip: spa,
+                            mac: sha,
+                            other: old,
+                        });
+                    } else {
+                        alerts.push(ArpAlert::MacChanged {
+                            ip: spa,
+                            old,
+                            new: sha,
+                        });
+                    }
+                    binding.previous.insert(old);
+                    binding.previous.remove(&sha);
+                    binding.mac = sha;
+                    binding.last_seen = ts;
+                }
+                Some(binding) => binding.last_seen = ts,
+                None => {
+                    self.bindings.insert(
+                        spa,
+                        ArpBinding {
+                            mac: sha,
+                            previous: HashSet::new(),
+                            first_seen: ts,
+                            last_seen: ts,
+                        },
+                    );
+                }
+            }
+        }
+
+        let first = self.alerts.len();
+        self.alerts
+            .extend(alerts.into_iter().map(|alert| (ts, alert)));
+        self.alerts[first..]
+            .iter()
+            .map(|(_, alert)| alert)
+            .collect()
+    }
+
+    /// IP to MAC table ordered by IP.
+    pub fn bindings(&self) -> Vec<(&Ipv4Addr, &ArpBinding)> {
+        let mut bindings: Vec<_> = self.bindings.iter().collect();
+        bindings.sort_by_key(|(ip, _)| *ip);
+        bindings
+    }
+
+    /// Every alert raised so far with the capture time of the packet that raised it.
+    pub fn alerts(&self) -> &[(Duration, ArpAlert)] {
+        &self.alerts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty() && self.alerts.is_empty()
+    }
+}
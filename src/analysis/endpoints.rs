@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::netframe::datalink::{EtherType, EthernetFrame, HwAddr};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Endpoint {
+    Mac(HwAddr),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Mac(addr) => write!(f, "{}", addr),
+            Endpoint::Ipv4(addr) => write!(f, "{}", addr),
+            Endpoint::Ipv6(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Traffic {
+    tx_packets: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    rx_bytes: u64,
+}
+
+impl Traffic {
+    pub fn tx_packets(&self) -> u64 {
+        self.tx_packets
+    }
+
+    pub fn tx_bytes(&self) -> u64 {
+        self.tx_bytes
+    }
+
+    pub fn rx_packets(&self) -> u64 {
+        self.rx_packets
+    }
+
+    pub fn rx_bytes(&self) -> u64 {
+        self.rx_bytes
+    }
+
+    pub fn packets(&self) -> u64 {
+        self.tx_packets + self.rx_packets
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.tx_bytes + self.rx_bytes
+    }
+}
+
+/// Per address packet and byte counters, split by direction.
+#[derive(Default)]
+pub struct EndpointTable {
+    endpoints: HashMap<Endpoint, Traffic>,
+}
+
+impl EndpointTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accounts a captured frame of `len` bytes on the wire to its hardware and
+    /// network layer addresses.
+    pub fn record(&mut self, frame: EthernetFrame, len: u32) {
+        let len = u64::from(len);
+        self.add(
+            Endpoint::Mac(frame.src_addr()),
+            Endpoint::Mac(frame.dest_addr()),
+            len,
+        );
+        match frame.try_next_header() {
+            EtherType::Ipv4(frame) => self.add(
+                Endpoint::Ipv4(frame.src_addr()),
+                Endpoint::Ipv4(frame.dest_addr()),
+                len,
+            ),
+            EtherType::Ipv6(frame) => self.add(
+                Endpoint::Ipv6(frame.src_addr()),
+                Endpoint::Ipv6(frame.dest_addr()),
+                len,
+            ),
+            _ => {}
+        }
+    }
+
+    fn add(&mut self, src: Endpoint, dest: Endpoint, len: u64) {
+        let tx = self.endpoints.entry(src).or_default();
+        tx.tx_packets += 1;
+        tx.tx_bytes += len;
+        let rx = self.endpoints.entry(dest).or_default();
+        rx.rx_packets += 1;
+        rx.rx_bytes += len;
+    }
+
+    /// Endpoints ordered by address family and then by address.
+    pub fn sorted(&self) -> Vec<(&Endpoint, &Traffic)> {
+        let mut endpoints: Vec<_> = self.endpoints.iter().collect();
+        endpoints.sort_by_key(|(endpoint, _)| *endpoint);
+        endpoints
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+}
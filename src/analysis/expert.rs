@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Severity {
+    Chat,
+    Note,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Chat => write!(f, "Chat"),
+            Severity::Note => write!(f, "Note"),
+            Severity::Warning => write!(f, "Warning"),
+            Severity::Error => write!(f, "Error"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Group {
+    Checksum,
+    Sequence,
+    Malformed,
+    Protocol,
+    Security,
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Group::Checksum => write!(f, "Checksum"),
+            Group::Sequence => write!(f, "Sequence"),
+            Group::Malformed => write!(f, "Malformed"),
+            Group::Protocol => write!(f, "Protocol"),
+            Group::Security => write!(f, "Security"),
+        }
+    }
+}
+
+/// Diagnostic note attached to a dissected packet.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ExpertInfo {
+    severity: Severity,
+    group: Group,
+    protocol: &'static str,
+    summary: &'static str,
+}
+
+impl ExpertInfo {
+    pub fn new(
+        severity: Severity,
+        group: Group,
+        protocol: &'static str,
+        summary: &'static str,
+    ) -> Self {
+        Self {
+            severity,
+            group,
+            protocol,
+            summary,
+        }
+    }
+
+    pub fn bad_checksum(protocol: &'static str) -> Self {
+        Self::new(Severity::Error, Group::Checksum, protocol, "Bad checksum")
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn group(&self) -> Group {
+        self.group
+    }
+
+    pub fn protocol(&self) -> &'static str {
+        self.protocol
+    }
+
+    pub fn summary(&self) -> &'static str {
+        self.summary
+    }
+}
+
+impl fmt::Display for ExpertInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}/{}] {}: {}",
+            self.severity, self.group, self.protocol, self.summary
+        )
+    }
+}
+
+/// Occurrence count of every distinct expert note seen during a run.
+#[derive(Default)]
+pub struct ExpertSummary {
+    counts: BTreeMap<ExpertInfo, u64>,
+}
+
+impl ExpertSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, info: ExpertInfo) {
+        *self.counts.entry(info).or_insert(0) += 1;
+    }
+
+    /// Notes ordered from the most to the least severe.
+    pub fn entries(&self) -> impl Iterator<Item = (&ExpertInfo, u64)> {
+        self.counts.iter().rev().map(|(info, count)| (info, *count))
+    }
+
+    pub fn count(&self, severity: Severity) -> u64 {
+        self.counts
+            .iter()
+            .filter(|(info, _)| info.severity == severity)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
@@ -0,0 +1,15 @@
+pub use arp::*;
+pub use endpoints::*;
+pub use expert::*;
+pub use neighbors::*;
+pub use rtp::*;
+pub use scan::*;
+pub use tcp::*;
+
+mod arp;
+mod endpoints;
+mod expert;
+mod neighbors;
+mod rtp;
+mod scan;
+mod tcp;
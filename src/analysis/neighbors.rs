@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::netframe::datalink::{
+    cdp_capability_names, lldp_capability_names, CdpFrame, CdpTlv, HwAddr, LldpFrame, LldpId,
+    LldpTlv,
+};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum DiscoveryProtocol {
+    Lldp,
+    Cdp,
+}
+
+impl fmt::Display for DiscoveryProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryProtocol::Lldp => write!(f, "LLDP"),
+            DiscoveryProtocol::Cdp => write!(f, "CDP"),
+        }
+    }
+}
+
+/// Port of a device announcing itself, as last described by its announcements.
+pub struct Neighbor {
+    protocol: DiscoveryProtocol,
+    mac: HwAddr,
+    chassis_id: String,
+    port_id: String,
+    port_description: Option<String>,
+    name: Option<String>,
+    platform: Option<String>,
+    capabilities: Vec<&'static str>,
+    addresses: Vec<IpAddr>,
+    vlan: Option<u16>,
+    first_seen: Duration,
+    last_seen: Duration,
+    packets: u64,
+}
+
+impl Neighbor {
+    fn new(
+        protocol: DiscoveryProtocol,
+        mac: HwAddr,
+        chassis_id: String,
+        port_id: String,
+        ts: Duration,
+    ) -> Self {
+        Self {
+            protocol,
+            mac,
+            chassis_id,
+            port_id,
+            port_description: None,
+            name: None,
+            platform: None,
+            capabilities: Vec::new(),
+            addresses: Vec::new(),
+            vlan: None,
+            first_seen: ts,
+            last_seen: ts,
+            packets: 0,
+        }
+    }
+
+    pub fn protocol(&self) -> DiscoveryProtocol {
+        self.protocol
+    }
+
+    /// Source address of the announcements, usually the one of the port itself.
+    pub fn mac(&self) -> HwAddr {
+        self.mac
+    }
+
+    /// Identifier of the device, the device ID for CDP.
+    pub fn chassis_id(&self) -> &str {
+        &self.chassis_id
+    }
+
+    pub fn port_id(&self) -> &str {
+        &self.port_id
+    }
+
+    pub fn port_description(&self) -> Option<&str> {
+        self.port_description.as_deref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Hardware platform for CDP, or first line of the system description for LLDP.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+
+    /// Enabled capabilities for LLDP, or every capability for CDP.
+    pub fn capabilities(&self) -> &[&'static str] {
+        &self.capabilities
+    }
+
+    /// Management and interface addresses announced so far.
+    pub fn addresses(&self) -> &[IpAddr] {
+        &self.addresses
+    }
+
+    /// Port VLAN ID for LLDP, or native VLAN for CDP.
+    pub fn vlan(&self) -> Option<u16> {
+        self.vlan
+    }
+
+    pub fn first_seen(&self) -> Duration {
+        self.first_seen
+    }
+
+    pub fn last_seen(&self) -> Duration {
+        self.last_seen
+    }
+
+    pub fn packets(&self) -> u64 {
+        self.packets
+    }
+
+    fn add_address(&mut self, addr: IpAddr) {
+        if !self.addresses.contains(&addr) {
+            self.addresses.push(addr);
+        }
+    }
+}
+
+fn text(value: &[u8]) -> String {
+    String::from_utf8_lossy(value).trim().to_string()
+}
+
+/// Devices found by their LLDP and CDP announcements, one entry per announcing port.
+#[derive(Default)]
+pub struct NeighborTable {
+    neighbors: HashMap<(DiscoveryProtocol, String, String), Neighbor>,
+}
+
+impl NeighborTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entry(
+        &mut self,
+        protocol: DiscoveryProtocol,
+        mac: HwAddr,
+        chassis_id: String,
+        port_id: String,
+        ts: Duration,
+    ) -> &mut Neighbor {
+        let neighbor = self
+            .neighbors
+            .entry((protocol, chassis_id.clone(), port_id.clone()))
+            .or_insert_with(|| Neighbor::new(protocol, mac, chassis_id, port_id, ts));
+        neighbor.mac = mac;
+        neighbor.last_seen = ts;
+        neighbor.packets += 1;
+        neighbor
+    }
+
+    /// Records an LLDP data unit sent from `mac` at `ts`.
+    pub fn record_lldp(&mut self, mac: HwAddr, frame: &LldpFrame, ts: Duration) {
+        let neighbor = self.entry(
+            DiscoveryProtocol::Lldp,
+            mac,
+            frame.chassis_id().to_string(),
+            frame.port_id().to_string(),
+            ts,
+        );
+        if let LldpId::Address(addr) = frame.chassis_id() {
+            neighbor.add_address(*addr);
+        }
+        for tlv in frame.tlvs() {
+            match tlv {
+                LldpTlv::PortDescription(value) => neighbor.port_description = Some(text(value)),
+                LldpTlv::SystemName(value) => neighbor.name = Some(text(value)),
+                LldpTlv::SystemDescription(value) => {
+                    neighbor.platform = text(value).lines().next().map(str::to_string)
+                }
+                LldpTlv::Capabilities { enabled, .. } => {
+                    neighbor.capabilities = lldp_capability_names(*enabled)
+                }
+                LldpTlv::ManagementAddress {
+                    address: Some(addr),
+                    ..
+                } => neighbor.add_address(*addr),
+                LldpTlv::PortVlanId(vlan_id) => neighbor.vlan = Some(*vlan_id),
+                _ => {}
+            }
+        }
+    }
+
+    /// Records a CDP packet sent from `mac` at `ts`, ignoring the packets without a
+    /// device ID.
+    pub fn record_cdp(&mut self, mac: HwAddr, frame: &CdpFrame, ts: Duration) {
+        let device_id = match frame.device_id() {
+            Some(device_id) => text(device_id),
+            None => return,
+        };
+        let port_id = frame.port_id().map(text).unwrap_or_default();
+        let neighbor = self.entry(DiscoveryProtocol::Cdp, mac, device_id.clone(), port_id, ts);
+        neighbor.name = Some(device_id);
+        for tlv in frame.tlvs() {
+            match tlv {
+                CdpTlv::Addresses(addresses) | CdpTlv::ManagementAddresses(addresses) => {
+                    for addr in addresses {
+                        neighbor.add_address(*addr);
+                    }
+                }
+                CdpTlv::Capabilities(bits) => neighbor.capabilities = cdp_capability_names(*bits),
+                CdpTlv::Platform(value) => neighbor.platform = Some(text(value)),
+                CdpTlv::NativeVlan(vlan_id) => neighbor.vlan = Some(*vlan_id),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = &Neighbor> {
+        self.neighbors.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+}
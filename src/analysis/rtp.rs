@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::netframe::application::{rtp_static_payload, RtpFrame, SdpSession};
+
+use super::{ExpertInfo, Group, Severity};
+
+/// Packets in sequence needed before a stream not negotiated by a session description
+/// is trusted (RFC 3550, appendix A.1). More are required than the RFC suggests, as
+/// any datagram between unprivileged even ports may pass for RTP.
+const MIN_SEQUENTIAL: u8 = 4;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum RtpEvent {
+    SequenceGap,
+    OutOfOrder,
+    Duplicate,
+}
+
+impl RtpEvent {
+    pub fn expert_info(&self) -> ExpertInfo {
+        let (severity, summary) = match self {
+            Self::SequenceGap => (Severity::Warning, "Previous packet not captured"),
+            Self::OutOfOrder => (Severity::Warning, "Out-of-order packet"),
+            Self::Duplicate => (Severity::Note, "Duplicate packet"),
+        };
+        ExpertInfo::new(severity, Group::Sequence, "RTP", summary)
+    }
+}
+
+impl fmt::Display for RtpEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expert_info().summary())
+    }
+}
+
+/// Packets of a single synchronization source sent from one socket to another.
+pub struct RtpStream {
+    src: SocketAddr,
+    dest: SocketAddr,
+    ssrc: u32,
+    payload_type: u8,
+    clock_rate: Option<u32>,
+    packets: u64,
+    // Extended sequence numbers, counting the wraparounds
+    base_seq: u64,
+    max_seq: u64,
+    seq_errors: u64,
+    // Packets still to be received in sequence before the stream is trusted
+    probation: u8,
+    // Arrival time, in timestamp units, and RTP timestamp of the previous packet
+    last: Option<(f64, u32)>,
+    jitter: f64,
+    max_jitter: f64,
+}
+
+impl RtpStream {
+    fn new(
+        src: SocketAddr,
+        dest: SocketAddr,
+        frame: &RtpFrame,
+        clock_rate: Option<u32>,
+        trusted: bool,
+    ) -> Self {
+        // Starting one cycle in keeps packets reordered before the first one positive
+        let seq = (1 << 16) + u64::from(frame.seq_num());
+        Self {
+            src,
+            dest,
+            ssrc: frame.ssrc(),
+            payload_type: frame.payload_type(),
+            clock_rate,
+            packets: 0,
+            base_seq: seq,
+            max_seq: seq,
+            seq_errors: 0,
+            probation: if trusted { 0 } else { MIN_SEQUENTIAL - 1 },
+            last: None,
+            jitter: 0.0,
+            max_jitter: 0.0,
+        }
+    }
+
+    pub fn src(&self) -> SocketAddr {
+        self.src
+    }
+
+    pub fn dest(&self) -> SocketAddr {
+        self.dest
+    }
+
+    pub fn ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    /// Payload type of the last packet.
+    pub fn payload_type(&self) -> u8 {
+        self.payload_type
+    }
+
+    pub fn packets(&self) -> u64 {
+        self.packets
+    }
+
+    /// Packets expected from the lowest and highest sequence numbers seen.
+    pub fn expected(&self) -> u64 {
+        self.max_seq - self.base_seq + 1
+    }
+
+    /// Packets expected but not received, negative when some were duplicated
+    /// (RFC 3550, appendix A.3).
+    pub fn lost(&self) -> i64 {
+        self.expected() as i64 - self.packets as i64
+    }
+
+    /// Whether the stream was negotiated by a session description, or has shown
+    /// enough packets in sequence to be told apart from other traffic.
+    pub fn is_valid(&self) -> bool {
+        self.probation == 0
+    }
+
+    /// Gaps, reordered and duplicated packets in the sequence numbers.
+    pub fn seq_errors(&self) -> u64 {
+        self.seq_errors
+    }
+
+    /// Interarrival jitter estimate after the last packet, or `None` if the clock
+    /// rate of the payload type is unknown.
+    pub fn jitter(&self) -> Option<Duration> {
+        self.to_duration(self.jitter)
+    }
+
+    pub fn max_jitter(&self) -> Option<Duration> {
+        self.to_duration(self.max_jitter)
+    }
+
+    fn to_duration(&self, units: f64) -> Option<Duration> {
+        Some(Duration::from_secs_f64(units / f64::from(self.clock_rate?)))
+    }
+
+    fn record(&mut self, frame: &RtpFrame, ts: Duration) -> Option<RtpEvent> {
+        // A stream on probation starts over unless its packets follow one another
+        if self.probation > 0 && self.packets > 0 {
+            let next = frame.seq_num() == (self.max_seq as u16).wrapping_add(1);
+            if next && frame.payload_type() == self.payload_type {
+                self.probation -= 1;
+            } else {
+                *self = Self::new(self.src, self.dest, frame, self.clock_rate, false);
+            }
+        }
+        self.packets += 1;
+        self.payload_type = frame.payload_type();
+
+        let delta = frame.seq_num().wrapping_sub(self.max_seq as u16) as i16;
+        let seq = (self.max_seq as i64 + i64::from(delta)) as u64;
+        let event = match delta {
+            _ if self.packets == 1 => None,
+            1 => None,
+            0 => Some(RtpEvent::Duplicate),
+            d if d > 1 => Some(RtpEvent::SequenceGap),
+            _ => Some(RtpEvent::OutOfOrder),
+        };
+        self.max_seq = self.max_seq.max(seq);
+        self.base_seq = self.base_seq.min(seq);
+        if event.is_some() {
+            self.seq_errors += 1;
+        }
+
+        // RFC 3550, appendix A.8: J += (|D| - J) / 16, the timestamps being
+        // subtracted modulo 2^32 so that their wraparound is not taken for a delay
+        if let Some(clock_rate) = self.clock_rate {
+            let arrival = ts.as_secs_f64() * f64::from(clock_rate);
+            if let Some((last_arrival, last_timestamp)) = self.last {
+                let elapsed = frame.timestamp().wrapping_sub(last_timestamp) as i32;
+                let d = arrival - last_arrival - f64::from(elapsed);
+                self.jitter += (d.abs() - self.jitter) / 16.0;
+                self.max_jitter = self.max_jitter.max(self.jitter);
+            }
+            self.last = Some((arrival, frame.timestamp()));
+        }
+        event.filter(|_| self.is_valid())
+    }
+}
+
+/// Tracks RTP streams, along with the media endpoints negotiated by session
+/// descriptions so that streams on any port can be recognized.
+#[derive(Default)]
+pub struct RtpTracker {
+    // Clock rate of the dynamic payload types bound to each endpoint
+    endpoints: HashMap<SocketAddr, HashMap<u8, u32>>,
+    streams: HashMap<(SocketAddr, SocketAddr, u32), RtpStream>,
+}
+
+impl RtpTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers the RTP endpoints of a session description, along with the RTCP
+    /// endpoints on the next port.
+    pub fn learn(&mut self, session: &SdpSession) {
+        for media in session
+            .media
+            .iter()
+            .filter(|media| media.is_rtp() && !media.is_disabled())
+        {
+            if let Some(endpoint) = session.endpoint(media) {
+                let rates = media
+                    .rtpmaps
+                    .iter()
+                    .map(|map| (map.payload_type, map.clock_rate))
+                    .collect();
+                self.endpoints.insert(endpoint, rates);
+                let rtcp = SocketAddr::new(endpoint.ip(), endpoint.port().wrapping_add(1));
+                self.endpoints.entry(rtcp).or_default();
+            }
+        }
+    }
+
+    /// Whether `addr` was negotiated as an RTP or RTCP endpoint.
+    pub fn is_media(&self, addr: &SocketAddr) -> bool {
+        self.endpoints.contains_key(addr)
+    }
+
+    /// Records a packet from `src` to `dest` captured at `ts`, measured from any fixed
+    /// point in time.
+    pub fn record(
+        &mut self,
+        src: SocketAddr,
+        dest: SocketAddr,
+        frame: &RtpFrame,
+        ts: Duration,
+    ) -> Option<RtpEvent> {
+        let payload_type = frame.payload_type();
+        let clock_rate = self
+            .endpoints
+            .get(&dest)
+            .and_then(|rates| rates.get(&payload_type).copied())
+            .or_else(|| rtp_static_payload(payload_type).map(|(_, rate)| rate));
+        let trusted = self.is_media(&dest);
+        let stream = self
+            .streams
+            .entry((src, dest, frame.ssrc()))
+            .or_insert_with(|| RtpStream::new(src, dest, frame, clock_rate, trusted));
+        if stream.clock_rate.is_none() {
+            stream.clock_rate = clock_rate;
+        }
+        if trusted {
+            stream.probation = 0;
+        }
+        stream.record(frame, ts)
+    }
+
+    /// Streams negotiated by a session description or past their probation.
+    pub fn streams(&self) -> impl Iterator<Item = &RtpStream> {
+        self.streams.values().filter(|stream| stream.is_valid())
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams().next().is_none()
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::netframe::internet::{check_ipv4, IpProtocol, Ipv4Frame, Ipv6Frame};
+use crate::netframe::transport::TcpFrame;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ProbeKind {
+    TcpSyn,
+    TcpFin,
+    TcpNull,
+    TcpXmas,
+    Udp,
+    IcmpEcho,
+}
+
+impl ProbeKind {
+    /// Classifies the segments sent by the common TCP scan techniques. Segments that
+    /// belong to regular connections are not probes.
+    pub fn from_tcp(frame: &TcpFrame) -> Option<Self> {
+        if frame.ack() || frame.rst() {
+            return None;
+        }
+        match (frame.syn(), frame.fin(), frame.psh(), frame.urg()) {
+            (true, false, _, _) => Some(Self::TcpSyn),
+            (false, true, true, true) => Some(Self::TcpXmas),
+            (false, true, false, false) => Some(Self::TcpFin),
+            (false, false, false, false) => Some(Self::TcpNull),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TcpSyn => write!(f, "TCP SYN"),
+            Self::TcpFin => write!(f, "TCP FIN"),
+            Self::TcpNull => write!(f, "TCP NULL"),
+            Self::TcpXmas => write!(f, "TCP Xmas"),
+            Self::Udp => write!(f, "UDP"),
+            Self::IcmpEcho => write!(f, "ICMP echo"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ScanKind {
+    /// Many ports probed on a single host.
+    Vertical(IpAddr),
+    /// A single port probed on many hosts.
+    Horizontal(u16),
+    /// Many hosts pinged.
+    Sweep,
+}
+
+pub struct ScanAlert {
+    source: IpAddr,
+    kind: ScanKind,
+    probe: ProbeKind,
+    targets: usize,
+}
+
+impl ScanAlert {
+    pub fn source(&self) -> IpAddr {
+        self.source
+    }
+
+    pub fn kind(&self) -> ScanKind {
+        self.kind
+    }
+
+    pub fn probe(&self) -> ProbeKind {
+        self.probe
+    }
+
+    /// Distinct ports (vertical scans) or hosts (horizontal scans and sweeps) probed
+    /// within the time window.
+    pub fn targets(&self) -> usize {
+        self.targets
+    }
+}
+
+impl fmt::Display for ScanAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ScanKind::Vertical(host) => write!(
+                f,
+                "{} scan from {}: {} ports probed on {}",
+                self.probe, self.source, self.targets, host
+            ),
+            ScanKind::Horizontal(port) => write!(
+                f,
+                "{} scan from {}: port {} probed on {} hosts",
+                self.probe, self.source, port, self.targets
+            ),
+            ScanKind::Sweep => write!(
+                f,
+                "{} sweep from {}: {} hosts probed",
+                self.probe, self.source, self.targets
+            ),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ScanThresholds {
+    /// Time window in which the probes from a source are correlated.
+    pub window: Duration,
+    /// Distinct ports probed on one host to report a vertical scan.
+    pub ports: usize,
+    /// Distinct hosts probed on one port, or pinged, to report a horizontal scan or sweep.
+    pub hosts: usize,
+}
+
+impl Default for ScanThresholds {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(60),
+            ports: 20,
+            hosts: 20,
+        }
+    }
+}
+
+/// Highest port of the well-known services, which answer from their own port.
+const MAX_SERVICE_PORT: u16 = 1023;
+/// Protocol number of ICMPv6, which has no dissector of its own.
+const ICMPV6: u16 = 0x3A;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+
+/// Source and destination addresses and ports of a UDP datagram.
+type UdpFlow = (IpAddr, u16, IpAddr, u16);
+
+struct Probe {
+    ts: Duration,
+    dest: IpAddr,
+    port: u16,
+}
+
+/// Decrements the count of `key`, removing it once it drops to zero. Returns whether
+/// it was removed.
+fn decrement<K: Hash + Eq>(counts: &mut HashMap<K, usize>, key: K) -> bool {
+    match counts.get_mut(&key) {
+        Some(count) if *count > 1 => {
+            *count -= 1;
+            false
+        }
+        _ => counts.remove(&key).is_some(),
+    }
+}
+
+/// Probes of a source in the time window, along with running counts of their targets
+/// so that each probe is accounted for without going over the whole window.
+#[derive(Default)]
+struct ProbeWindow {
+    probes: VecDeque<Probe>,
+    /// Probes per host and port.
+    targets: HashMap<(IpAddr, u16), usize>,
+    /// Distinct ports probed per host.
+    ports: HashMap<IpAddr, usize>,
+    /// Distinct hosts probed per port.
+    hosts: HashMap<u16, usize>,
+}
+
+impl ProbeWindow {
+    fn push(&mut self, probe: Probe) {
+        let count = self.targets.entry((probe.dest, probe.port)).or_default();
+        *count += 1;
+        if *count == 1 {
+            *self.ports.entry(probe.dest).or_default() += 1;
+            *self.hosts.entry(probe.port).or_default() += 1;
+        }
+        self.probes.push_back(probe);
+    }
+
+    /// Drops the probes sent more than `window` before `ts`.
+    fn expire(&mut self, ts: Duration, window: Duration) {
+        while self
+            .probes
+            .front()
+            .is_some_and(|probe| ts.checked_sub(probe.ts).unwrap_or_default() > window)
+        {
+            if let Some(probe) = self.probes.pop_front() {
+                if decrement(&mut self.targets, (probe.dest, probe.port)) {
+                    decrement(&mut self.ports, probe.dest);
+                    decrement(&mut self.hosts, probe.port);
+                }
+            }
+        }
+    }
+}
+
+/// Correlates the probes sent by each source within a sliding time window to find
+/// port scans and host sweeps.
+pub struct ScanDetector {
+    thresholds: ScanThresholds,
+    probes: HashMap<(IpAddr, ProbeKind), ProbeWindow>,
+    // Time of the last datagram of each UDP flow seen in the time window
+    udp_flows: HashMap<UdpFlow, Duration>,
+    // Datagrams in the order they were seen, to expire their flows
+    udp_times: VecDeque<(Duration, UdpFlow)>,
+}
+
+impl ScanDetector {
+    pub fn new(thresholds: ScanThresholds) -> Self {
+        Self {
+            thresholds,
+            probes: HashMap::new(),
+            udp_flows: HashMap::new(),
+            udp_times: VecDeque::new(),
+        }
+    }
+
+    pub fn thresholds(&self) -> ScanThresholds {
+        self.thresholds
+    }
+
+    /// Inspects an IPv4 datagram captured at `ts`, returning the alerts for the
+    /// thresholds it made its source cross.
+    pub fn inspect(&mut self, frame: Ipv4Frame, ts: Duration) -> Vec<ScanAlert> {
+        let src = IpAddr::V4(frame.src_addr());
+        let dest = IpAddr::V4(frame.dest_addr());
+        match frame.try_next_header() {
+            IpProtocol::Icmp(frame) => match (frame.type_(), frame.code()) {
+                (8, _) => self.add(src, dest, 0, ProbeKind::IcmpEcho, ts),
+                // A port unreachable quotes the UDP probe that hit a closed port, which
+                // reveals the probe even when it was not captured itself
+                (3, 3) if check_ipv4(frame.payload()).is_ok() => {
+                    let original = Ipv4Frame::from(frame.payload());
+                    let (scanner, target) = (original.src_addr(), original.dest_addr());
+                    match original.try_next_header() {
+                        IpProtocol::Udp(probe) => self.add(
+                            scanner.into(),
+                            target.into(),
+                            probe.dest_port(),
+                            ProbeKind::Udp,
+                            ts,
+                        ),
+                        _ => Vec::new(),
+                    }
+                }
+                _ => Vec::new(),
+            },
+            other => self.inspect_transport(src, dest, other, ts),
+        }
+    }
+
+    /// Inspects an IPv6 packet captured at `ts`. Only the upper layer protocols
+    /// directly following the fixed header are inspected.
+    pub fn inspect_ipv6(&mut self, frame: Ipv6Frame, ts: Duration) -> Vec<ScanAlert> {
+        let src = IpAddr::V6(frame.src_addr());
+        let dest = IpAddr::V6(frame.dest_addr());
+        match frame.try_next_header() {
+            IpProtocol::Other(frame) if frame.type_id() == ICMPV6 => {
+                match frame.payload().first() {
+                    Some(&ICMPV6_ECHO_REQUEST) => self.add(src, dest, 0, ProbeKind::IcmpEcho, ts),
+                    _ => Vec::new(),
+                }
+            }
+            other => self.inspect_transport(src, dest, other, ts),
+        }
+    }
+
+    fn inspect_transport(
+        &mut self,
+        src: IpAddr,
+        dest: IpAddr,
+        protocol: IpProtocol,
+        ts: Duration,
+    ) -> Vec<ScanAlert> {
+        match protocol {
+            IpProtocol::Tcp(frame) => match ProbeKind::from_tcp(&frame) {
+                Some(kind) => self.add(src, dest, frame.dest_port(), kind, ts),
+                None => Vec::new(),
+            },
+            IpProtocol::Udp(frame) => {
+                let (src_port, dest_port) = (frame.src_port(), frame.dest_port());
+                let answered = self.see_udp((src, src_port, dest, dest_port), ts);
+                // A service answering a datagram seen in the window is not probing its
+                // client, but datagrams from other ports still count when answered, as
+                // open ports answer the probes of a scanner
+                if answered && src_port <= MAX_SERVICE_PORT {
+                    Vec::new()
+                } else {
+                    self.add(src, dest, dest_port, ProbeKind::Udp, ts)
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Records a datagram of `flow` seen at `ts`, returning whether a datagram of the
+    /// reverse flow was seen in the time window.
+    fn see_udp(&mut self, flow: UdpFlow, ts: Duration) -> bool {
+        while let Some(&(seen, key)) = self.udp_times.front() {
+            if ts.checked_sub(seen).unwrap_or_default() <= self.thresholds.window {
+                break;
+            }
+            self.udp_times.pop_front();
+            // Only the last datagram of a flow keeps it in the window
+            if self.udp_flows.get(&key) == Some(&seen) {
+                self.udp_flows.remove(&key);
+            }
+        }
+        let (src, src_port, dest, dest_port) = flow;
+        let answered = self
+            .udp_flows
+            .contains_key(&(dest, dest_port, src, src_port));
+        if self.udp_flows.insert(flow, ts) != Some(ts) {
+            self.udp_times.push_back((ts, flow));
+        }
+        answered
+    }
+
+    fn add(
+        &mut self,
+        src: IpAddr,
+        dest: IpAddr,
+        port: u16,
+        kind: ProbeKind,
+        ts: Duration,
+    ) -> Vec<ScanAlert> {
+        let thresholds = self.thresholds;
+        let probes = self.probes.entry((src, kind)).or_default();
+        probes.push(Probe { ts, dest, port });
+        probes.expire(ts, thresholds.window);
+
+        let mut alerts = Vec::new();
+        // Alerts are raised when a count reaches its threshold, so a scan is reported
+        // once per window instead of once per probe
+        if probes.targets[&(dest, port)] != 1 {
+            return alerts;
+        }
+        let hosts = probes.hosts[&port];
+        if kind == ProbeKind::IcmpEcho {
+            // Pings have no port, so their hosts are all counted under port 0
+            if hosts == thresholds.hosts {
+                alerts.push(ScanAlert {
+                    source: src,
+                    kind: ScanKind::Sweep,
+                    probe: kind,
+                    targets: hosts,
+                });
+            }
+            return alerts;
+        }
+
+        let ports = probes.ports[&dest];
+        if ports == thresholds.ports {
+            alerts.push(ScanAlert {
+                source: src,
+                kind: ScanKind::Vertical(dest),
+                probe: kind,
+                targets: ports,
+            });
+        }
+        if hosts == thresholds.hosts {
+            alerts.push(ScanAlert {
+                source: src,
+                kind: ScanKind::Horizontal(port),
+                probe: kind,
+                targets: hosts,
+            });
+        }
+        alerts
+    }
+}
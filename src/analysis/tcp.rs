@@ -0,0 +1,395 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::mem;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::netframe::transport::{TcpFrame, TcpOption};
+
+use super::{ExpertInfo, Group, Severity};
+
+/// Time under which a segment below the expected sequence number is considered out of
+/// order instead of retransmitted, when no RTT estimate is available yet.
+const OUT_OF_ORDER_THRESHOLD: Duration = Duration::from_millis(3);
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum TcpEvent {
+    Retransmission,
+    FastRetransmission,
+    OutOfOrder,
+    LostSegment,
+    DuplicateAck,
+    ZeroWindow,
+    WindowFull,
+    KeepAlive,
+    RstAfterData,
+}
+
+impl TcpEvent {
+    pub fn expert_info(&self) -> ExpertInfo {
+        let (severity, summary) = match self {
+            Self::Retransmission => (Severity::Note, "Retransmission"),
+            Self::FastRetransmission => (Severity::Note, "Fast retransmission"),
+            Self::OutOfOrder => (Severity::Warning, "Out-of-order segment"),
+            Self::LostSegment => (Severity::Warning, "Previous segment not captured"),
+            Self::DuplicateAck => (Severity::Note, "Duplicate ACK"),
+            Self::ZeroWindow => (Severity::Warning, "Zero window"),
+            Self::WindowFull => (Severity::Warning, "Window full"),
+            Self::KeepAlive => (Severity::Note, "Keep-alive"),
+            Self::RstAfterData => (Severity::Warning, "Connection reset after data transfer"),
+        };
+        ExpertInfo::new(severity, Group::Sequence, "TCP", summary)
+    }
+}
+
+impl fmt::Display for TcpEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expert_info().summary())
+    }
+}
+
+/// Result of analyzing a single segment.
+pub struct TcpAnalysis {
+    events: Vec<TcpEvent>,
+    ack_rtt: Option<Duration>,
+    window: Option<u32>,
+}
+
+impl TcpAnalysis {
+    pub fn events(&self) -> &[TcpEvent] {
+        &self.events
+    }
+
+    /// Time elapsed since the data segment acknowledged by this one was seen.
+    pub fn ack_rtt(&self) -> Option<Duration> {
+        self.ack_rtt
+    }
+
+    /// Advertised window after applying the window scale negotiated in the handshake,
+    /// or `None` if the handshake was not captured.
+    pub fn window(&self) -> Option<u32> {
+        self.window
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct RttStats {
+    samples: u32,
+    min: Duration,
+    max: Duration,
+    total: Duration,
+}
+
+impl RttStats {
+    fn add(&mut self, rtt: Duration) {
+        if self.samples == 0 || rtt < self.min {
+            self.min = rtt;
+        }
+        if rtt > self.max {
+            self.max = rtt;
+        }
+        self.total += rtt;
+        self.samples += 1;
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.avg().map(|_| self.min)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.avg().map(|_| self.max)
+    }
+
+    pub fn avg(&self) -> Option<Duration> {
+        if self.samples == 0 {
+            None
+        } else {
+            Some(self.total / self.samples)
+        }
+    }
+}
+
+#[derive(Default)]
+struct Direction {
+    isn: Option<u32>,
+    next_seq: Option<u32>,
+    last_ack: Option<u32>,
+    last_window: u16,
+    window_scale: Option<u8>,
+    syn_seen: bool,
+    syn_time: Option<Duration>,
+    last_seg_time: Option<Duration>,
+    dup_acks: u32,
+    sent_data: bool,
+    // End sequence number and capture time of the data segments not acknowledged yet
+    unacked: VecDeque<(u32, Duration)>,
+    packets: u64,
+    bytes: u64,
+}
+
+/// Both directions of a TCP connection. `addrs().0` is the lowest of the two socket
+/// addresses, not necessarily the one that opened the connection.
+pub struct Connection {
+    addrs: (SocketAddr, SocketAddr),
+    dirs: [Direction; 2],
+    initial_rtt: Option<Duration>,
+    rtt: RttStats,
+    events: BTreeMap<TcpEvent, u64>,
+}
+
+impl Connection {
+    fn new(addrs: (SocketAddr, SocketAddr)) -> Self {
+        Self {
+            addrs,
+            dirs: Default::default(),
+            initial_rtt: None,
+            rtt: RttStats::default(),
+            events: BTreeMap::new(),
+        }
+    }
+
+    pub fn addrs(&self) -> (SocketAddr, SocketAddr) {
+        self.addrs
+    }
+
+    /// Packets sent from `addrs().0` and from `addrs().1`.
+    pub fn packets(&self) -> (u64, u64) {
+        (self.dirs[0].packets, self.dirs[1].packets)
+    }
+
+    /// Payload bytes sent from `addrs().0` and from `addrs().1`.
+    pub fn bytes(&self) -> (u64, u64) {
+        (self.dirs[0].bytes, self.dirs[1].bytes)
+    }
+
+    /// Time between the SYN and the SYN-ACK.
+    pub fn initial_rtt(&self) -> Option<Duration> {
+        self.initial_rtt
+    }
+
+    /// RTT samples taken from data segments and the ACKs covering them.
+    pub fn rtt(&self) -> RttStats {
+        self.rtt
+    }
+
+    pub fn event_count(&self, event: TcpEvent) -> u64 {
+        self.events.get(&event).copied().unwrap_or(0)
+    }
+
+    /// Window scale shift applied to the windows advertised by `dir`, known only when
+    /// both SYNs were captured.
+    fn window_scale(&self, dir: usize) -> Option<u8> {
+        let (ours, theirs) = (&self.dirs[dir], &self.dirs[1 - dir]);
+        if !(ours.syn_seen && theirs.syn_seen) {
+            return None;
+        }
+        match (ours.window_scale, theirs.window_scale) {
+            (Some(shift), Some(_)) => Some(shift.min(14)),
+            _ => Some(0),
+        }
+    }
+}
+
+/// Tracks the state of both directions of every TCP connection to flag segments
+/// that only make sense in the context of the previous ones.
+#[derive(Default)]
+pub struct TcpTracker {
+    connections: HashMap<(SocketAddr, SocketAddr), Connection>,
+    // Connections whose address pair was reused by a later one
+    finished: Vec<Connection>,
+}
+
+/// `a < b` in sequence number space, accounting for wraparound.
+fn seq_lt(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) < 0
+}
+
+impl TcpTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyzes a segment from `src` to `dest` captured at `ts`, measured from any fixed
+    /// point in time.
+    pub fn analyze(
+        &mut self,
+        src: SocketAddr,
+        dest: SocketAddr,
+        frame: &TcpFrame,
+        ts: Duration,
+    ) -> TcpAnalysis {
+        let (key, s, r) = if src <= dest {
+            ((src, dest), 0, 1)
+        } else {
+            ((dest, src), 1, 0)
+        };
+        let conn = self
+            .connections
+            .entry(key)
+            .or_insert_with(|| Connection::new(key));
+
+        let seq = frame.seq_num();
+        let ack = frame.ack_num();
+        let window = frame.window_sz();
+        let payload_len = frame.payload().len() as u32;
+        let seg_len = payload_len + u32::from(frame.syn()) + u32::from(frame.fin());
+        let control = frame.syn() || frame.fin() || frame.rst();
+        let mut events = Vec::new();
+
+        if frame.syn() {
+            // A new SYN on a known address pair means the ports have been reused
+            if !frame.ack() && conn.dirs[s].next_seq != Some(seq.wrapping_add(1)) {
+                let previous = mem::replace(conn, Connection::new(key));
+                if previous.packets() != (0, 0) {
+                    self.finished.push(previous);
+                }
+                conn.dirs[s].syn_time = Some(ts);
+            }
+            if let (true, Some(syn_time)) = (frame.ack(), conn.dirs[r].syn_time) {
+                if conn.initial_rtt.is_none()
+                    && ack == conn.dirs[r].isn.map(|isn| isn.wrapping_add(1))
+                {
+                    conn.initial_rtt = Some(ts.checked_sub(syn_time).unwrap_or_default());
+                }
+            }
+            let sender = &mut conn.dirs[s];
+            sender.isn = Some(seq);
+            sender.syn_seen = true;
+            sender.window_scale = frame.options().find_map(|opt| match opt {
+                TcpOption::WindowScale(shift) => Some(shift),
+                _ => None,
+            });
+        }
+
+        if frame.rst() && (conn.dirs[s].sent_data || conn.dirs[r].sent_data) {
+            events.push(TcpEvent::RstAfterData);
+        }
+
+        let mut keep_alive = false;
+        if let Some(next_seq) = conn.dirs[s].next_seq {
+            let sender = &conn.dirs[s];
+            let receiver = &conn.dirs[r];
+            if seg_len <= 1 && !control && seq == next_seq.wrapping_sub(1) {
+                keep_alive = true;
+                events.push(TcpEvent::KeepAlive);
+            } else if seg_len > 0 && seq_lt(next_seq, seq) {
+                events.push(TcpEvent::LostSegment);
+            } else if seg_len > 0 && seq_lt(seq, next_seq) {
+                let threshold = conn.rtt.avg().unwrap_or(OUT_OF_ORDER_THRESHOLD);
+                if receiver.dup_acks >= 2 && receiver.last_ack == Some(seq) {
+                    events.push(TcpEvent::FastRetransmission);
+                } else if sender
+                    .last_seg_time
+                    .and_then(|last| ts.checked_sub(last))
+                    .is_some_and(|elapsed| elapsed < threshold)
+                {
+                    events.push(TcpEvent::OutOfOrder);
+                } else {
+                    events.push(TcpEvent::Retransmission);
+                }
+            }
+        }
+        let resent = events.iter().any(|event| {
+            matches!(
+                event,
+                TcpEvent::Retransmission | TcpEvent::FastRetransmission | TcpEvent::OutOfOrder
+            )
+        });
+
+        if payload_len > 0 && !control {
+            let receiver = &conn.dirs[r];
+            if let (Some(last_ack), Some(shift)) = (receiver.last_ack, conn.window_scale(r)) {
+                let limit = last_ack.wrapping_add(u32::from(receiver.last_window) << shift);
+                if seq.wrapping_add(payload_len) == limit {
+                    events.push(TcpEvent::WindowFull);
+                }
+            }
+        }
+        if window == 0 && !control {
+            events.push(TcpEvent::ZeroWindow);
+        }
+
+        let sender = &mut conn.dirs[s];
+        if seg_len == 0 && !control && !keep_alive && sender.next_seq.is_some() {
+            if ack.is_some() && ack == sender.last_ack && window == sender.last_window {
+                sender.dup_acks += 1;
+                events.push(TcpEvent::DuplicateAck);
+            } else {
+                sender.dup_acks = 0;
+            }
+        }
+
+        // Match the acknowledgment against the oldest unacknowledged data of the peer
+        let mut ack_rtt = None;
+        if let Some(ack) = ack {
+            let unacked = &mut conn.dirs[r].unacked;
+            while let Some(&(end, sent)) = unacked.front() {
+                if seq_lt(ack, end) {
+                    break;
+                }
+                unacked.pop_front();
+                ack_rtt = ts.checked_sub(sent);
+            }
+        }
+        if let Some(rtt) = ack_rtt {
+            conn.rtt.add(rtt);
+        }
+
+        let window_scale = conn.window_scale(s);
+        let sender = &mut conn.dirs[s];
+        let end = seq.wrapping_add(seg_len);
+        if resent {
+            // Karn's algorithm: ambiguous samples from retransmitted data are discarded
+            sender
+                .unacked
+                .retain(|&(unacked_end, _)| !seq_lt(seq, unacked_end));
+        } else if seg_len > 0 && !keep_alive {
+            sender.unacked.push_back((end, ts));
+        }
+        sender.next_seq = match sender.next_seq {
+            Some(next_seq) if seq_lt(end, next_seq) => Some(next_seq),
+            _ => Some(end),
+        };
+        sender.last_ack = ack;
+        sender.last_window = window;
+        if seg_len > 0 {
+            sender.last_seg_time = Some(ts);
+        }
+        sender.sent_data |= payload_len > 0;
+        sender.packets += 1;
+        sender.bytes += u64::from(payload_len);
+
+        for event in &events {
+            *conn.events.entry(*event).or_insert(0) += 1;
+        }
+
+        TcpAnalysis {
+            events,
+            ack_rtt,
+            // Windows in SYN segments are never scaled
+            window: if frame.syn() {
+                Some(u32::from(window))
+            } else {
+                window_scale.map(|shift| u32::from(window) << shift)
+            },
+        }
+    }
+
+    /// Connections seen so far, including the ones replaced by a later connection
+    /// on the same address pair.
+    pub fn connections(&self) -> impl Iterator<Item = &Connection> {
+        self.finished.iter().chain(self.connections.values())
+    }
+
+    pub fn len(&self) -> usize {
+        self.finished.len() + self.connections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.finished.is_empty() && self.connections.is_empty()
+    }
+}
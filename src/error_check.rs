@@ -39,3 +39,33 @@ pub fn u16_checksum16(slice: &[u16]) -> u16 {
 pub fn u8_checksum16(slice: &[u8]) -> u16 {
     !u8_slice_to_sum16(slice)
 }
+
+/// Lookup table of the reflected CRC-32C (Castagnoli) polynomial.
+const CRC32C_TABLE: [u32; 256] = crc32c_table();
+
+const fn crc32c_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82F6_3B78
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32C checksum, as used by SCTP (RFC 9260, appendix A).
+pub fn crc32c(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (crc >> 8) ^ CRC32C_TABLE[usize::from((crc as u8) ^ byte)]
+    })
+}
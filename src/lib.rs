@@ -2,6 +2,7 @@ extern crate ux;
 
 use std::convert::AsMut;
 
+pub mod analysis;
 pub mod error_check;
 pub mod netframe;
 
@@ -1,14 +1,19 @@
 #[macro_use]
 extern crate prettytable;
 
+use std::convert::TryFrom;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
 
 use chrono::prelude::*;
-use pcap::{Activated, Capture, Device};
+use pcap::{Activated, Capture, Device, PacketHeader};
 use prettytable::{format, Table};
+use serde_json::{json, Map, Value};
 use structopt::StructOpt;
 
-use oxycap::netframe::{datalink::*, internet::*, transport::*};
+use oxycap::analysis::*;
+use oxycap::netframe::{application::*, datalink::*, internet::*, transport::*, FrameError};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "rpcs")]
@@ -30,7 +35,67 @@ enum Command {
     /// Starts the sniffer loop
     ///
     /// Use `from-dev` to sniff from a network device, and `from-file` to analyze a .pcap file.
-    Sniff(Mode),
+    Sniff {
+        /// Prints every packet as a line of JSON instead of a table, with all the fields
+        /// that are summarized in the table, such as HTTP headers
+        #[structopt(long)]
+        json: bool,
+
+        /// Path of a file of ESP security associations used to decrypt ESP payloads, with the
+        /// SPI, the algorithm (aes-cbc, aes-cbc-hmac-sha1-96, aes-cbc-hmac-sha256-128 or
+        /// aes-gcm) and the key on each line
+        #[structopt(long, parse(from_os_str))]
+        esp_sa: Option<PathBuf>,
+
+        #[structopt(subcommand)]
+        mode: Mode,
+    },
+    /// Prints statistics about a capture once it has been fully read
+    Stats(Report),
+    /// Lists the switches and routers announcing themselves with LLDP or CDP, with the
+    /// port each one was seen on
+    Neighbors(Mode),
+    /// Detects port scans and host sweeps
+    ///
+    /// An alert is printed as soon as a source crosses one of the thresholds within the time window.
+    /// UDP datagrams answering a datagram seen within the window from a well-known port are not
+    /// counted as probes. IPv6 packets are inspected up to their fixed header only, so probes
+    /// behind extension headers are missed.
+    DetectScans {
+        /// Time window in seconds in which the probes from a source are correlated
+        #[structopt(short, long, default_value = "60")]
+        window: u64,
+
+        /// Distinct ports probed on a single host to report a vertical scan
+        #[structopt(long, default_value = "20")]
+        ports: usize,
+
+        /// Distinct hosts probed on a single port, or pinged, to report a horizontal scan or sweep
+        #[structopt(long, default_value = "20")]
+        hosts: usize,
+
+        #[structopt(subcommand)]
+        mode: Mode,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum Report {
+    /// Lists every MAC, IPv4 and IPv6 address seen with the packets and bytes sent and received
+    Endpoints {
+        /// Path of a Wireshark `manuf` file used to resolve MAC vendors instead of the built-in list
+        #[structopt(long, parse(from_os_str))]
+        manuf: Option<PathBuf>,
+
+        #[structopt(subcommand)]
+        mode: Mode,
+    },
+    /// Lists every TCP connection with its retransmissions, lost segments and RTT estimates
+    Tcp(Mode),
+    /// Lists every RTP stream with its packet loss, sequence errors and jitter
+    ///
+    /// Streams are found on the ports negotiated by SIP, or else by their headers.
+    Rtp(Mode),
 }
 
 #[derive(Debug, StructOpt)]
@@ -90,40 +155,22 @@ fn main() {
             table.printstd();
         }
         // Starts the sniffer.
-        Command::Sniff(mode) => {
-            let mut sn_len = -1;
-            // Using a `dyn Activated` type to avoid repeating the `while` loop code
-            let mut cap: Capture<dyn Activated> = match mode {
-                Mode::FromDev {
-                    index,
-                    snaplen,
-                    promisc,
-                    timeout,
-                    buffer_size,
-                } => {
-                    // TODO: Throw error in case pcap fails to find any device
-                    let dev = Device::list().unwrap().remove(index);
-                    sn_len = snaplen;
-
-                    Capture::<dyn Activated>::from(
-                        Capture::from_device(dev)
-                            .unwrap() // TODO: Throw error in case finding device fails
-                            .promisc(promisc) // Set promiscuous mode flag
-                            .snaplen(snaplen) // Set packet max length
-                            .timeout(timeout) // Set max wait time between packets
-                            .buffer_size(buffer_size) // Set max buffer size
-                            .open()
-                            .unwrap(), // TODO: throw error in case opening device fails
-                    )
-                }
-                Mode::FromFile { path } => {
-                    //TODO: Exception with opening file errors
-                    Capture::<dyn Activated>::from(Capture::from_file(path).unwrap())
-                }
+        Command::Sniff { json, esp_sa, mode } => {
+            let esp = match esp_sa {
+                Some(path) => SecurityAssociations::from_file(path).unwrap(), // TODO: throw error in case reading the file fails
+                None => SecurityAssociations::new(),
+            };
+            let (mut cap, sn_len) = open_capture(mode);
+            let mut state = SniffState {
+                json,
+                esp,
+                ..SniffState::default()
             };
             let mut packet_count = 1u32;
             let ltype = cap.get_datalink();
             while let Ok(packet) = cap.next() {
+                state.ts = packet_time(packet.header);
+                state.depth = 0;
                 let current_time = Local
                     .timestamp(
                         packet.header.ts.tv_sec as i64,
@@ -151,19 +198,533 @@ fn main() {
                 if sn_len != -1 {
                     table.add_row(row!["\tCapture length:", sn_len]);
                 }
+                if packet.header.caplen < packet.header.len {
+                    state.add_expert(
+                        &mut table,
+                        ExpertInfo::new(
+                            Severity::Warning,
+                            Group::Malformed,
+                            "Frame",
+                            "Packet snapped by the capture length",
+                        ),
+                    );
+                }
                 match ltype {
-                    pcap::Linktype(1) => handle_ethernet(EthernetFrame::from(packet), table),
+                    pcap::Linktype(1) => {
+                        handle_ethernet(EthernetFrame::from(packet), table, &mut state)
+                    }
                     _ => {
                         table.add_row(row!["\tUnknown linktype:"]);
                     }
                 }
                 packet_count += 1;
             }
+            // Summaries would break the stream of JSON lines
+            if !json {
+                print_expert_summary(&state.expert);
+                if !state.arp.is_empty() {
+                    print_arp_summary(&state.arp);
+                }
+            }
+        }
+        Command::Stats(Report::Endpoints { manuf, mode }) => {
+            let db = match manuf {
+                Some(path) => OuiDatabase::from_file(path).unwrap(), // TODO: throw error in case reading the file fails
+                None => OuiDatabase::embedded(),
+            };
+            let (mut cap, _) = open_capture(mode);
+            let ltype = cap.get_datalink();
+            let mut endpoints = EndpointTable::new();
+            while let Ok(packet) = cap.next() {
+                if ltype == pcap::Linktype(1) {
+                    let len = packet.header.len;
+                    endpoints.record(EthernetFrame::from(packet), len);
+                }
+            }
+            print_endpoints(&endpoints, &db);
+        }
+        Command::Stats(Report::Tcp(mode)) => {
+            let (mut cap, _) = open_capture(mode);
+            let ltype = cap.get_datalink();
+            let mut tracker = TcpTracker::new();
+            while let Ok(packet) = cap.next() {
+                if ltype != pcap::Linktype(1) {
+                    continue;
+                }
+                let ts = packet_time(packet.header);
+                let (src_addr, dest_addr, frame) =
+                    match EthernetFrame::from(packet).try_next_header() {
+                        EtherType::Ipv4(frame) => (
+                            IpAddr::V4(frame.src_addr()),
+                            IpAddr::V4(frame.dest_addr()),
+                            frame.try_next_header(),
+                        ),
+                        EtherType::Ipv6(frame) => (
+                            IpAddr::V6(frame.src_addr()),
+                            IpAddr::V6(frame.dest_addr()),
+                            frame.try_next_header(),
+                        ),
+                        _ => continue,
+                    };
+                if let IpProtocol::Tcp(frame) = frame {
+                    tracker.analyze(
+                        SocketAddr::new(src_addr, frame.src_port()),
+                        SocketAddr::new(dest_addr, frame.dest_port()),
+                        &frame,
+                        ts,
+                    );
+                }
+            }
+            print_tcp_connections(&tracker);
+        }
+        Command::Stats(Report::Rtp(mode)) => {
+            let (mut cap, _) = open_capture(mode);
+            let ltype = cap.get_datalink();
+            let mut tracker = RtpTracker::new();
+            while let Ok(packet) = cap.next() {
+                if ltype != pcap::Linktype(1) {
+                    continue;
+                }
+                let ts = packet_time(packet.header);
+                let (src_addr, dest_addr, frame) =
+                    match EthernetFrame::from(packet).try_next_header() {
+                        EtherType::Ipv4(frame) => (
+                            IpAddr::V4(frame.src_addr()),
+                            IpAddr::V4(frame.dest_addr()),
+                            frame.try_next_header(),
+                        ),
+                        EtherType::Ipv6(frame) => (
+                            IpAddr::V6(frame.src_addr()),
+                            IpAddr::V6(frame.dest_addr()),
+                            frame.try_next_header(),
+                        ),
+                        _ => continue,
+                    };
+                if let IpProtocol::Udp(frame) = frame {
+                    let src = SocketAddr::new(src_addr, frame.src_port());
+                    let dest = SocketAddr::new(dest_addr, frame.dest_port());
+                    let frame = if tracker.is_media(&dest) {
+                        frame.try_media()
+                    } else {
+                        frame.try_next_header()
+                    };
+                    match frame {
+                        AppProtocol::Sip(frame) => {
+                            if let Some(Ok(sdp)) = frame.sdp() {
+                                tracker.learn(&sdp);
+                            }
+                        }
+                        AppProtocol::Rtp(frame) => {
+                            tracker.record(src, dest, &frame, ts);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            print_rtp_streams(&tracker);
+        }
+        Command::Neighbors(mode) => {
+            let (mut cap, _) = open_capture(mode);
+            let ltype = cap.get_datalink();
+            let mut neighbors = NeighborTable::new();
+            while let Ok(packet) = cap.next() {
+                if ltype != pcap::Linktype(1) {
+                    continue;
+                }
+                let ts = packet_time(packet.header);
+                let frame = EthernetFrame::from(packet);
+                let src_addr = frame.src_addr();
+                match frame.try_next_header() {
+                    EtherType::Lldp(frame) => neighbors.record_lldp(src_addr, &frame, ts),
+                    EtherType::IeeeSnap(frame) => {
+                        if let SnapProtocol::Cdp(frame) = frame.try_next_header() {
+                            neighbors.record_cdp(src_addr, &frame, ts);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            print_neighbors(&neighbors);
+        }
+        Command::DetectScans {
+            window,
+            ports,
+            hosts,
+            mode,
+        } => {
+            let (mut cap, _) = open_capture(mode);
+            let ltype = cap.get_datalink();
+            let mut detector = ScanDetector::new(ScanThresholds {
+                window: Duration::from_secs(window),
+                ports,
+                hosts,
+            });
+            let mut alert_count = 0;
+            while let Ok(packet) = cap.next() {
+                if ltype != pcap::Linktype(1) {
+                    continue;
+                }
+                let ts = packet_time(packet.header);
+                let alerts = match EthernetFrame::from(packet).try_next_header() {
+                    EtherType::Ipv4(frame) => detector.inspect(frame, ts),
+                    EtherType::Ipv6(frame) => detector.inspect_ipv6(frame, ts),
+                    _ => continue,
+                };
+                for alert in alerts {
+                    println!("{}  {}", format_time(ts), alert);
+                    alert_count += 1;
+                }
+            }
+            println!("\n{} scan alerts", alert_count);
+        }
+    }
+}
+
+/// State kept across packets while sniffing.
+#[derive(Default)]
+struct SniffState {
+    // Capture time of the packet being dissected
+    ts: Duration,
+    // Tunnels decapsulated so far in the packet being dissected
+    depth: usize,
+    expert: ExpertSummary,
+    tcp: TcpTracker,
+    rtp: RtpTracker,
+    arp: ArpMonitor,
+    // Keys to decrypt ESP payloads
+    esp: SecurityAssociations,
+    json: bool,
+}
+
+impl SniffState {
+    /// Shows `info` inline in the packet table and counts it for the final summary.
+    fn add_expert(&mut self, table: &mut Table, info: ExpertInfo) {
+        table.add_row(row!["\tExpert info:", info]);
+        self.expert.record(info);
+    }
+
+    /// Prints the table of a fully dissected packet in the selected output format.
+    fn print(&self, table: &Table) {
+        if self.json {
+            println!("{}", table_to_json(table));
+        } else {
+            table.printstd();
+        }
+    }
+}
+
+/// Adds `value` as the member `name` of `fields`, turning it into an array when a
+/// row with the same name was already added.
+fn add_json_field(fields: &mut Map<String, Value>, name: &str, value: Value) {
+    match fields.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(previous) => *previous = Value::Array(vec![previous.take(), value]),
+        None => {
+            fields.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// Converts the table of a dissected packet into a JSON array with an object for each
+/// header. Rows with a name and a value become members of the header object, and the
+/// rows nested under a name without a value are collected into an array.
+fn table_to_json(table: &Table) -> Value {
+    let mut headers = Vec::new();
+    let mut fields = Map::new();
+    let mut title = None;
+    // Name of the array collecting the nested rows
+    let mut list: Option<String> = None;
+    for row in table.row_iter() {
+        if let Some(cell) = row.get_cell(0).filter(|cell| cell.get_hspan() > 1) {
+            if let Some(title) = title.replace(cell.get_content()) {
+                headers.push(json!({ "header": title, "fields": fields }));
+            }
+            fields = Map::new();
+            list = None;
+            continue;
+        }
+        let cells: Vec<_> = row
+            .iter()
+            .map(|cell| cell.get_content())
+            .filter(|content| !content.trim().is_empty())
+            .collect();
+        match cells.as_slice() {
+            [] => {}
+            [name, value, ..] => {
+                list = None;
+                let name = name.trim().trim_end_matches(':');
+                add_json_field(&mut fields, name, Value::from(value.trim()));
+            }
+            [line] => {
+                let text = line.trim();
+                match &list {
+                    Some(name) if line.starts_with("\t\t") => {
+                        add_json_field(&mut fields, name, Value::from(text))
+                    }
+                    _ if text.ends_with(':') => {
+                        let name = text.trim_end_matches(':');
+                        fields.insert(name.to_string(), Value::Array(Vec::new()));
+                        list = Some(name.to_string());
+                    }
+                    _ => add_json_field(&mut fields, "Info", Value::from(text)),
+                }
+            }
+        }
+    }
+    if let Some(title) = title {
+        headers.push(json!({ "header": title, "fields": fields }));
+    }
+    Value::Array(headers)
+}
+
+/// Capture time of a packet as the time elapsed since the Unix epoch.
+fn packet_time(header: &PacketHeader) -> Duration {
+    Duration::new(header.ts.tv_sec as u64, header.ts.tv_usec as u32 * 1000)
+}
+
+fn format_time(ts: Duration) -> String {
+    DateTime::<Local>::from(UNIX_EPOCH + ts)
+        .format("%+")
+        .to_string()
+}
+
+/// Opens the capture described by `mode`, returning it along with the snapshot length
+/// requested by the user (-1 when reading from a file).
+fn open_capture(mode: Mode) -> (Capture<dyn Activated>, i32) {
+    match mode {
+        Mode::FromDev {
+            index,
+            snaplen,
+            promisc,
+            timeout,
+            buffer_size,
+        } => {
+            // TODO: Throw error in case pcap fails to find any device
+            let dev = Device::list().unwrap().remove(index);
+
+            let cap = Capture::<dyn Activated>::from(
+                Capture::from_device(dev)
+                    .unwrap() // TODO: Throw error in case finding device fails
+                    .promisc(promisc) // Set promiscuous mode flag
+                    .snaplen(snaplen) // Set packet max length
+                    .timeout(timeout) // Set max wait time between packets
+                    .buffer_size(buffer_size) // Set max buffer size
+                    .open()
+                    .unwrap(), // TODO: throw error in case opening device fails
+            );
+            (cap, snaplen)
         }
+        Mode::FromFile { path } => {
+            //TODO: Exception with opening file errors
+            (
+                Capture::<dyn Activated>::from(Capture::from_file(path).unwrap()),
+                -1,
+            )
+        }
+    }
+}
+
+fn print_endpoints(endpoints: &EndpointTable, db: &OuiDatabase) {
+    println!("Endpoints ({}):\n", endpoints.len());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "Address", "Type", "Packets", "Bytes", "Tx packets", "Tx bytes", "Rx packets", "Rx bytes"]);
+    for (endpoint, traffic) in endpoints.sorted() {
+        let (address, kind) = match endpoint {
+            Endpoint::Mac(addr) => {
+                let kind = if addr.is_broadcast() {
+                    "Ethernet (broadcast)"
+                } else if addr.is_multicast() {
+                    "Ethernet (multicast)"
+                } else if addr.is_locally_administered() {
+                    "Ethernet (local)"
+                } else {
+                    "Ethernet"
+                };
+                (addr.resolve(db), kind)
+            }
+            Endpoint::Ipv4(addr) => (addr.to_string(), "IPv4"),
+            Endpoint::Ipv6(addr) => (addr.to_string(), "IPv6"),
+        };
+        table.add_row(row![
+            address,
+            kind,
+            r -> traffic.packets(),
+            r -> traffic.bytes(),
+            r -> traffic.tx_packets(),
+            r -> traffic.tx_bytes(),
+            r -> traffic.rx_packets(),
+            r -> traffic.rx_bytes()
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_tcp_connections(tracker: &TcpTracker) {
+    let format_rtt = |rtt: Option<Duration>| match rtt {
+        Some(rtt) => format!("{:.3} ms", rtt.as_secs_f64() * 1000.0),
+        None => String::from("-"),
+    };
+    println!("TCP connections ({}):\n", tracker.len());
+    let mut connections: Vec<_> = tracker.connections().collect();
+    connections.sort_by_key(|conn| conn.addrs());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "Address A", "Address B", "Packets A→B", "Bytes A→B", "Packets B→A", "Bytes B→A",
+        "Retrans.", "Fast retrans.", "Out-of-order", "Lost", "Dup ACK", "Zero win.", "iRTT", "RTT min", "RTT avg", "RTT max"]);
+    for conn in connections {
+        let (addr_a, addr_b) = conn.addrs();
+        let (packets_a, packets_b) = conn.packets();
+        let (bytes_a, bytes_b) = conn.bytes();
+        let rtt = conn.rtt();
+        table.add_row(row![
+            addr_a,
+            addr_b,
+            r -> packets_a,
+            r -> bytes_a,
+            r -> packets_b,
+            r -> bytes_b,
+            r -> conn.event_count(TcpEvent::Retransmission),
+            r -> conn.event_count(TcpEvent::FastRetransmission),
+            r -> conn.event_count(TcpEvent::OutOfOrder),
+            r -> conn.event_count(TcpEvent::LostSegment),
+            r -> conn.event_count(TcpEvent::DuplicateAck),
+            r -> conn.event_count(TcpEvent::ZeroWindow),
+            r -> format_rtt(conn.initial_rtt()),
+            r -> format_rtt(rtt.min()),
+            r -> format_rtt(rtt.avg()),
+            r -> format_rtt(rtt.max())
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_rtp_streams(tracker: &RtpTracker) {
+    let format_jitter = |jitter: Option<Duration>| match jitter {
+        Some(jitter) => format!("{:.3} ms", jitter.as_secs_f64() * 1000.0),
+        None => String::from("-"),
+    };
+    println!("RTP streams ({}):\n", tracker.len());
+    let mut streams: Vec<_> = tracker.streams().collect();
+    streams.sort_by_key(|stream| (stream.src(), stream.dest(), stream.ssrc()));
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(
+        row![b => "Source", "Destination", "SSRC", "Payload", "Packets", "Expected", "Lost",
+        "Seq. errors", "Jitter", "Max jitter"],
+    );
+    for stream in streams {
+        let payload = match rtp_static_payload(stream.payload_type()) {
+            Some((encoding, _)) => format!("{} ({})", stream.payload_type(), encoding),
+            None => stream.payload_type().to_string(),
+        };
+        table.add_row(row![
+            stream.src(),
+            stream.dest(),
+            format!("0x{:08X}", stream.ssrc()),
+            payload,
+            r -> stream.packets(),
+            r -> stream.expected(),
+            r -> format!(
+                "{} ({:.1}%)",
+                stream.lost(),
+                stream.lost() as f64 * 100.0 / stream.expected() as f64
+            ),
+            r -> stream.seq_errors(),
+            r -> format_jitter(stream.jitter()),
+            r -> format_jitter(stream.max_jitter())
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_neighbors(neighbors: &NeighborTable) {
+    let format_addresses = |addresses: &[IpAddr]| {
+        let addresses: Vec<_> = addresses.iter().map(IpAddr::to_string).collect();
+        addresses.join(", ")
+    };
+    println!("Neighbors ({}):\n", neighbors.len());
+    let mut sorted: Vec<_> = neighbors.neighbors().collect();
+    sorted.sort_by(|a, b| {
+        (a.name(), a.chassis_id(), a.port_id()).cmp(&(b.name(), b.chassis_id(), b.port_id()))
+    });
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "Protocol", "Name", "Chassis ID", "Port", "Port description", "VLAN",
+        "Platform", "Capabilities", "Addresses", "Source MAC", "Packets", "Last seen"]);
+    for neighbor in sorted {
+        table.add_row(row![
+            neighbor.protocol(),
+            neighbor.name().unwrap_or("-"),
+            neighbor.chassis_id(),
+            neighbor.port_id(),
+            neighbor.port_description().unwrap_or("-"),
+            r -> neighbor.vlan().map_or_else(|| String::from("-"), |vlan| vlan.to_string()),
+            neighbor.platform().unwrap_or("-"),
+            neighbor.capabilities().join(", "),
+            format_addresses(neighbor.addresses()),
+            neighbor.mac(),
+            r -> neighbor.packets(),
+            format_time(neighbor.last_seen())
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_arp_summary(monitor: &ArpMonitor) {
+    println!("\nARP table:\n");
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "IP address", "MAC address", "Previous MACs", "First seen", "Last seen"]);
+    for (ip, binding) in monitor.bindings() {
+        let previous: Vec<_> = binding.previous().map(HwAddr::to_string).collect();
+        table.add_row(row![
+            ip,
+            binding.mac(),
+            previous.join(", "),
+            format_time(binding.first_seen()),
+            format_time(binding.last_seen())
+        ]);
+    }
+    table.printstd();
+
+    println!("\nARP alerts ({}):\n", monitor.alerts().len());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "Time", "Severity", "Alert"]);
+    for (ts, alert) in monitor.alerts() {
+        table.add_row(row![format_time(*ts), alert.expert_info().severity(), alert]);
+    }
+    table.printstd();
+}
+
+fn print_expert_summary(expert: &ExpertSummary) {
+    println!(
+        "Expert info summary: {} errors, {} warnings, {} notes, {} chats\n",
+        expert.count(Severity::Error),
+        expert.count(Severity::Warning),
+        expert.count(Severity::Note),
+        expert.count(Severity::Chat)
+    );
+    if expert.is_empty() {
+        return;
+    }
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row![b => "Severity", "Group", "Protocol", "Summary", "Count"]);
+    for (info, count) in expert.entries() {
+        table.add_row(row![
+            info.severity(),
+            info.group(),
+            info.protocol(),
+            info.summary(),
+            r -> count
+        ]);
     }
+    table.printstd();
 }
 
-fn handle_ethernet(frame: EthernetFrame, mut table: Table) {
+fn handle_ethernet(frame: EthernetFrame, mut table: Table, state: &mut SniffState) {
     let src_addr = frame.src_addr();
     let dest_addr = frame.dest_addr();
     table.add_row(row![H2 -> format!("Ethernet II, Src: {}, Dest: {}", src_addr, dest_addr)]);
@@ -177,20 +738,25 @@ fn handle_ethernet(frame: EthernetFrame, mut table: Table) {
     }
 
     match frame {
-        EtherType::Ipv4(frame) => handle_ipv4(frame, table),
-        EtherType::Ipv6(frame) => handle_ipv6(frame, table),
-        EtherType::Arp(frame) => handle_arp(frame, table),
-        EtherType::IeeeLlc(frame) => handle_ieee_llc(frame, table),
+        EtherType::Ipv4(frame) => handle_ipv4(frame, table, state),
+        EtherType::Ipv6(frame) => handle_ipv6(frame, table, state),
+        EtherType::Arp(frame) => handle_arp(frame, table, state),
+        EtherType::IeeeLlc(frame) => handle_ieee_llc(frame, table, state),
+        EtherType::Mpls(frame) => handle_mpls(frame, table, state),
+        EtherType::Pppoe(frame) => handle_pppoe(frame, table, state),
+        EtherType::Lldp(frame) => handle_lldp(frame, table, state),
+        EtherType::IeeeSnap(frame) => handle_snap(frame, table, state),
+        EtherType::Malformed(name, err) => handle_malformed(name, err, table, state),
         _ => {
             table.add_empty_row();
             table.add_empty_row();
             table.add_empty_row();
-            table.printstd();
+            state.print(&table);
         }
     }
 }
 
-fn handle_ieee_llc(frame: IeeeLlcFrame, mut table: Table) {
+fn handle_ieee_llc(frame: IeeeLlcFrame, mut table: Table, state: &mut SniffState) {
     table.add_row(row!["\tDSAP:", frame.dsap()]);
     table.add_row(row!["\tSSAP:", frame.ssap()]);
     table.add_row(row![
@@ -270,10 +836,66 @@ fn handle_ieee_llc(frame: IeeeLlcFrame, mut table: Table) {
     table.add_empty_row();
     table.add_empty_row();
     table.add_empty_row();
-    table.printstd();
+    state.print(&table);
+}
+
+fn handle_snap(frame: SnapFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Subnetwork Access Protocol"]);
+    table.add_row(row!["\tOrganization code:", to_hex(&frame.oui())]);
+    table.add_row(row!["\tProtocol ID:", format!("0x{:04X}", frame.pid())]);
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tProtocol:", frame]);
+    match frame {
+        SnapProtocol::Cdp(frame) => handle_cdp(frame, table, state),
+        SnapProtocol::Malformed(name, err) => handle_malformed(name, err, table, state),
+        SnapProtocol::Other(_) => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            state.print(&table);
+        }
+    }
+}
+
+fn handle_cdp(frame: CdpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Cisco Discovery Protocol"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    table.add_row(row!["\tTime to live:", format!("{} s", frame.ttl())]);
+    table.add_row(row!["\tChecksum:", format!("0x{:04X}", frame.checksum())]);
+    table.add_row(row!["\tTLVs:"]);
+    for tlv in frame.tlvs() {
+        table.add_row(row![format!("\t\t{}", tlv)]);
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_lldp(frame: LldpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Link Layer Discovery Protocol"]);
+    table.add_row(row!["\tTLVs:"]);
+    for tlv in frame.tlvs() {
+        table.add_row(row![format!("\t\t{}", tlv)]);
+    }
+    if frame.ttl() == 0 {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Note,
+                Group::Protocol,
+                "LLDP",
+                "Neighbor shutting down",
+            ),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
 }
 
-fn handle_arp(frame: ArpFrame, mut table: Table) {
+fn handle_arp(frame: ArpFrame, mut table: Table, state: &mut SniffState) {
     let to_hex = |mut acc: String, val: &u8| {
         acc.push_str(format!("{:02X}", val).as_str());
         acc
@@ -293,13 +915,164 @@ fn handle_arp(frame: ArpFrame, mut table: Table) {
     table.add_row(row!["\tSPA:", spa]);
     table.add_row(row!["\tTHA:", tha]);
     table.add_row(row!["\tTPA:", tpa]);
+    let alerts: Vec<_> = state
+        .arp
+        .inspect(&frame, state.ts)
+        .into_iter()
+        .map(|alert| (alert.to_string(), alert.expert_info()))
+        .collect();
+    for (alert, info) in alerts {
+        table.add_row(row!["\tAlert:", alert]);
+        state.add_expert(&mut table, info);
+    }
     table.add_empty_row();
     table.add_empty_row();
     table.add_empty_row();
-    table.printstd();
+    state.print(&table);
+}
+
+fn handle_mpls(frame: MplsFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "MultiProtocol Label Switching"]);
+    table.add_row(row!["\tLabel stack:"]);
+    for label in frame.labels() {
+        table.add_row(row![format!("\t\t{}", label)]);
+    }
+    if let Some(control_word) = frame.control_word() {
+        table.add_row(row!["\tControl word:", format!("0x{:08X}", control_word)]);
+    }
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tEncapsulated:", frame]);
+    handle_encapsulated(frame, table, state);
+}
+
+fn handle_pppoe(frame: PppoeFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "PPP-over-Ethernet"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    table.add_row(row!["\tType:", frame.typ()]);
+    table.add_row(row!["\tCode:", frame.code()]);
+    table.add_row(row![
+        "\tSession ID:",
+        format!("0x{:04X}", frame.session_id())
+    ]);
+    table.add_row(row!["\tPayload length:", frame.len()]);
+    if !frame.is_discovery() {
+        match frame.ppp() {
+            Ok(frame) => handle_ppp(frame, table, state),
+            Err(err) => handle_malformed("PPP", err, table, state),
+        }
+        return;
+    }
+    match frame.tags() {
+        Ok(tags) => {
+            table.add_row(row!["\tTags:"]);
+            for tag in tags {
+                table.add_row(row![format!("\t\t{}", tag)]);
+                if tag.is_error() {
+                    state.add_expert(
+                        &mut table,
+                        ExpertInfo::new(Severity::Warning, Group::Protocol, "PPPoE", "Error tag"),
+                    );
+                }
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(Severity::Error, Group::Malformed, "PPPoE", "Malformed tag"),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_ppp(frame: PppFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Point-to-Point Protocol"]);
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tProtocol:", frame]);
+    let (protocol_name, packet) = match frame {
+        PppPayload::Ipv4(frame) => return handle_ipv4(frame, table, state),
+        PppPayload::Ipv6(frame) => return handle_ipv6(frame, table, state),
+        PppPayload::Malformed(name, err) => return handle_malformed(name, err, table, state),
+        PppPayload::Lcp(packet) => ("Link Control Protocol", packet),
+        PppPayload::Ipcp(packet) => ("Internet Protocol Control Protocol", packet),
+        PppPayload::Ipv6cp(packet) => ("IPv6 Control Protocol", packet),
+        PppPayload::Pap(packet) => ("Password Authentication Protocol", packet),
+        PppPayload::Chap(packet) => ("Challenge Handshake Authentication Protocol", packet),
+        PppPayload::Other(_) => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            return state.print(&table);
+        }
+    };
+    table.add_row(row![H2 -> protocol_name]);
+    let code = match packet.code_name() {
+        Some(code_name) => format!("{} ({})", code_name, packet.code()),
+        None => packet.code().to_string(),
+    };
+    table.add_row(row!["\tCode:", code]);
+    table.add_row(row!["\tIdentifier:", packet.identifier()]);
+    if let Err(err) = add_ppp_packet(&mut table, &packet) {
+        table.add_row(row!["\tError:", err]);
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Error, Group::Malformed, "PPP", "Malformed packet"),
+        );
+    }
+    if packet.has_cleartext_password() {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Warning,
+                Group::Security,
+                "PAP",
+                "Cleartext password",
+            ),
+        );
+    }
+    if packet.is_auth_failure() {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Warning,
+                Group::Security,
+                "PPP",
+                "Authentication failed",
+            ),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
 }
 
-fn handle_ipv4(frame: Ipv4Frame, mut table: Table) {
+fn add_ppp_packet(table: &mut Table, packet: &PppPacket) -> Result<(), FrameError> {
+    if let Some(name) = packet.name()? {
+        table.add_row(row!["\tName:", String::from_utf8_lossy(name)]);
+    }
+    if let Some(message) = packet.message()? {
+        table.add_row(row!["\tMessage:", String::from_utf8_lossy(message)]);
+    }
+    let options = packet.options()?;
+    if !options.is_empty() {
+        table.add_row(row!["\tOptions:"]);
+    }
+    for option in options {
+        let name = match packet.option_name(option.typ) {
+            Some(name) => name.to_string(),
+            None => format!("Unknown ({})", option.typ),
+        };
+        table.add_row(row![format!("\t\t{}: {}", name, to_hex(option.data))]);
+    }
+    Ok(())
+}
+
+fn handle_ipv4(frame: Ipv4Frame, mut table: Table, state: &mut SniffState) {
     let src_addr = frame.src_addr();
     let dest_addr = frame.dest_addr();
     table.add_row(row![H2 -> format!("Internet Protocol, Src: {}, Dest: {}", src_addr, dest_addr)]);
@@ -340,6 +1113,42 @@ fn handle_ipv4(frame: Ipv4Frame, mut table: Table) {
     ]);
     table.add_row(row!["\tSource address:", src_addr]);
     table.add_row(row!["\tDestination address:", dest_addr]);
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("IPv4"));
+    }
+    let total_len = usize::from(frame.total_len());
+    if total_len < usize::from(frame.header_len()) {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Error,
+                Group::Malformed,
+                "IPv4",
+                "Total length smaller than the header length",
+            ),
+        );
+    } else if total_len > frame.captured_len() {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Warning,
+                Group::Malformed,
+                "IPv4",
+                "Total length exceeds the captured length",
+            ),
+        );
+    }
+    match frame.ttl() {
+        0 => state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Error, Group::Protocol, "IPv4", "Time to live is zero"),
+        ),
+        1 if !dest_addr.is_multicast() => state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Note, Group::Protocol, "IPv4", "Time to live is one"),
+        ),
+        _ => {}
+    }
     //TODO: implement options on ipv4
     if let Some(opts) = frame.opts() {
         table.add_row(row!["\tOptions:"]);
@@ -349,8 +1158,48 @@ fn handle_ipv4(frame: Ipv4Frame, mut table: Table) {
     table.add_row(row!["\tProtocol:", frame]);
 
     match frame {
-        IpProtocol::Tcp(frame) => handle_tcp(frame, table),
-        IpProtocol::Udp(frame) => handle_udp(frame, table),
+        IpProtocol::Tcp(frame) => handle_tcp(
+            frame,
+            table,
+            state,
+            IpAddr::V4(src_addr),
+            IpAddr::V4(dest_addr),
+        ),
+        IpProtocol::Udp(frame) => handle_udp(
+            frame,
+            table,
+            state,
+            IpAddr::V4(src_addr),
+            IpAddr::V4(dest_addr),
+        ),
+        IpProtocol::Sctp(frame) => handle_sctp(
+            frame,
+            table,
+            state,
+            IpAddr::V4(src_addr),
+            IpAddr::V4(dest_addr),
+        ),
+        IpProtocol::Gre(frame) => handle_gre(frame, table, state),
+        IpProtocol::Ospf(frame) => handle_ospf(frame, table, state),
+        IpProtocol::Pim(frame) => handle_pim(frame, table, state),
+        IpProtocol::Vrrp(frame) => handle_vrrp(frame, table, state),
+        IpProtocol::Esp(frame) => handle_esp(
+            frame,
+            table,
+            state,
+            IpAddr::V4(src_addr),
+            IpAddr::V4(dest_addr),
+        ),
+        IpProtocol::Ah(frame) => handle_ah(
+            frame,
+            table,
+            state,
+            IpAddr::V4(src_addr),
+            IpAddr::V4(dest_addr),
+        ),
+        IpProtocol::Ipv4(frame) => handle_encapsulated(Encapsulated::Ipv4(frame), table, state),
+        IpProtocol::Ipv6(frame) => handle_encapsulated(Encapsulated::Ipv6(frame), table, state),
+        IpProtocol::Malformed(name, err) => handle_malformed(name, err, table, state),
         IpProtocol::Icmp(frame) => {
             let msg = frame.get_control_msg();
             table.add_row(row![H2 -> "Internet Control Management Protocol"]);
@@ -406,10 +1255,13 @@ fn handle_ipv4(frame: Ipv4Frame, mut table: Table) {
                     }
                 )
             ]);
+            if !frame.has_integrity() {
+                state.add_expert(&mut table, ExpertInfo::bad_checksum("ICMP"));
+            }
             table.add_empty_row();
             table.add_empty_row();
             table.add_empty_row();
-            table.printstd();
+            state.print(&table);
         }
         IpProtocol::Igmp(frame) => {
             table.add_row(row![H2 -> "Internet Group Management Protocol"]);
@@ -443,46 +1295,203 @@ fn handle_ipv4(frame: Ipv4Frame, mut table: Table) {
                     }
                 )
             ]);
+            if !frame.has_integrity() {
+                state.add_expert(&mut table, ExpertInfo::bad_checksum("IGMP"));
+            }
             table.add_empty_row();
             table.add_empty_row();
             table.add_empty_row();
-            table.printstd();
+            state.print(&table);
         }
         _ => {
             table.add_empty_row();
             table.add_empty_row();
             table.add_empty_row();
-            table.printstd();
+            state.print(&table);
+        }
+    }
+}
+
+fn handle_ipv6(frame: Ipv6Frame, mut table: Table, state: &mut SniffState) {
+    let src_addr = frame.src_addr();
+    let dest_addr = frame.dest_addr();
+    table.add_row(
+        row![H2 -> format!("Internet Protocol Version 6, Src: {}, Dest: {}", src_addr, dest_addr)],
+    );
+    table.add_row(row![
+        "\tTraffic class:",
+        format!("0x{:02X}", frame.traffic_class())
+    ]);
+    table.add_row(row![
+        "\tFlow label:",
+        format!("0x{:05X}", frame.flow_label())
+    ]);
+    table.add_row(row!["\tPayload length:", frame.payload_len()]);
+    table.add_row(row!["\tHop limit:", frame.hop_limit()]);
+    table.add_row(row!["\tSource address:", src_addr]);
+    table.add_row(row!["\tDestination address:", dest_addr]);
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tNext header:", frame]);
+
+    match frame {
+        IpProtocol::Tcp(frame) => handle_tcp(
+            frame,
+            table,
+            state,
+            IpAddr::V6(src_addr),
+            IpAddr::V6(dest_addr),
+        ),
+        IpProtocol::Udp(frame) => handle_udp(
+            frame,
+            table,
+            state,
+            IpAddr::V6(src_addr),
+            IpAddr::V6(dest_addr),
+        ),
+        IpProtocol::Sctp(frame) => handle_sctp(
+            frame,
+            table,
+            state,
+            IpAddr::V6(src_addr),
+            IpAddr::V6(dest_addr),
+        ),
+        IpProtocol::Gre(frame) => handle_gre(frame, table, state),
+        IpProtocol::Ospf(frame) => handle_ospf(frame, table, state),
+        IpProtocol::Pim(frame) => handle_pim(frame, table, state),
+        IpProtocol::Vrrp(frame) => handle_vrrp(frame, table, state),
+        IpProtocol::Esp(frame) => handle_esp(
+            frame,
+            table,
+            state,
+            IpAddr::V6(src_addr),
+            IpAddr::V6(dest_addr),
+        ),
+        IpProtocol::Ah(frame) => handle_ah(
+            frame,
+            table,
+            state,
+            IpAddr::V6(src_addr),
+            IpAddr::V6(dest_addr),
+        ),
+        IpProtocol::Ipv4(frame) => handle_encapsulated(Encapsulated::Ipv4(frame), table, state),
+        IpProtocol::Ipv6(frame) => handle_encapsulated(Encapsulated::Ipv6(frame), table, state),
+        IpProtocol::Malformed(name, err) => handle_malformed(name, err, table, state),
+        _ => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            state.print(&table);
         }
     }
 }
 
-fn handle_ipv6(frame: Ipv6Frame, mut table: Table) {
-    table.add_row(row!["\tSource address:", frame.src_addr()]);
-    table.add_row(row!["\tDestination address:", frame.dest_addr()]);
+fn handle_malformed(name: &'static str, err: FrameError, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> name]);
+    table.add_row(row!["\tError:", err]);
+    state.add_expert(
+        &mut table,
+        ExpertInfo::new(Severity::Error, Group::Malformed, name, "Malformed packet"),
+    );
     table.add_empty_row();
     table.add_empty_row();
     table.add_empty_row();
-    table.printstd();
+    state.print(&table);
 }
 
-fn handle_tcp(frame: TcpFrame, mut table: Table) {
-    table.add_row(row![H2 -> "Transmission Control Protocol"]);
-    table.add_row(row!["\tSource port:", frame.src_port()]);
-    table.add_row(row!["\tDestination port:", frame.dest_port()]);
+/// Counts one more nested layer of `name` against `MAX_ENCAPSULATION_DEPTH`. Past the
+/// limit, the table is printed with an error and `false` is returned.
+fn enter_nested(name: &'static str, table: &mut Table, state: &mut SniffState) -> bool {
+    if state.depth >= MAX_ENCAPSULATION_DEPTH {
+        state.add_expert(
+            table,
+            ExpertInfo::new(
+                Severity::Error,
+                Group::Malformed,
+                name,
+                "Too many nested layers",
+            ),
+        );
+        table.add_empty_row();
+        table.add_empty_row();
+        table.add_empty_row();
+        state.print(table);
+        return false;
+    }
+    state.depth += 1;
+    true
+}
+
+/// Dissects the frame carried by a tunnel, whose layers follow the outer ones in the
+/// same table.
+fn handle_encapsulated(frame: Encapsulated, mut table: Table, state: &mut SniffState) {
+    if !enter_nested("Tunnel", &mut table, state) {
+        return;
+    }
+    match frame {
+        Encapsulated::Ethernet(frame) => handle_ethernet(frame, table, state),
+        Encapsulated::Ipv4(frame) => handle_ipv4(frame, table, state),
+        Encapsulated::Ipv6(frame) => handle_ipv6(frame, table, state),
+        Encapsulated::Malformed(name, err) => handle_malformed(name, err, table, state),
+        Encapsulated::Other(_) => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            state.print(&table);
+        }
+    }
+}
+
+fn handle_gre(frame: GreFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Generic Routing Encapsulation"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
     table.add_row(row![
-        "\tSequence number:",
-        format!("0x{:08X}", frame.seq_num())
+        "\tProtocol type:",
+        format!("0x{:04X}", frame.protocol_type())
     ]);
-    if let Some(ack) = frame.ack_num() {
+    if let Some(checksum) = frame.checksum() {
         table.add_row(row![
-            "\tAcknowledge number:",
-            format!("0x{:08X}", ack)
-        ]);
+            "\tChecksum:",
+            format!(
+                "0x{:04X} ({})",
+                checksum,
+                if frame.has_integrity() {
+                    "PASS"
+                } else {
+                    "FAIL"
+                }
+            )
+        ]);
     }
-    table.add_row(row!["\tData offset:", frame.data_offset()]);
+    match (frame.nvgre_vsid(), frame.nvgre_flow_id(), frame.key()) {
+        (Some(vsid), Some(flow_id), _) => {
+            table.add_row(row!["\tVirtual subnet ID:", vsid]);
+            table.add_row(row!["\tFlow ID:", flow_id]);
+        }
+        (_, _, Some(key)) => {
+            table.add_row(row!["\tKey:", format!("0x{:08X}", key)]);
+        }
+        _ => {}
+    }
+    if let Some(seq) = frame.seq_num() {
+        table.add_row(row!["\tSequence number:", seq]);
+    }
+    if let Some(ack) = frame.ack_num() {
+        table.add_row(row!["\tAcknowledgment number:", ack]);
+    }
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("GRE"));
+    }
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tEncapsulated:", frame]);
+    handle_encapsulated(frame, table, state);
+}
+
+fn handle_ospf(frame: OspfFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> format!("Open Shortest Path First (version {})", frame.version())]);
+    table.add_row(row!["\tRouter ID:", frame.router_id()]);
+    table.add_row(row!["\tArea ID:", frame.area_id()]);
     table.add_row(row![
-        "\tHeader checksum:",
+        "\tChecksum:",
         format!(
             "0x{:04X} ({})",
             frame.checksum(),
@@ -493,13 +1502,472 @@ fn handle_tcp(frame: TcpFrame, mut table: Table) {
             }
         )
     ]);
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("OSPF"));
+    }
+    match frame.auth_type() {
+        Some(0) => table.add_row(row!["\tAuthentication:", "None"]),
+        Some(1) => table.add_row(row!["\tAuthentication:", "Simple password"]),
+        Some(2) => table.add_row(row!["\tAuthentication:", "Cryptographic"]),
+        Some(other) => table.add_row(row!["\tAuthentication:", other]),
+        None => table.add_row(row!["\tInstance ID:", frame.instance_id().unwrap_or(0)]),
+    };
+    match frame.message() {
+        Ok(msg) => {
+            table.add_row(row!["\tMessage:", msg]);
+            match msg {
+                OspfMessage::Hello(hello) => {
+                    if let Some(mask) = hello.network_mask {
+                        table.add_row(row!["\tNetwork mask:", mask]);
+                    }
+                    if let Some(interface_id) = hello.interface_id {
+                        table.add_row(row!["\tInterface ID:", interface_id]);
+                    }
+                    table.add_row(row![
+                        "\tHello interval:",
+                        format!("{} s", hello.hello_interval)
+                    ]);
+                    table.add_row(row![
+                        "\tDead interval:",
+                        format!("{} s", hello.dead_interval)
+                    ]);
+                    table.add_row(row!["\tPriority:", hello.priority]);
+                    table.add_row(row!["\tDesignated router:", hello.designated_router]);
+                    table.add_row(row!["\tBackup designated router:", hello.backup_router]);
+                    table.add_row(row!["\tNeighbors:"]);
+                    for neighbor in &hello.neighbors {
+                        table.add_row(row![format!("\t\t{}", neighbor)]);
+                    }
+                    // Neighbors declare each other dead between two hellos otherwise
+                    if hello.dead_interval <= u32::from(hello.hello_interval) {
+                        state.add_expert(
+                            &mut table,
+                            ExpertInfo::new(
+                                Severity::Warning,
+                                Group::Protocol,
+                                "OSPF",
+                                "Dead interval not above hello interval",
+                            ),
+                        );
+                    }
+                }
+                OspfMessage::DatabaseDescription {
+                    mtu,
+                    flags,
+                    seq_num,
+                    lsas,
+                    ..
+                } => {
+                    table.add_row(row!["\tInterface MTU:", mtu]);
+                    let flags: Vec<_> = [(0x04, "Init"), (0x02, "More"), (0x01, "Master")]
+                        .iter()
+                        .filter(|(bit, _)| flags & bit != 0)
+                        .map(|(_, name)| *name)
+                        .collect();
+                    table.add_row(row!["\tFlags:", flags.join(", ")]);
+                    table.add_row(row!["\tSequence number:", seq_num]);
+                    table.add_row(row!["\tLSA headers:"]);
+                    for lsa in &lsas {
+                        table.add_row(row![format!("\t\t{}", lsa)]);
+                    }
+                }
+                OspfMessage::LinkStateRequest(requests) => {
+                    table.add_row(row!["\tRequests:"]);
+                    for request in &requests {
+                        table.add_row(row![format!("\t\t{}", request)]);
+                    }
+                }
+                OspfMessage::LinkStateUpdate(lsas) | OspfMessage::LinkStateAck(lsas) => {
+                    table.add_row(row!["\tLSAs:"]);
+                    for lsa in &lsas {
+                        table.add_row(row![format!("\t\t{}", lsa)]);
+                    }
+                }
+                OspfMessage::Other(_) => {}
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "OSPF",
+                    "Malformed message",
+                ),
+            );
+        }
+    }
     table.add_empty_row();
     table.add_empty_row();
     table.add_empty_row();
-    table.printstd();
+    state.print(&table);
+}
+
+fn handle_pim(frame: PimFrame, mut table: Table, state: &mut SniffState) {
+    let describe_source = |source: &PimAddress| {
+        let flags: Vec<_> = [(source.is_wildcard(), "WC"), (source.is_rpt(), "RPT")]
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| *name)
+            .collect();
+        if flags.is_empty() {
+            source.to_string()
+        } else {
+            format!("{} ({})", source, flags.join(", "))
+        }
+    };
+    table.add_row(row![H2 -> "Protocol Independent Multicast"]);
+    table.add_row(row![
+        "\tChecksum:",
+        format!(
+            "0x{:04X} ({})",
+            frame.checksum(),
+            if frame.has_integrity() {
+                "PASS"
+            } else {
+                "FAIL"
+            }
+        )
+    ]);
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("PIM"));
+    }
+    match frame.message() {
+        Ok(msg) => {
+            table.add_row(row!["\tMessage:", msg]);
+            match msg {
+                PimMessage::Hello(options) => {
+                    table.add_row(row!["\tOptions:"]);
+                    for option in &options {
+                        table.add_row(row![format!("\t\t{}", option)]);
+                    }
+                }
+                PimMessage::Register {
+                    border,
+                    null,
+                    packet,
+                } => {
+                    let flags: Vec<_> = [(border, "Border"), (null, "Null register")]
+                        .iter()
+                        .filter(|(set, _)| *set)
+                        .map(|(_, name)| *name)
+                        .collect();
+                    table.add_row(row!["\tFlags:", flags.join(", ")]);
+                    table.add_row(row!["\tEncapsulated:", packet]);
+                    return handle_encapsulated(packet, table, state);
+                }
+                PimMessage::RegisterStop { group, source } => {
+                    table.add_row(row!["\tGroup:", group]);
+                    table.add_row(row!["\tSource:", source]);
+                }
+                PimMessage::JoinPrune {
+                    upstream_neighbor,
+                    holdtime,
+                    groups,
+                } => {
+                    table.add_row(row!["\tUpstream neighbor:", upstream_neighbor]);
+                    table.add_row(row!["\tHoldtime:", format!("{} s", holdtime)]);
+                    table.add_row(row!["\tGroups:"]);
+                    for set in &groups {
+                        table.add_row(row![format!("\t\t{}", set.group)]);
+                        for joined in &set.joined {
+                            table.add_row(row![format!("\t\t\tJoin {}", describe_source(joined))]);
+                        }
+                        for pruned in &set.pruned {
+                            table.add_row(row![format!("\t\t\tPrune {}", describe_source(pruned))]);
+                        }
+                    }
+                }
+                PimMessage::Assert {
+                    group,
+                    source,
+                    rpt,
+                    metric_preference,
+                    metric,
+                } => {
+                    table.add_row(row!["\tGroup:", group]);
+                    table.add_row(row!["\tSource:", source]);
+                    table.add_row(row!["\tRPT:", rpt]);
+                    table.add_row(row!["\tMetric preference:", metric_preference]);
+                    table.add_row(row!["\tMetric:", metric]);
+                }
+                PimMessage::Other(..) => {}
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "PIM",
+                    "Malformed message",
+                ),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_vrrp(frame: VrrpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(
+        row![H2 -> format!("Virtual Router Redundancy Protocol (version {})", frame.version())],
+    );
+    table.add_row(row!["\tVirtual router ID:", frame.vrid()]);
+    match frame.priority() {
+        0 => table.add_row(row!["\tPriority:", "0 (master stopping)"]),
+        255 => table.add_row(row!["\tPriority:", "255 (address owner)"]),
+        priority => table.add_row(row!["\tPriority:", priority]),
+    };
+    table.add_row(row![
+        "\tAdvertisement interval:",
+        format!("{} ms", frame.advert_interval().as_millis())
+    ]);
+    match frame.auth_type() {
+        Some(0) => {
+            table.add_row(row!["\tAuthentication:", "None"]);
+        }
+        Some(1) => {
+            let password = String::from_utf8_lossy(frame.auth_data());
+            table.add_row(row![
+                "\tAuthentication:",
+                format!("Password: {}", password.trim_end_matches('\0'))
+            ]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Warning,
+                    Group::Security,
+                    "VRRP",
+                    "Cleartext password",
+                ),
+            );
+        }
+        Some(other) => {
+            table.add_row(row!["\tAuthentication:", other]);
+        }
+        None => {}
+    }
+    table.add_row(row![
+        "\tChecksum:",
+        format!(
+            "0x{:04X} ({})",
+            frame.checksum(),
+            if frame.has_integrity() {
+                "PASS"
+            } else {
+                "FAIL"
+            }
+        )
+    ]);
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("VRRP"));
+    }
+    table.add_row(row!["\tAddresses:"]);
+    for addr in frame.addresses() {
+        table.add_row(row![format!("\t\t{}", addr)]);
+    }
+    if frame.priority() == 0 {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Note, Group::Protocol, "VRRP", "Master stopping"),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+/// Dissects the protocol protected by an AH or ESP header of a packet from `src_addr`
+/// to `dest_addr`.
+fn handle_protected(
+    frame: IpProtocol,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    match frame {
+        IpProtocol::Tcp(frame) => handle_tcp(frame, table, state, src_addr, dest_addr),
+        IpProtocol::Udp(frame) => handle_udp(frame, table, state, src_addr, dest_addr),
+        IpProtocol::Sctp(frame) => handle_sctp(frame, table, state, src_addr, dest_addr),
+        IpProtocol::Esp(frame) => {
+            if enter_nested("ESP", &mut table, state) {
+                handle_esp(frame, table, state, src_addr, dest_addr);
+            }
+        }
+        IpProtocol::Ah(frame) => {
+            if enter_nested("AH", &mut table, state) {
+                handle_ah(frame, table, state, src_addr, dest_addr);
+            }
+        }
+        IpProtocol::Gre(frame) => handle_gre(frame, table, state),
+        IpProtocol::Ospf(frame) => handle_ospf(frame, table, state),
+        IpProtocol::Pim(frame) => handle_pim(frame, table, state),
+        IpProtocol::Vrrp(frame) => handle_vrrp(frame, table, state),
+        IpProtocol::Ipv4(frame) => handle_encapsulated(Encapsulated::Ipv4(frame), table, state),
+        IpProtocol::Ipv6(frame) => handle_encapsulated(Encapsulated::Ipv6(frame), table, state),
+        IpProtocol::Malformed(name, err) => handle_malformed(name, err, table, state),
+        _ => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            state.print(&table);
+        }
+    }
+}
+
+fn handle_ah(
+    frame: AhFrame,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    table.add_row(row![H2 -> "Authentication Header"]);
+    table.add_row(row!["\tSPI:", format!("0x{:08X}", frame.spi())]);
+    table.add_row(row!["\tSequence number:", frame.seq_num()]);
+    table.add_row(row!["\tLength:", frame.header_len()]);
+    table.add_row(row!["\tICV:", to_hex(frame.icv())]);
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tNext header:", frame]);
+    handle_protected(frame, table, state, src_addr, dest_addr);
+}
+
+fn handle_esp(
+    frame: EspFrame,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    table.add_row(row![H2 -> "Encapsulating Security Payload"]);
+    table.add_row(row!["\tSPI:", format!("0x{:08X}", frame.spi())]);
+    table.add_row(row!["\tSequence number:", frame.seq_num()]);
+    let decrypted = state
+        .esp
+        .get(frame.spi())
+        .map(|sa| (sa.algorithm, frame.decrypt(sa)));
+    match decrypted {
+        Some((algorithm, Ok(plaintext))) => {
+            table.add_row(row!["\tAlgorithm:", algorithm]);
+            table.add_row(row!["\tPad length:", plaintext.pad_len()]);
+            let frame = plaintext.try_next_header();
+            table.add_row(row!["\tNext header:", frame]);
+            handle_protected(frame, table, state, src_addr, dest_addr);
+            return;
+        }
+        Some((algorithm, Err(err))) => {
+            table.add_row(row!["\tAlgorithm:", algorithm]);
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Warning,
+                    Group::Security,
+                    "ESP",
+                    "Decryption failed",
+                ),
+            );
+        }
+        None => {
+            table.add_row(row![
+                "\tEncrypted payload:",
+                format!("{} bytes", frame.data().len())
+            ]);
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_tcp(
+    frame: TcpFrame,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    let src = SocketAddr::new(src_addr, frame.src_port());
+    let dest = SocketAddr::new(dest_addr, frame.dest_port());
+    let analysis = state.tcp.analyze(src, dest, &frame, state.ts);
+    table.add_row(row![H2 -> "Transmission Control Protocol"]);
+    table.add_row(row!["\tSource port:", frame.src_port()]);
+    table.add_row(row!["\tDestination port:", frame.dest_port()]);
+    table.add_row(row![
+        "\tSequence number:",
+        format!("0x{:08X}", frame.seq_num())
+    ]);
+    if let Some(ack) = frame.ack_num() {
+        table.add_row(row![
+            "\tAcknowledge number:",
+            format!("0x{:08X}", ack)
+        ]);
+    }
+    table.add_row(row!["\tData offset:", frame.data_offset()]);
+    table.add_row(row!["\tWindow size:", frame.window_sz()]);
+    if let Some(window) = analysis.window() {
+        table.add_row(row!["\tCalculated window size:", window]);
+    }
+    if let Some(rtt) = analysis.ack_rtt() {
+        table.add_row(row![
+            "\tRTT to ACK:",
+            format!("{:.3} ms", rtt.as_secs_f64() * 1000.0)
+        ]);
+    }
+    table.add_row(row![
+        "\tHeader checksum:",
+        format!(
+            "0x{:04X} ({})",
+            frame.checksum(),
+            if frame.has_integrity() {
+                "PASS"
+            } else {
+                "FAIL"
+            }
+        )
+    ]);
+    let mut malformed_opts = false;
+    if frame.opts().is_some() {
+        table.add_row(row!["\tOptions:"]);
+        for opt in frame.options() {
+            malformed_opts |= matches!(opt, TcpOption::Malformed(_));
+            table.add_row(row![format!("\t\t{}", opt)]);
+        }
+    }
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("TCP"));
+    }
+    if malformed_opts {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Error, Group::Malformed, "TCP", "Malformed option"),
+        );
+    }
+    for event in analysis.events() {
+        state.add_expert(&mut table, event.expert_info());
+    }
+    handle_app(frame.try_next_header(), table, state, src, dest);
 }
 
-fn handle_udp(frame: UdpFrame, mut table: Table) {
+fn handle_udp(
+    frame: UdpFrame,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    let src = SocketAddr::new(src_addr, frame.src_port());
+    let dest = SocketAddr::new(dest_addr, frame.dest_port());
     table.add_row(row![H2 -> "User Datagram Protocol Protocol"]);
     table.add_row(row!["\tSource port:", frame.src_port()]);
     table.add_row(row!["\tDestination port:", frame.dest_port()]);
@@ -515,8 +1983,1343 @@ fn handle_udp(frame: UdpFrame, mut table: Table) {
             }
         )
     ]);
+    // A zero checksum means the sender did not compute one
+    if frame.checksum() != 0 && !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("UDP"));
+    }
+    // Media streams negotiated by SIP are decoded whatever their ports
+    let frame = if state.rtp.is_media(&dest) {
+        frame.try_media()
+    } else {
+        frame.try_next_header()
+    };
+    handle_app(frame, table, state, src, dest);
+}
+
+fn handle_sctp(
+    frame: SctpFrame,
+    mut table: Table,
+    state: &mut SniffState,
+    src_addr: IpAddr,
+    dest_addr: IpAddr,
+) {
+    let src = SocketAddr::new(src_addr, frame.src_port());
+    let dest = SocketAddr::new(dest_addr, frame.dest_port());
+    table.add_row(row![H2 -> "Stream Control Transmission Protocol"]);
+    table.add_row(row!["\tSource port:", frame.src_port()]);
+    table.add_row(row!["\tDestination port:", frame.dest_port()]);
+    table.add_row(row![
+        "\tVerification tag:",
+        format!("0x{:08X}", frame.verification_tag())
+    ]);
+    table.add_row(row![
+        "\tChecksum:",
+        format!(
+            "0x{:08X} ({})",
+            frame.checksum(),
+            if frame.has_integrity() {
+                "PASS"
+            } else {
+                "FAIL"
+            }
+        )
+    ]);
+    if !frame.has_integrity() {
+        state.add_expert(&mut table, ExpertInfo::bad_checksum("SCTP"));
+    }
+    match frame.chunks() {
+        Ok(chunks) => {
+            table.add_row(row!["\tChunks:"]);
+            for chunk in &chunks {
+                table.add_row(row![format!("\t\t{}", chunk)]);
+                match chunk {
+                    SctpChunk::Init(init) | SctpChunk::InitAck(init) => {
+                        for param in &init.params {
+                            table.add_row(row![format!("\t\t\t{}", param)]);
+                        }
+                    }
+                    SctpChunk::Abort { .. } => state.add_expert(
+                        &mut table,
+                        ExpertInfo::new(
+                            Severity::Warning,
+                            Group::Sequence,
+                            "SCTP",
+                            "Association aborted",
+                        ),
+                    ),
+                    _ => {}
+                }
+            }
+            // Bundled messages would share the table, so only the first one is dissected
+            let message = chunks.iter().find_map(|chunk| match chunk {
+                SctpChunk::Data(data) => Some(data.try_next_header()),
+                _ => None,
+            });
+            if let Some(frame) = message {
+                return handle_app(frame, table, state, src, dest);
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(Severity::Error, Group::Malformed, "SCTP", "Malformed chunk"),
+            );
+        }
+    }
     table.add_empty_row();
     table.add_empty_row();
     table.add_empty_row();
-    table.printstd();
+    state.print(&table);
+}
+
+fn handle_app(
+    frame: AppProtocol,
+    mut table: Table,
+    state: &mut SniffState,
+    src: SocketAddr,
+    dest: SocketAddr,
+) {
+    match frame {
+        AppProtocol::Dns(frame) => handle_dns(frame, table, state),
+        AppProtocol::Dhcp(frame) => handle_dhcp(frame, table, state),
+        AppProtocol::Dhcpv6(frame) => handle_dhcpv6(frame, table, state),
+        AppProtocol::Http(frame) => handle_http(frame, table, state),
+        AppProtocol::Tls(frame) => handle_tls(frame, table, state),
+        AppProtocol::Quic(frame) => handle_quic(frame, table, state),
+        AppProtocol::Ntp(frame) => handle_ntp(frame, table, state),
+        AppProtocol::Snmp(frame) => handle_snmp(frame, table, state),
+        AppProtocol::Syslog(frame) => handle_syslog(frame, table, state),
+        AppProtocol::Sip(frame) => handle_sip(frame, table, state),
+        AppProtocol::Rtp(frame) => {
+            let event = state.rtp.record(src, dest, &frame, state.ts);
+            handle_rtp(frame, table, state, event)
+        }
+        AppProtocol::Rtcp(frame) => handle_rtcp(frame, table, state),
+        AppProtocol::Vxlan(frame) => handle_vxlan(frame, table, state),
+        AppProtocol::Geneve(frame) => handle_geneve(frame, table, state),
+        AppProtocol::Rip(frame) => handle_rip(frame, table, state),
+        AppProtocol::Bgp(frame) => handle_bgp(frame, table, state),
+        AppProtocol::Hsrp(frame) => handle_hsrp(frame, table, state),
+        AppProtocol::Malformed(name, err) => handle_malformed(name, err, table, state),
+        AppProtocol::Other(_) => {
+            table.add_empty_row();
+            table.add_empty_row();
+            table.add_empty_row();
+            state.print(&table);
+        }
+    }
+}
+
+fn handle_dns(frame: DnsFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> format!(
+        "Domain Name System ({})",
+        if frame.is_response() { "response" } else { "query" }
+    )]);
+    table.add_row(row!["\tTransaction ID:", format!("0x{:04X}", frame.id())]);
+    table.add_row(row!["\tOpcode:", frame.opcode()]);
+    let flags = [
+        (frame.authoritative(), "Authoritative"),
+        (frame.truncated(), "Truncated"),
+        (frame.recursion_desired(), "Recursion desired"),
+        (frame.recursion_available(), "Recursion available"),
+        (frame.authentic_data(), "Authentic data"),
+        (frame.checking_disabled(), "Checking disabled"),
+    ];
+    table.add_row(row!["\tFlags:"]);
+    for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+        table.add_row(row!["", format!("\t\t{}", flag)]);
+    }
+    if frame.is_response() {
+        table.add_row(row!["\tReply code:", frame.rcode()]);
+    }
+    table.add_row(row!["\tQuestions:", frame.qdcount()]);
+    table.add_row(row!["\tAnswer RRs:", frame.ancount()]);
+    table.add_row(row!["\tAuthority RRs:", frame.nscount()]);
+    table.add_row(row!["\tAdditional RRs:", frame.arcount()]);
+    if frame.missing() > 0 {
+        table.add_row(row![
+            "\tContinued in the next segments:",
+            format!("{} bytes", frame.missing())
+        ]);
+    }
+    match frame.sections() {
+        Ok(sections) => {
+            if !sections.questions.is_empty() {
+                table.add_row(row!["\tQueries:"]);
+                for question in &sections.questions {
+                    table.add_row(row![format!("\t\t{}", question)]);
+                }
+            }
+            let records = [
+                ("\tAnswers:", &sections.answers),
+                ("\tAuthoritative nameservers:", &sections.authorities),
+                ("\tAdditional records:", &sections.additionals),
+            ];
+            for (title, records) in records.iter().filter(|(_, records)| !records.is_empty()) {
+                table.add_row(row![title]);
+                for record in records.iter() {
+                    table.add_row(row![format!("\t\t{}", record)]);
+                }
+            }
+        }
+        Err(_) if frame.missing() > 0 => {
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(Severity::Note, Group::Sequence, "DNS", "Incomplete message"),
+            );
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(Severity::Error, Group::Malformed, "DNS", "Malformed packet"),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_dhcp(frame: DhcpFrame, mut table: Table, state: &mut SniffState) {
+    let title = match frame.message_type() {
+        Some(typ) => format!("Dynamic Host Configuration Protocol ({})", typ),
+        None => String::from("Bootstrap Protocol"),
+    };
+    table.add_row(row![H2 -> title]);
+    let op = match frame.op() {
+        1 => "Boot request",
+        2 => "Boot reply",
+        _ => "Unknown",
+    };
+    table.add_row(row!["\tMessage type:", format!("{} ({})", op, frame.op())]);
+    table.add_row(row!["\tHardware type:", frame.htype()]);
+    table.add_row(row!["\tHops:", frame.hops()]);
+    table.add_row(row![
+        "\tTransaction ID:",
+        format!("0x{:08X}", frame.xid())
+    ]);
+    table.add_row(row!["\tSeconds elapsed:", frame.secs()]);
+    table.add_row(row![
+        "\tFlags:",
+        format!(
+            "0x{:04X} ({})",
+            frame.flags(),
+            if frame.broadcast() { "Broadcast" } else { "Unicast" }
+        )
+    ]);
+    table.add_row(row!["\tClient IP address:", frame.ciaddr()]);
+    table.add_row(row!["\tYour IP address:", frame.yiaddr()]);
+    table.add_row(row!["\tNext server IP address:", frame.siaddr()]);
+    table.add_row(row!["\tRelay agent IP address:", frame.giaddr()]);
+    match frame.chaddr() {
+        Some(addr) => table.add_row(row!["\tClient MAC address:", addr]),
+        None => table.add_row(row![
+            "\tClient hardware address:",
+            format!("{:02X?}", frame.raw_chaddr())
+        ]),
+    };
+    let sname = frame.sname();
+    if !sname.is_empty() {
+        table.add_row(row!["\tServer host name:", sname]);
+    }
+    let file = frame.file();
+    if !file.is_empty() {
+        table.add_row(row!["\tBoot file name:", file]);
+    }
+    match frame.options() {
+        Ok(options) if !options.is_empty() => {
+            table.add_row(row!["\tOptions:"]);
+            for option in &options {
+                table.add_row(row![format!("\t\t{}", option)]);
+            }
+        }
+        Ok(_) => {}
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(Severity::Error, Group::Malformed, "DHCP", "Malformed option"),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_dhcpv6(frame: Dhcpv6Frame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> format!(
+        "DHCPv6 ({})",
+        frame.msg_type()
+    )]);
+    if let Err(err) = add_dhcpv6_message(&mut table, &frame, 1) {
+        table.add_row(row!["\tError:", err]);
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Error,
+                Group::Malformed,
+                "DHCPv6",
+                "Malformed option",
+            ),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+/// Adds the fields of a DHCPv6 message indented by `depth` tabs, descending into
+/// the messages encapsulated by relay agents.
+fn add_dhcpv6_message(
+    table: &mut Table,
+    frame: &Dhcpv6Frame,
+    depth: usize,
+) -> Result<(), FrameError> {
+    let indent = "\t".repeat(depth);
+    table.add_row(row![format!("{}Message type:", indent), frame.msg_type()]);
+    if let Some(xid) = frame.transaction_id() {
+        table.add_row(row![
+            format!("{}Transaction ID:", indent),
+            format!("0x{:06X}", xid)
+        ]);
+    }
+    if let (Some(hops), Some(link), Some(peer)) =
+        (frame.hop_count(), frame.link_addr(), frame.peer_addr())
+    {
+        table.add_row(row![format!("{}Hop count:", indent), hops]);
+        table.add_row(row![format!("{}Link address:", indent), link]);
+        table.add_row(row![format!("{}Peer address:", indent), peer]);
+    }
+    let options = frame.options()?;
+    if !options.is_empty() {
+        table.add_row(row![format!("{}Options:", indent)]);
+    }
+    add_dhcpv6_options(table, &options, depth + 1)
+}
+
+fn add_dhcpv6_options(
+    table: &mut Table,
+    options: &[Dhcpv6Option],
+    depth: usize,
+) -> Result<(), FrameError> {
+    let indent = "\t".repeat(depth);
+    for option in options {
+        table.add_row(row![format!("{}{}", indent, option)]);
+        if let Dhcpv6Option::RelayMessage(frame) = option {
+            add_dhcpv6_message(table, frame, depth + 1)?;
+        }
+        add_dhcpv6_options(table, option.sub_options(), depth + 1)?;
+    }
+    Ok(())
+}
+
+fn handle_http(frame: HttpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Hypertext Transfer Protocol"]);
+    table.add_row(row![format!("\t{}", frame.start_line())]);
+    if let Some(len) = frame.content_length() {
+        table.add_row(row!["\tContent length:", len]);
+    }
+    match frame.chunks() {
+        Ok(chunks) if !chunks.is_empty() => {
+            table.add_row(row!["\tChunks:"]);
+            for chunk in chunks {
+                table.add_row(row![format!(
+                    "\t\t{} bytes at body offset {}",
+                    chunk.len, chunk.offset
+                )]);
+            }
+        }
+        Ok(_) => {}
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "HTTP",
+                    "Malformed chunked body",
+                ),
+            );
+        }
+    }
+    if !frame.headers_complete() {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Note,
+                Group::Sequence,
+                "HTTP",
+                "Header block continues in the next segment",
+            ),
+        );
+    }
+    // The table only keeps the summary, the JSON output gets every header
+    if state.json {
+        table.add_row(row!["\tHeaders:"]);
+        for (name, value) in frame.headers() {
+            table.add_row(row![format!("\t\t{}: {}", name, value)]);
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_tls(frame: TlsFrame, mut table: Table, state: &mut SniffState) {
+    for record in frame.records() {
+        table.add_row(row![H2 -> format!("Transport Layer Security: {}", record.content_type())]);
+        table.add_row(row!["\tVersion:", record.version()]);
+        if record.is_complete() {
+            table.add_row(row!["\tLength:", record.len()]);
+        } else {
+            table.add_row(row![
+                "\tLength:",
+                format!(
+                    "{} ({} bytes in this segment)",
+                    record.len(),
+                    record.fragment().len()
+                )
+            ]);
+        }
+        match record.content_type() {
+            TlsContentType::Handshake => match record.handshakes() {
+                Ok(handshakes) => {
+                    for handshake in &handshakes {
+                        add_tls_handshake(&mut table, handshake, state);
+                    }
+                }
+                Err(err) => {
+                    table.add_row(row!["\tError:", err]);
+                    state.add_expert(
+                        &mut table,
+                        ExpertInfo::new(
+                            Severity::Error,
+                            Group::Malformed,
+                            "TLS",
+                            "Malformed handshake message",
+                        ),
+                    );
+                }
+            },
+            TlsContentType::Alert => match record.alert() {
+                Some(alert) => {
+                    table.add_row(row!["\tAlert:", alert]);
+                    if alert.is_fatal() {
+                        state.add_expert(
+                            &mut table,
+                            ExpertInfo::new(Severity::Error, Group::Protocol, "TLS", "Fatal alert"),
+                        );
+                    }
+                }
+                None => {
+                    table.add_row(row!["\tAlert:", "Encrypted Alert"]);
+                }
+            },
+            _ => {}
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn cipher_suite(suite: u16) -> String {
+    match cipher_suite_name(suite) {
+        Some(name) => format!("{} (0x{:04x})", name, suite),
+        None if is_grease(suite) => format!("Reserved (GREASE) (0x{:04x})", suite),
+        None => format!("Unknown (0x{:04x})", suite),
+    }
+}
+
+fn add_tls_handshake(table: &mut Table, handshake: &TlsHandshake, state: &mut SniffState) {
+    table.add_row(row!["\tHandshake type:", handshake]);
+    match handshake {
+        TlsHandshake::ClientHello(hello) => {
+            table.add_row(row!["\tClient version:", hello.version()]);
+            if let Some(name) = hello.server_name() {
+                table.add_row(row!["\tServer name:", name]);
+            }
+            if !hello.alpn().is_empty() {
+                table.add_row(row!["\tALPN:", hello.alpn().join(", ")]);
+            }
+            table.add_row(row!["\tCipher suites:"]);
+            for &suite in hello.cipher_suites() {
+                table.add_row(row![format!("\t\t{}", cipher_suite(suite))]);
+            }
+            table.add_row(row!["\tExtensions:"]);
+            for extension in hello.extensions() {
+                table.add_row(row![format!("\t\t{}", extension)]);
+            }
+            table.add_row(row!["\tJA3:", hello.ja3_string()]);
+            table.add_row(row!["\tJA3 hash:", hello.ja3()]);
+        }
+        TlsHandshake::ServerHello(hello) => {
+            table.add_row(row!["\tServer version:", hello.negotiated_version()]);
+            table.add_row(row!["\tCipher suite:", cipher_suite(hello.cipher_suite())]);
+            if let Some(protocol) = hello.alpn() {
+                table.add_row(row!["\tALPN:", protocol]);
+            }
+            table.add_row(row!["\tExtensions:"]);
+            for extension in hello.extensions() {
+                table.add_row(row![format!("\t\t{}", extension)]);
+            }
+            table.add_row(row!["\tJA3S:", hello.ja3s_string()]);
+            table.add_row(row!["\tJA3S hash:", hello.ja3s()]);
+        }
+        TlsHandshake::Certificate(certs) => {
+            let now = DateTime::<Utc>::from(UNIX_EPOCH + state.ts);
+            let mut expired = false;
+            table.add_row(row!["\tCertificates:"]);
+            for &cert in certs {
+                match X509Certificate::try_from(cert) {
+                    Ok(cert) => {
+                        table.add_row(row![format!("\t\tSubject: {}", cert.subject())]);
+                        table.add_row(row![format!("\t\tIssuer: {}", cert.issuer())]);
+                        table.add_row(row![format!(
+                            "\t\tValidity: {} to {}",
+                            cert.not_before().format("%+"),
+                            cert.not_after().format("%+")
+                        )]);
+                        expired |= cert.not_after() < now || cert.not_before() > now;
+                    }
+                    Err(err) => {
+                        table.add_row(row![format!("\t\tError: {}", err)]);
+                    }
+                }
+            }
+            if expired {
+                state.add_expert(
+                    table,
+                    ExpertInfo::new(
+                        Severity::Warning,
+                        Group::Security,
+                        "TLS",
+                        "Certificate outside its validity period",
+                    ),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_quic(frame: QuicFrame, mut table: Table, state: &mut SniffState) {
+    let packets = match frame.packets() {
+        Ok(packets) => packets,
+        Err(err) => {
+            table.add_row(row![H2 -> "QUIC"]);
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "QUIC",
+                    "Malformed packet",
+                ),
+            );
+            Vec::new()
+        }
+    };
+    for packet in &packets {
+        table.add_row(row![H2 -> format!("QUIC: {}", packet.packet_type())]);
+        if !packet.is_long_header() {
+            table.add_row(row!["\tLength:", packet.len()]);
+            continue;
+        }
+        table.add_row(row![
+            "\tVersion:",
+            format!(
+                "{} (0x{:08x})",
+                quic_version_name(packet.version()),
+                packet.version()
+            )
+        ]);
+        table.add_row(row!["\tDestination connection ID:", to_hex(packet.dcid())]);
+        table.add_row(row!["\tSource connection ID:", to_hex(packet.scid())]);
+        table.add_row(row!["\tLength:", packet.len()]);
+        match packet.packet_type() {
+            QuicPacketType::VersionNegotiation => {
+                table.add_row(row!["\tSupported versions:"]);
+                for version in packet.supported_versions() {
+                    table.add_row(row![format!(
+                        "\t\t{} (0x{:08x})",
+                        quic_version_name(version),
+                        version
+                    )]);
+                }
+            }
+            QuicPacketType::Initial => {
+                if !packet.token().is_empty() {
+                    table.add_row(row!["\tToken:", to_hex(packet.token())]);
+                }
+                add_quic_initial(&mut table, packet, state);
+            }
+            _ => {}
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn add_quic_initial(table: &mut Table, packet: &QuicPacket, state: &mut SniffState) {
+    // Server Initial packets use keys derived from the connection ID chosen by the
+    // client, which they do not carry
+    let plaintext = match packet.decrypt_initial() {
+        Ok(plaintext) => plaintext,
+        Err(_) => {
+            table.add_row(row!["\tPayload:", "Encrypted (no client keys)"]);
+            return;
+        }
+    };
+    table.add_row(row!["\tPacket number:", plaintext.packet_number()]);
+    let crypto = plaintext
+        .frames()
+        .and_then(|frames| {
+            table.add_row(row!["\tFrames:"]);
+            for frame in &frames {
+                table.add_row(row![format!("\t\t{}", frame)]);
+            }
+            plaintext.crypto()
+        })
+        .and_then(|crypto| {
+            for handshake in TlsHandshake::read_all(&crypto)? {
+                add_tls_handshake(table, &handshake, state);
+            }
+            Ok(())
+        });
+    if let Err(err) = crypto {
+        table.add_row(row!["\tError:", err]);
+        state.add_expert(
+            table,
+            ExpertInfo::new(
+                Severity::Error,
+                Group::Malformed,
+                "QUIC",
+                "Malformed Initial payload",
+            ),
+        );
+    }
+}
+
+fn ntp_time(time: Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time.format("%+").to_string(),
+        None => String::from("Not set"),
+    }
+}
+
+fn handle_ntp(frame: NtpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> format!("Network Time Protocol ({})", frame.mode())]);
+    table.add_row(row!["\tLeap indicator:", frame.leap_indicator()]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    table.add_row(row!["\tMode:", frame.mode()]);
+    match frame.message() {
+        NtpMessage::Packet(packet) => {
+            table.add_row(row!["\tStratum:", packet.stratum()]);
+            table.add_row(row![
+                "\tPoll interval:",
+                format!("{} ({} s)", packet.poll(), 2f64.powi(packet.poll().into()))
+            ]);
+            table.add_row(row![
+                "\tPrecision:",
+                format!(
+                    "{} ({} s)",
+                    packet.precision(),
+                    2f64.powi(packet.precision().into())
+                )
+            ]);
+            table.add_row(row!["\tRoot delay:", format!("{} s", packet.root_delay())]);
+            table.add_row(row![
+                "\tRoot dispersion:",
+                format!("{} s", packet.root_dispersion())
+            ]);
+            table.add_row(row!["\tReference ID:", packet.reference_id()]);
+            table.add_row(row![
+                "\tReference timestamp:",
+                ntp_time(packet.reference_timestamp())
+            ]);
+            table.add_row(row![
+                "\tOrigin timestamp:",
+                ntp_time(packet.origin_timestamp())
+            ]);
+            table.add_row(row![
+                "\tReceive timestamp:",
+                ntp_time(packet.receive_timestamp())
+            ]);
+            table.add_row(row![
+                "\tTransmit timestamp:",
+                ntp_time(packet.transmit_timestamp())
+            ]);
+            if let Some((key_id, digest)) = packet.authenticator() {
+                table.add_row(row!["\tKey ID:", key_id]);
+                table.add_row(row!["\tMessage digest:", format!("{:02X?}", digest)]);
+            }
+            if packet.is_kiss_of_death() {
+                state.add_expert(
+                    &mut table,
+                    ExpertInfo::new(
+                        Severity::Warning,
+                        Group::Protocol,
+                        "NTP",
+                        "Kiss-o'-Death from server",
+                    ),
+                );
+            }
+        }
+        NtpMessage::Control(control) => {
+            let kind = if control.is_response() {
+                "Response"
+            } else {
+                "Request"
+            };
+            table.add_row(row!["\tType:", kind]);
+            table.add_row(row!["\tOpcode:", control.opcode()]);
+            table.add_row(row!["\tSequence:", control.sequence()]);
+            table.add_row(row!["\tStatus:", format!("0x{:04X}", control.status())]);
+            table.add_row(row!["\tAssociation ID:", control.association_id()]);
+            table.add_row(row!["\tOffset:", control.offset()]);
+            table.add_row(row!["\tCount:", control.count()]);
+            if !control.data().is_empty() {
+                table.add_row(row!["\tData:", String::from_utf8_lossy(control.data())]);
+            }
+            if control.is_error() {
+                state.add_expert(
+                    &mut table,
+                    ExpertInfo::new(Severity::Note, Group::Protocol, "NTP", "Control error"),
+                );
+            }
+        }
+        NtpMessage::Private(private) => {
+            let kind = if private.is_response() {
+                "Response"
+            } else {
+                "Request"
+            };
+            table.add_row(row!["\tType:", kind]);
+            table.add_row(row!["\tSequence:", private.sequence()]);
+            table.add_row(row!["\tImplementation:", private.implementation()]);
+            table.add_row(row!["\tRequest code:", private.request_code()]);
+            table.add_row(row!["\tError:", private.error()]);
+            table.add_row(row!["\tItems:", private.item_count()]);
+            table.add_row(row!["\tItem size:", private.item_size()]);
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_snmp(frame: SnmpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Simple Network Management Protocol"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    if let Some(community) = frame.community() {
+        table.add_row(row!["\tCommunity:", String::from_utf8_lossy(community)]);
+    }
+    if let Some(header) = frame.v3_header() {
+        table.add_row(row!["\tMessage ID:", header.msg_id]);
+        table.add_row(row!["\tMaximum size:", header.max_size]);
+        table.add_row(row![
+            "\tFlags:",
+            format!(
+                "0x{:02X} (auth: {}, priv: {}, reportable: {})",
+                header.flags,
+                header.is_authenticated(),
+                header.is_encrypted(),
+                header.is_reportable()
+            )
+        ]);
+        table.add_row(row!["\tSecurity model:", header.security_model]);
+        table.add_row(row!["\tEngine ID:", to_hex(header.engine_id)]);
+        table.add_row(row!["\tEngine boots:", header.engine_boots]);
+        table.add_row(row!["\tEngine time:", header.engine_time]);
+        table.add_row(row![
+            "\tUser name:",
+            String::from_utf8_lossy(header.user_name)
+        ]);
+        if let Some(engine_id) = header.context_engine_id {
+            table.add_row(row!["\tContext engine ID:", to_hex(engine_id)]);
+        }
+        if let Some(name) = header.context_name {
+            table.add_row(row!["\tContext name:", String::from_utf8_lossy(name)]);
+        }
+    }
+    if let Some(encrypted) = frame.encrypted_pdu() {
+        table.add_row(row![
+            "\tPDU:",
+            format!("Encrypted ({} bytes)", encrypted.len())
+        ]);
+    }
+    if let Some(pdu) = frame.pdu() {
+        table.add_row(row!["\tPDU type:", pdu.pdu_type()]);
+        match pdu {
+            SnmpPdu::Request {
+                pdu_type: SnmpPduType::GetBulkRequest,
+                request_id,
+                error_status,
+                error_index,
+                ..
+            } => {
+                table.add_row(row!["\tRequest ID:", request_id]);
+                table.add_row(row!["\tNon-repeaters:", error_status]);
+                table.add_row(row!["\tMax repetitions:", error_index]);
+            }
+            SnmpPdu::Request {
+                request_id,
+                error_status,
+                error_index,
+                ..
+            } => {
+                table.add_row(row!["\tRequest ID:", request_id]);
+                table.add_row(row![
+                    "\tError status:",
+                    format!(
+                        "{} ({})",
+                        snmp_error_name(*error_status).unwrap_or("Unknown"),
+                        error_status
+                    )
+                ]);
+                table.add_row(row!["\tError index:", error_index]);
+                if *error_status != 0 {
+                    state.add_expert(
+                        &mut table,
+                        ExpertInfo::new(Severity::Warning, Group::Protocol, "SNMP", "Error status"),
+                    );
+                }
+            }
+            SnmpPdu::TrapV1 {
+                enterprise,
+                agent_addr,
+                generic_trap,
+                specific_trap,
+                timestamp,
+                ..
+            } => {
+                table.add_row(row!["\tEnterprise:", enterprise]);
+                table.add_row(row!["\tAgent address:", agent_addr]);
+                table.add_row(row![
+                    "\tGeneric trap:",
+                    format!(
+                        "{} ({})",
+                        snmp_generic_trap_name(*generic_trap).unwrap_or("Unknown"),
+                        generic_trap
+                    )
+                ]);
+                table.add_row(row!["\tSpecific trap:", specific_trap]);
+                table.add_row(row!["\tTimestamp:", SnmpValue::TimeTicks(*timestamp)]);
+            }
+        }
+        table.add_row(row!["\tVariable bindings:"]);
+        for varbind in pdu.varbinds() {
+            table.add_row(row![format!("\t\t{} = {}", varbind.oid, varbind.value)]);
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_syslog(frame: SyslogFrame, mut table: Table, state: &mut SniffState) {
+    for message in frame.messages() {
+        let facility = syslog_facility_name(message.facility()).unwrap_or("Unknown");
+        table.add_row(row![H2 -> format!("Syslog: {}.{}", facility, message.severity())]);
+        table.add_row(row![
+            "\tFacility:",
+            format!("{} ({})", facility, message.facility())
+        ]);
+        table.add_row(row![
+            "\tSeverity:",
+            format!("{} ({})", message.severity(), message.pri() & 0b111)
+        ]);
+        if let Some(version) = message.version() {
+            table.add_row(row!["\tVersion:", version]);
+        }
+        if let Some(timestamp) = message.timestamp() {
+            match message.datetime() {
+                Some(time) => table.add_row(row!["\tTimestamp:", time.format("%+")]),
+                None => table.add_row(row!["\tTimestamp:", timestamp]),
+            };
+        }
+        if let Some(hostname) = message.hostname() {
+            table.add_row(row!["\tHostname:", hostname]);
+        }
+        if let Some(app_name) = message.app_name() {
+            table.add_row(row!["\tApp name:", app_name]);
+        }
+        if let Some(procid) = message.procid() {
+            table.add_row(row!["\tProcess ID:", procid]);
+        }
+        if let Some(msgid) = message.msgid() {
+            table.add_row(row!["\tMessage ID:", msgid]);
+        }
+        if !message.structured_data().is_empty() {
+            table.add_row(row!["\tStructured data:"]);
+            for element in message.structured_data() {
+                table.add_row(row![format!("\t\t{}", element)]);
+            }
+        }
+        table.add_row(row!["\tMessage:", message.message()]);
+    }
+    if !frame.partial().is_empty() {
+        if frame.messages().is_empty() {
+            table.add_row(row![H2 -> "Syslog"]);
+        }
+        table.add_row(row![
+            "\tIncomplete message:",
+            format!(
+                "{} bytes, continued in the next segment",
+                frame.partial().len()
+            )
+        ]);
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Note,
+                Group::Sequence,
+                "Syslog",
+                "Incomplete message",
+            ),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_sip(frame: SipFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Session Initiation Protocol"]);
+    table.add_row(row![format!("\t{}", frame.start_line())]);
+    if let Some(call_id) = frame.call_id() {
+        table.add_row(row!["\tCall-ID:", call_id]);
+    }
+    if let Some((seq, method)) = frame.cseq() {
+        table.add_row(row!["\tCSeq:", format!("{} {}", seq, method)]);
+    }
+    if let Some(from) = frame.from() {
+        table.add_row(row!["\tFrom:", from]);
+    }
+    if let Some(to) = frame.to() {
+        table.add_row(row!["\tTo:", to]);
+    }
+    if let Some(contact) = frame.contact() {
+        table.add_row(row!["\tContact:", contact]);
+    }
+    let via = frame.via();
+    if !via.is_empty() {
+        table.add_row(row!["\tVia:"]);
+        for hop in via {
+            table.add_row(row![format!("\t\t{}", hop)]);
+        }
+    }
+    // Authentication challenges are part of a normal exchange
+    if frame
+        .status()
+        .is_some_and(|status| status >= 400 && status != 401 && status != 407)
+    {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Warning, Group::Protocol, "SIP", "Error response"),
+        );
+    }
+    match frame.sdp() {
+        Some(Ok(sdp)) => {
+            state.rtp.learn(&sdp);
+            if let Some(connection) = &sdp.connection {
+                table.add_row(row!["\tSDP connection:", connection]);
+            }
+            table.add_row(row!["\tSDP media:"]);
+            for media in &sdp.media {
+                let endpoint = match sdp.endpoint(media) {
+                    Some(addr) => addr.to_string(),
+                    None => media.port.to_string(),
+                };
+                let codecs: Vec<_> = media
+                    .formats
+                    .iter()
+                    .map(|format| media.codec(format).unwrap_or_else(|| format.to_string()))
+                    .collect();
+                table.add_row(row![format!(
+                    "\t\t{} {} {}: {}",
+                    media.media,
+                    endpoint,
+                    media.proto,
+                    codecs.join(", ")
+                )]);
+            }
+        }
+        Some(Err(err)) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "SDP",
+                    "Malformed session description",
+                ),
+            );
+        }
+        None => {}
+    }
+    // The table only keeps the summary, the JSON output gets every header
+    if state.json {
+        table.add_row(row!["\tHeaders:"]);
+        for (name, value) in frame.headers() {
+            table.add_row(row![format!("\t\t{}: {}", name, value)]);
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_rtp(frame: RtpFrame, mut table: Table, state: &mut SniffState, event: Option<RtpEvent>) {
+    let payload_type = match rtp_static_payload(frame.payload_type()) {
+        Some((encoding, rate)) => format!("{} ({}/{})", frame.payload_type(), encoding, rate),
+        None => frame.payload_type().to_string(),
+    };
+    table.add_row(row![H2 -> "Real-time Transport Protocol"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    table.add_row(row!["\tPadding:", frame.padding()]);
+    table.add_row(row!["\tMarker:", frame.marker()]);
+    table.add_row(row!["\tPayload type:", payload_type]);
+    table.add_row(row!["\tSequence number:", frame.seq_num()]);
+    table.add_row(row!["\tTimestamp:", frame.timestamp()]);
+    table.add_row(row!["\tSSRC:", format!("0x{:08X}", frame.ssrc())]);
+    if frame.csrc_count() > 0 {
+        table.add_row(row!["\tCSRCs:"]);
+        for csrc in frame.csrcs() {
+            table.add_row(row![format!("\t\t0x{:08X}", csrc)]);
+        }
+    }
+    if let Some((profile, data)) = frame.extension() {
+        table.add_row(row![
+            "\tHeader extension:",
+            format!("0x{:04X} ({} bytes)", profile, data.len())
+        ]);
+    }
+    table.add_row(row!["\tPayload length:", frame.payload().len()]);
+    if let Some(event) = event {
+        state.add_expert(&mut table, event.expert_info());
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn add_rtcp_report_blocks(table: &mut Table, reports: &[RtcpReportBlock]) {
+    for report in reports {
+        table.add_row(row![format!(
+            "\t\tReport on 0x{:08X}: {}/256 lost, {} lost in total, highest sequence number {}, jitter {}",
+            report.ssrc,
+            report.fraction_lost,
+            report.cumulative_lost,
+            report.highest_seq,
+            report.jitter
+        )]);
+    }
+}
+
+fn handle_rtcp(frame: RtcpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Real-time Transport Control Protocol"]);
+    match frame.packets() {
+        Ok(packets) => {
+            for packet in packets {
+                table.add_row(row![format!("\t{}", packet)]);
+                match packet {
+                    RtcpPacket::SenderReport {
+                        ssrc,
+                        ntp_time: time,
+                        rtp_timestamp,
+                        packet_count,
+                        octet_count,
+                        reports,
+                    } => {
+                        table.add_row(row!["\t\tSSRC:", format!("0x{:08X}", ssrc)]);
+                        table.add_row(row!["\t\tNTP timestamp:", ntp_time(time)]);
+                        table.add_row(row!["\t\tRTP timestamp:", rtp_timestamp]);
+                        table.add_row(row!["\t\tPackets sent:", packet_count]);
+                        table.add_row(row!["\t\tOctets sent:", octet_count]);
+                        add_rtcp_report_blocks(&mut table, &reports);
+                    }
+                    RtcpPacket::ReceiverReport { ssrc, reports } => {
+                        table.add_row(row!["\t\tSSRC:", format!("0x{:08X}", ssrc)]);
+                        add_rtcp_report_blocks(&mut table, &reports);
+                    }
+                    RtcpPacket::SourceDescription(chunks) => {
+                        for chunk in chunks {
+                            let items: Vec<_> = chunk
+                                .items
+                                .iter()
+                                .map(|(item, value)| {
+                                    let name = sdes_item_name(*item)
+                                        .map_or_else(|| item.to_string(), String::from);
+                                    format!("{}={}", name, String::from_utf8_lossy(value))
+                                })
+                                .collect();
+                            table.add_row(row![format!(
+                                "\t\t0x{:08X}: {}",
+                                chunk.ssrc,
+                                items.join(", ")
+                            )]);
+                        }
+                    }
+                    RtcpPacket::Bye { ssrcs, reason } => {
+                        for ssrc in ssrcs {
+                            table.add_row(row![format!("\t\t0x{:08X}", ssrc)]);
+                        }
+                        if let Some(reason) = reason {
+                            table.add_row(row!["\t\tReason:", String::from_utf8_lossy(reason)]);
+                        }
+                    }
+                    RtcpPacket::Other(_, data) => {
+                        table.add_row(row!["\t\tLength:", data.len()]);
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "RTCP",
+                    "Malformed packet",
+                ),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_vxlan(frame: VxlanFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Virtual eXtensible Local Area Network"]);
+    table.add_row(row!["\tFlags:", format!("0x{:02X}", frame.flags())]);
+    table.add_row(row!["\tVNI:", frame.vni()]);
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tEncapsulated:", frame]);
+    handle_encapsulated(frame, table, state);
+}
+
+fn handle_geneve(frame: GeneveFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Generic Network Virtualization Encapsulation"]);
+    table.add_row(row!["\tVersion:", frame.version()]);
+    table.add_row(row!["\tOAM:", frame.is_oam()]);
+    table.add_row(row!["\tCritical options:", frame.has_critical_options()]);
+    table.add_row(row![
+        "\tProtocol type:",
+        format!("0x{:04X}", frame.protocol_type())
+    ]);
+    table.add_row(row!["\tVNI:", frame.vni()]);
+    match frame.options() {
+        Ok(options) if options.is_empty() => {}
+        Ok(options) => {
+            table.add_row(row!["\tOptions:"]);
+            for option in options {
+                table.add_row(row![format!("\t\t{}", option)]);
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "Geneve",
+                    "Malformed option",
+                ),
+            );
+        }
+    }
+    let frame = frame.try_next_header();
+    table.add_row(row!["\tEncapsulated:", frame]);
+    handle_encapsulated(frame, table, state);
+}
+
+fn handle_rip(frame: RipFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Routing Information Protocol"]);
+    table.add_row(row!["\tCommand:", frame.command()]);
+    if frame.is_ng() {
+        table.add_row(row!["\tVersion:", "RIPng"]);
+    } else {
+        table.add_row(row!["\tVersion:", frame.version()]);
+    }
+    table.add_row(row!["\tEntries:"]);
+    for entry in frame.entries() {
+        table.add_row(row![format!("\t\t{}", entry)]);
+    }
+    let password = frame
+        .entries()
+        .iter()
+        .any(|entry| matches!(entry, RipEntry::Authentication { auth_type: 2, .. }));
+    if password {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(
+                Severity::Warning,
+                Group::Security,
+                "RIP",
+                "Cleartext password",
+            ),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_bgp(frame: BgpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Border Gateway Protocol"]);
+    match frame.messages() {
+        Ok(messages) => {
+            for msg in &messages {
+                table.add_row(row![format!("\t{}", msg)]);
+                match msg {
+                    BgpMessage::Open { capabilities, .. } => {
+                        for capability in capabilities {
+                            table.add_row(row![format!("\t\t{}", capability)]);
+                        }
+                    }
+                    BgpMessage::Update {
+                        withdrawn,
+                        attributes,
+                        nlri,
+                    } => {
+                        for prefix in withdrawn {
+                            table.add_row(row![format!("\t\tWithdrawn: {}", prefix)]);
+                        }
+                        for attribute in attributes {
+                            table.add_row(row![format!("\t\t{}", attribute)]);
+                        }
+                        for prefix in nlri {
+                            table.add_row(row![format!("\t\tAnnounced: {}", prefix)]);
+                        }
+                    }
+                    BgpMessage::Notification { code, data, .. } => {
+                        if !data.is_empty() {
+                            table.add_row(row![format!("\t\tData: {}", to_hex(data))]);
+                        }
+                        state.add_expert(
+                            &mut table,
+                            ExpertInfo::new(
+                                Severity::Warning,
+                                Group::Sequence,
+                                "BGP",
+                                bgp_error_name(*code).unwrap_or("Unknown error"),
+                            ),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Err(err) => {
+            table.add_row(row!["\tError:", err]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Error,
+                    Group::Malformed,
+                    "BGP",
+                    "Malformed message",
+                ),
+            );
+        }
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
+}
+
+fn handle_hsrp(frame: HsrpFrame, mut table: Table, state: &mut SniffState) {
+    table.add_row(row![H2 -> "Hot Standby Router Protocol"]);
+    table.add_row(row![
+        "\tVersion:",
+        if frame.version() == 0 {
+            1
+        } else {
+            frame.version()
+        }
+    ]);
+    table.add_row(row!["\tOp code:", frame.op_code()]);
+    table.add_row(row!["\tState:", frame.state()]);
+    table.add_row(row!["\tGroup:", frame.group()]);
+    table.add_row(row!["\tPriority:", frame.priority()]);
+    table.add_row(row![
+        "\tHello time:",
+        format!("{} ms", frame.hello_time().as_millis())
+    ]);
+    table.add_row(row![
+        "\tHold time:",
+        format!("{} ms", frame.hold_time().as_millis())
+    ]);
+    if let Some(addr) = frame.virtual_ip() {
+        table.add_row(row!["\tVirtual IP address:", addr]);
+    }
+    if let Some(mac) = frame.identifier() {
+        table.add_row(row!["\tIdentifier:", mac]);
+    }
+    match frame.auth() {
+        HsrpAuth::None => {
+            table.add_row(row!["\tAuthentication:", "None"]);
+        }
+        HsrpAuth::Text(password) => {
+            let password = String::from_utf8_lossy(password);
+            table.add_row(row![
+                "\tAuthentication:",
+                format!("Password: {}", password.trim_end_matches('\0'))
+            ]);
+            state.add_expert(
+                &mut table,
+                ExpertInfo::new(
+                    Severity::Warning,
+                    Group::Security,
+                    "HSRP",
+                    "Cleartext password",
+                ),
+            );
+        }
+        HsrpAuth::Md5(_) => {
+            table.add_row(row!["\tAuthentication:", "MD5"]);
+        }
+    }
+    let summary = match frame.op_code() {
+        HsrpOpCode::Coup => Some("Router taking over as active"),
+        HsrpOpCode::Resign => Some("Active router resigning"),
+        _ => None,
+    };
+    if let Some(summary) = summary {
+        state.add_expert(
+            &mut table,
+            ExpertInfo::new(Severity::Note, Group::Protocol, "HSRP", summary),
+        );
+    }
+    table.add_empty_row();
+    table.add_empty_row();
+    table.add_empty_row();
+    state.print(&table);
 }
@@ -0,0 +1,515 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use super::*;
+
+const BGP_HEADER_LEN: usize = 19;
+const BGP_MARKER: [u8; 16] = [0xFF; 16];
+/// Flag of path attributes whose length is two bytes long.
+const EXTENDED_LENGTH: u8 = 0x10;
+const AFI_IPV4: u16 = 1;
+const AFI_IPV6: u16 = 2;
+
+/// Name of the error code `code` of a NOTIFICATION message.
+pub fn bgp_error_name(code: u8) -> Option<&'static str> {
+    let name = match code {
+        1 => "Message header error",
+        2 => "OPEN message error",
+        3 => "UPDATE message error",
+        4 => "Hold timer expired",
+        5 => "Finite state machine error",
+        6 => "Cease",
+        7 => "ROUTE-REFRESH message error",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Prefix of a route announced or withdrawn by an UPDATE message.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BgpPrefix {
+    pub addr: IpAddr,
+    pub len: u8,
+}
+
+impl fmt::Display for BgpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+/// Reads the prefixes of `reader`, encoded as a length in bits followed by the
+/// significant bytes of the address.
+fn read_prefixes(reader: &mut ByteReader, afi: u16) -> Result<Vec<BgpPrefix>, FrameError> {
+    let max_len = match afi {
+        AFI_IPV4 => 32,
+        _ => 128,
+    };
+    let mut prefixes = Vec::new();
+    while !reader.is_empty() {
+        let len = reader.u8()?;
+        if len > max_len {
+            return Err(reader.malformed("prefix longer than the address"));
+        }
+        let bytes = reader.bytes(usize::from(len).div_ceil(8))?;
+        let addr = match afi {
+            AFI_IPV4 => {
+                let mut addr = [0; 4];
+                addr[..bytes.len()].copy_from_slice(bytes);
+                IpAddr::from(addr)
+            }
+            _ => {
+                let mut addr = [0; 16];
+                addr[..bytes.len()].copy_from_slice(bytes);
+                IpAddr::from(addr)
+            }
+        };
+        prefixes.push(BgpPrefix { addr, len });
+    }
+    Ok(prefixes)
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum AsPathSegment {
+    Set(Vec<u32>),      // 1
+    Sequence(Vec<u32>), // 2
+    /// Confederation segments (RFC 5065), types 3 and 4.
+    Confederation(Vec<u32>),
+}
+
+impl fmt::Display for AsPathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |asns: &[u32]| {
+            asns.iter()
+                .map(|asn| asn.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match self {
+            AsPathSegment::Set(asns) => write!(f, "{{{}}}", join(asns)),
+            AsPathSegment::Sequence(asns) => write!(f, "{}", join(asns)),
+            AsPathSegment::Confederation(asns) => write!(f, "({})", join(asns)),
+        }
+    }
+}
+
+/// Reads the segments of an AS path whose AS numbers are `as_len` bytes long.
+fn read_as_path(value: &[u8], as_len: usize) -> Result<Vec<AsPathSegment>, FrameError> {
+    let mut reader = ByteReader::new(value, "AS path");
+    let mut segments = Vec::new();
+    while !reader.is_empty() {
+        let typ = reader.u8()?;
+        let count = reader.u8()?;
+        if count == 0 {
+            return Err(reader.malformed("empty segment"));
+        }
+        let mut asns = Vec::new();
+        for _ in 0..count {
+            asns.push(match as_len {
+                2 => u32::from(reader.u16()?),
+                _ => reader.u32()?,
+            });
+        }
+        segments.push(match typ {
+            1 => AsPathSegment::Set(asns),
+            2 => AsPathSegment::Sequence(asns),
+            3 | 4 => AsPathSegment::Confederation(asns),
+            _ => return Err(reader.malformed("unknown segment type")),
+        });
+    }
+    Ok(segments)
+}
+
+pub enum BgpAttribute<'a> {
+    /// 0 for IGP, 1 for EGP and 2 for incomplete.
+    Origin(u8), // 1
+    AsPath(Vec<AsPathSegment>), // 2 and 17 (AS4_PATH)
+    NextHop(Ipv4Addr),          // 3
+    MultiExitDisc(u32),         // 4
+    LocalPref(u32),             // 5
+    AtomicAggregate,            // 6
+    Aggregator(&'a [u8]),       // 7
+    /// Communities, each an AS number followed by a value (RFC 1997).
+    Communities(Vec<u32>), // 8
+    /// Routes of another address family (RFC 4760).
+    MpReachNlri {
+        afi: u16,
+        safi: u8,
+        next_hops: Vec<IpAddr>,
+        nlri: Vec<BgpPrefix>,
+    }, // 14
+    MpUnreachNlri {
+        afi: u16,
+        safi: u8,
+        withdrawn: Vec<BgpPrefix>,
+    }, // 15
+    Other {
+        flags: u8,
+        typ: u8,
+        value: &'a [u8],
+    },
+}
+
+impl<'a> fmt::Display for BgpAttribute<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |items: Vec<String>| items.join(", ");
+        match self {
+            BgpAttribute::Origin(0) => write!(f, "Origin: IGP"),
+            BgpAttribute::Origin(1) => write!(f, "Origin: EGP"),
+            BgpAttribute::Origin(_) => write!(f, "Origin: incomplete"),
+            BgpAttribute::AsPath(segments) => write!(
+                f,
+                "AS path: {}",
+                segments
+                    .iter()
+                    .map(|segment| segment.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            BgpAttribute::NextHop(addr) => write!(f, "Next hop: {}", addr),
+            BgpAttribute::MultiExitDisc(med) => write!(f, "MED: {}", med),
+            BgpAttribute::LocalPref(pref) => write!(f, "Local preference: {}", pref),
+            BgpAttribute::AtomicAggregate => write!(f, "Atomic aggregate"),
+            BgpAttribute::Aggregator(value) => write!(f, "Aggregator: {}", to_hex(value)),
+            BgpAttribute::Communities(communities) => write!(
+                f,
+                "Communities: {}",
+                join(
+                    communities
+                        .iter()
+                        .map(|community| format!("{}:{}", community >> 16, community & 0xFFFF))
+                        .collect()
+                )
+            ),
+            BgpAttribute::MpReachNlri {
+                afi,
+                safi,
+                next_hops,
+                nlri,
+            } => write!(
+                f,
+                "MP reach (AFI {}, SAFI {}): {} via {}",
+                afi,
+                safi,
+                join(nlri.iter().map(|prefix| prefix.to_string()).collect()),
+                join(next_hops.iter().map(|addr| addr.to_string()).collect())
+            ),
+            BgpAttribute::MpUnreachNlri {
+                afi,
+                safi,
+                withdrawn,
+            } => write!(
+                f,
+                "MP unreach (AFI {}, SAFI {}): {}",
+                afi,
+                safi,
+                join(withdrawn.iter().map(|prefix| prefix.to_string()).collect())
+            ),
+            BgpAttribute::Other { typ, value, .. } => {
+                write!(f, "Attribute {}: {}", typ, to_hex(value))
+            }
+        }
+    }
+}
+
+impl<'a> BgpAttribute<'a> {
+    fn read(flags: u8, typ: u8, value: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(value, "BGP path attribute");
+        let attribute = match typ {
+            1 => BgpAttribute::Origin(reader.u8()?),
+            // Whether AS numbers are 2 or 4 bytes long was negotiated by the OPEN
+            // messages, so the 2 byte encoding is assumed when it parses exactly
+            2 => BgpAttribute::AsPath(read_as_path(value, 2).or_else(|_| read_as_path(value, 4))?),
+            3 => BgpAttribute::NextHop(Ipv4Addr::from(reader.u32()?)),
+            4 => BgpAttribute::MultiExitDisc(reader.u32()?),
+            5 => BgpAttribute::LocalPref(reader.u32()?),
+            6 => BgpAttribute::AtomicAggregate,
+            7 => BgpAttribute::Aggregator(value),
+            8 => {
+                let mut communities = Vec::new();
+                while !reader.is_empty() {
+                    communities.push(reader.u32()?);
+                }
+                BgpAttribute::Communities(communities)
+            }
+            14 => {
+                let afi = reader.u16()?;
+                let safi = reader.u8()?;
+                let len = usize::from(reader.u8()?);
+                let next_hops = reader.bytes(len)?;
+                // IPv6 next hops may be followed by a link-local address
+                let next_hops = match afi {
+                    AFI_IPV4 => next_hops
+                        .chunks_exact(4)
+                        .map(|addr| IpAddr::from(clone_into_array::<[u8; 4], u8>(addr)))
+                        .collect(),
+                    AFI_IPV6 => next_hops
+                        .chunks_exact(16)
+                        .map(|addr| IpAddr::from(clone_into_array::<[u8; 16], u8>(addr)))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                reader.u8()?;
+                let nlri = match afi {
+                    AFI_IPV4 | AFI_IPV6 => read_prefixes(&mut reader, afi)?,
+                    _ => Vec::new(),
+                };
+                BgpAttribute::MpReachNlri {
+                    afi,
+                    safi,
+                    next_hops,
+                    nlri,
+                }
+            }
+            15 => {
+                let afi = reader.u16()?;
+                let safi = reader.u8()?;
+                let withdrawn = match afi {
+                    AFI_IPV4 | AFI_IPV6 => read_prefixes(&mut reader, afi)?,
+                    _ => Vec::new(),
+                };
+                BgpAttribute::MpUnreachNlri {
+                    afi,
+                    safi,
+                    withdrawn,
+                }
+            }
+            17 => BgpAttribute::AsPath(read_as_path(value, 4)?),
+            typ => BgpAttribute::Other { flags, typ, value },
+        };
+        Ok(attribute)
+    }
+}
+
+/// Capability advertised by an OPEN message (RFC 5492).
+pub struct BgpCapability<'a> {
+    pub code: u8,
+    pub value: &'a [u8],
+}
+
+impl<'a> fmt::Display for BgpCapability<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.code, self.value) {
+            (1, [afi_high, afi_low, _, safi]) => write!(
+                f,
+                "Multiprotocol (AFI {}, SAFI {})",
+                u16::from_be_bytes([*afi_high, *afi_low]),
+                safi
+            ),
+            (2, _) => write!(f, "Route refresh"),
+            (64, _) => write!(f, "Graceful restart"),
+            (65, [a, b, c, d]) => {
+                write!(
+                    f,
+                    "4-octet AS number: {}",
+                    u32::from_be_bytes([*a, *b, *c, *d])
+                )
+            }
+            (69, _) => write!(f, "ADD-PATH"),
+            (70, _) => write!(f, "Enhanced route refresh"),
+            (code, value) => write!(f, "Capability {}: {}", code, to_hex(value)),
+        }
+    }
+}
+
+pub enum BgpMessage<'a> {
+    Open {
+        version: u8,
+        /// AS number of the sender, 23456 (AS_TRANS) when it is 4 bytes long.
+        my_as: u16,
+        hold_time: u16,
+        bgp_id: Ipv4Addr,
+        capabilities: Vec<BgpCapability<'a>>,
+    }, // 1
+    Update {
+        withdrawn: Vec<BgpPrefix>,
+        attributes: Vec<BgpAttribute<'a>>,
+        nlri: Vec<BgpPrefix>,
+    }, // 2
+    Notification {
+        code: u8,
+        subcode: u8,
+        data: &'a [u8],
+    }, // 3
+    Keepalive, // 4
+    RouteRefresh {
+        afi: u16,
+        safi: u8,
+    }, // 5
+    Other(u8, &'a [u8]),
+}
+
+impl<'a> fmt::Display for BgpMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BgpMessage::Open {
+                version,
+                my_as,
+                hold_time,
+                bgp_id,
+                ..
+            } => write!(
+                f,
+                "OPEN (version {}, AS {}, hold time {} s, ID {})",
+                version, my_as, hold_time, bgp_id
+            ),
+            BgpMessage::Update {
+                withdrawn,
+                attributes,
+                nlri,
+            } => write!(
+                f,
+                "UPDATE ({} withdrawn, {} attributes, {} announced)",
+                withdrawn.len(),
+                attributes.len(),
+                nlri.len()
+            ),
+            BgpMessage::Notification { code, subcode, .. } => {
+                write!(f, "NOTIFICATION: ")?;
+                match bgp_error_name(*code) {
+                    Some(name) => write!(f, "{}", name)?,
+                    None => write!(f, "error {}", code)?,
+                }
+                write!(f, " (subcode {})", subcode)
+            }
+            BgpMessage::Keepalive => write!(f, "KEEPALIVE"),
+            BgpMessage::RouteRefresh { afi, safi } => {
+                write!(f, "ROUTE-REFRESH (AFI {}, SAFI {})", afi, safi)
+            }
+            BgpMessage::Other(typ, _) => write!(f, "Unknown message ({})", typ),
+        }
+    }
+}
+
+impl<'a> BgpMessage<'a> {
+    fn read(typ: u8, body: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(body, "BGP message");
+        let message = match typ {
+            1 => {
+                let version = reader.u8()?;
+                let my_as = reader.u16()?;
+                let hold_time = reader.u16()?;
+                let bgp_id = Ipv4Addr::from(reader.u32()?);
+                let len = usize::from(reader.u8()?);
+                let mut params = reader.sub(len)?;
+                let mut capabilities = Vec::new();
+                while !params.is_empty() {
+                    let param_type = params.u8()?;
+                    let len = usize::from(params.u8()?);
+                    let mut value = params.sub(len)?;
+                    // Only capabilities are still in use among the optional parameters
+                    if param_type != 2 {
+                        continue;
+                    }
+                    while !value.is_empty() {
+                        let code = value.u8()?;
+                        let len = usize::from(value.u8()?);
+                        capabilities.push(BgpCapability {
+                            code,
+                            value: value.bytes(len)?,
+                        });
+                    }
+                }
+                BgpMessage::Open {
+                    version,
+                    my_as,
+                    hold_time,
+                    bgp_id,
+                    capabilities,
+                }
+            }
+            2 => {
+                let len = usize::from(reader.u16()?);
+                let withdrawn = read_prefixes(&mut reader.sub(len)?, AFI_IPV4)?;
+                let len = usize::from(reader.u16()?);
+                let mut attrs = reader.sub(len)?;
+                let mut attributes = Vec::new();
+                while !attrs.is_empty() {
+                    let flags = attrs.u8()?;
+                    let typ = attrs.u8()?;
+                    let len = match flags & EXTENDED_LENGTH {
+                        0 => usize::from(attrs.u8()?),
+                        _ => usize::from(attrs.u16()?),
+                    };
+                    attributes.push(BgpAttribute::read(flags, typ, attrs.bytes(len)?)?);
+                }
+                BgpMessage::Update {
+                    withdrawn,
+                    attributes,
+                    nlri: read_prefixes(&mut reader, AFI_IPV4)?,
+                }
+            }
+            3 => BgpMessage::Notification {
+                code: reader.u8()?,
+                subcode: reader.u8()?,
+                data: reader.rest(),
+            },
+            4 => BgpMessage::Keepalive,
+            5 => {
+                let afi = reader.u16()?;
+                reader.u8()?;
+                BgpMessage::RouteRefresh {
+                    afi,
+                    safi: reader.u8()?,
+                }
+            }
+            other => BgpMessage::Other(other, body),
+        };
+        Ok(message)
+    }
+}
+
+/// Border Gateway Protocol messages (RFC 4271) starting at the beginning of a TCP
+/// payload.
+pub struct BgpFrame<'a>(&'a [u8]);
+
+impl<'a> BgpFrame<'a> {
+    /// Whether `data` begins with the marker of a message header, which tells the
+    /// start of a message apart from its continuation.
+    pub fn is_bgp(data: &[u8]) -> bool {
+        data.starts_with(&BGP_MARKER)
+    }
+
+    /// Messages in the payload, omitting the last one if it continues in the next
+    /// segment.
+    pub fn messages(&self) -> Result<Vec<BgpMessage<'a>>, FrameError> {
+        let mut reader = ByteReader::new(self.0, "BGP header");
+        let mut messages = Vec::new();
+        while reader.remaining() >= BGP_HEADER_LEN && Self::is_bgp(&self.0[reader.pos()..]) {
+            let start = reader.pos();
+            reader.bytes(BGP_MARKER.len())?;
+            let len = usize::from(reader.u16()?);
+            let typ = reader.u8()?;
+            if len < BGP_HEADER_LEN {
+                return Err(reader.malformed("length shorter than the header"));
+            }
+            match reader.bytes(len - BGP_HEADER_LEN) {
+                Ok(body) => messages.push(BgpMessage::read(typ, body)?),
+                Err(_) => {
+                    reader.seek(start)?;
+                    break;
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for BgpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let reader = ByteReader::new(slice, "BGP header");
+        if !Self::is_bgp(slice) {
+            return Err(reader.malformed("missing marker"));
+        }
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for BgpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -0,0 +1,288 @@
+use std::net::Ipv4Addr;
+
+use crate::netframe::datalink::HwAddr;
+
+use super::*;
+
+/// Length of the fixed BOOTP header, up to the `file` field included.
+const BOOTP_LEN: usize = 236;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DhcpMessageType {
+    Discover, // 1
+    Offer,    // 2
+    Request,  // 3
+    Decline,  // 4
+    Ack,      // 5
+    Nak,      // 6
+    Release,  // 7
+    Inform,   // 8
+    Other(u8),
+}
+
+impl From<u8> for DhcpMessageType {
+    fn from(val: u8) -> Self {
+        match val {
+            1 => Self::Discover,
+            2 => Self::Offer,
+            3 => Self::Request,
+            4 => Self::Decline,
+            5 => Self::Ack,
+            6 => Self::Nak,
+            7 => Self::Release,
+            8 => Self::Inform,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for DhcpMessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::Discover => "Discover",
+            Self::Offer => "Offer",
+            Self::Request => "Request",
+            Self::Decline => "Decline",
+            Self::Ack => "ACK",
+            Self::Nak => "NAK",
+            Self::Release => "Release",
+            Self::Inform => "Inform",
+            Self::Other(val) => return write!(f, "Unknown ({})", val),
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+pub enum DhcpOption<'a> {
+    SubnetMask(Ipv4Addr),                // 1
+    Router(Vec<Ipv4Addr>),               // 3
+    DnsServers(Vec<Ipv4Addr>),           // 6
+    HostName(String),                    // 12
+    DomainName(String),                  // 15
+    RequestedIp(Ipv4Addr),               // 50
+    LeaseTime(u32),                      // 51
+    MessageType(DhcpMessageType),        // 53
+    ServerId(Ipv4Addr),                  // 54
+    ParameterRequestList(Vec<u8>),       // 55
+    ClientId(u8, &'a [u8]),              // 61
+    RelayAgentInfo(Vec<(u8, &'a [u8])>), // 82
+    Other(u8, &'a [u8]),
+}
+
+fn addr_list(addrs: &[Ipv4Addr]) -> String {
+    let addrs: Vec<_> = addrs.iter().map(Ipv4Addr::to_string).collect();
+    addrs.join(", ")
+}
+
+impl<'a> fmt::Display for DhcpOption<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SubnetMask(mask) => write!(f, "(1) Subnet mask: {}", mask),
+            Self::Router(addrs) => write!(f, "(3) Router: {}", addr_list(addrs)),
+            Self::DnsServers(addrs) => write!(f, "(6) Domain name server: {}", addr_list(addrs)),
+            Self::HostName(name) => write!(f, "(12) Host name: {}", name),
+            Self::DomainName(name) => write!(f, "(15) Domain name: {}", name),
+            Self::RequestedIp(addr) => write!(f, "(50) Requested IP address: {}", addr),
+            Self::LeaseTime(secs) => write!(f, "(51) IP address lease time: {} s", secs),
+            Self::MessageType(typ) => write!(f, "(53) DHCP message type: {}", typ),
+            Self::ServerId(addr) => write!(f, "(54) Server identifier: {}", addr),
+            Self::ParameterRequestList(codes) => {
+                let codes: Vec<_> = codes.iter().map(u8::to_string).collect();
+                write!(f, "(55) Parameter request list: {}", codes.join(", "))
+            }
+            Self::ClientId(1, id) if id.len() == 6 => {
+                write!(f, "(61) Client identifier: {}", HwAddr::from(*id))
+            }
+            Self::ClientId(htype, id) => {
+                write!(f, "(61) Client identifier: type {}, {}", htype, to_hex(id))
+            }
+            Self::RelayAgentInfo(subopts) => {
+                write!(f, "(82) Relay agent information:")?;
+                for (code, value) in subopts {
+                    match code {
+                        1 => write!(f, " circuit ID {}", to_hex(value))?,
+                        2 => write!(f, " remote ID {}", to_hex(value))?,
+                        other => write!(f, " suboption {} {}", other, to_hex(value))?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Other(code, value) => write!(f, "({}) {}", code, to_hex(value)),
+        }
+    }
+}
+
+fn addrs(reader: &mut ByteReader) -> Result<Vec<Ipv4Addr>, FrameError> {
+    if reader.remaining() % 4 != 0 {
+        return Err(reader.malformed("address list length is not a multiple of 4"));
+    }
+    let mut addrs = Vec::new();
+    while !reader.is_empty() {
+        addrs.push(Ipv4Addr::from(reader.u32()?));
+    }
+    Ok(addrs)
+}
+
+fn addr(reader: &mut ByteReader) -> Result<Ipv4Addr, FrameError> {
+    match addrs(reader)?.as_slice() {
+        [addr] => Ok(*addr),
+        _ => Err(reader.malformed("expected a single address")),
+    }
+}
+
+fn read_option<'a>(code: u8, value: &'a [u8]) -> Result<DhcpOption<'a>, FrameError> {
+    let mut reader = ByteReader::new(value, "DHCP option");
+    Ok(match code {
+        1 => DhcpOption::SubnetMask(addr(&mut reader)?),
+        3 => DhcpOption::Router(addrs(&mut reader)?),
+        6 => DhcpOption::DnsServers(addrs(&mut reader)?),
+        12 => DhcpOption::HostName(String::from_utf8_lossy(value).into_owned()),
+        15 => DhcpOption::DomainName(String::from_utf8_lossy(value).into_owned()),
+        50 => DhcpOption::RequestedIp(addr(&mut reader)?),
+        51 => DhcpOption::LeaseTime(reader.u32()?),
+        53 => DhcpOption::MessageType(DhcpMessageType::from(reader.u8()?)),
+        54 => DhcpOption::ServerId(addr(&mut reader)?),
+        55 => DhcpOption::ParameterRequestList(value.to_vec()),
+        61 => DhcpOption::ClientId(reader.u8()?, reader.rest()),
+        82 => {
+            let mut subopts = Vec::new();
+            while !reader.is_empty() {
+                let code = reader.u8()?;
+                let len = usize::from(reader.u8()?);
+                subopts.push((code, reader.bytes(len)?));
+            }
+            DhcpOption::RelayAgentInfo(subopts)
+        }
+        other => DhcpOption::Other(other, value),
+    })
+}
+
+pub struct DhcpFrame<'a>(&'a [u8]);
+
+impl<'a> DhcpFrame<'a> {
+    /// 1 for requests from clients, 2 for replies from servers.
+    pub fn op(&self) -> u8 {
+        self.0[0]
+    }
+
+    pub fn htype(&self) -> u8 {
+        self.0[1]
+    }
+
+    pub fn hlen(&self) -> u8 {
+        self.0[2]
+    }
+
+    pub fn hops(&self) -> u8 {
+        self.0[3]
+    }
+
+    pub fn xid(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.0[4..8]))
+    }
+
+    pub fn secs(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[8..10]))
+    }
+
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[10..12]))
+    }
+
+    pub fn broadcast(&self) -> bool {
+        self.0[10] & 0b1000_0000 != 0
+    }
+
+    pub fn ciaddr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.0[12..16]))
+    }
+
+    pub fn yiaddr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.0[16..20]))
+    }
+
+    pub fn siaddr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.0[20..24]))
+    }
+
+    pub fn giaddr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.0[24..28]))
+    }
+
+    pub fn raw_chaddr(&self) -> &'a [u8] {
+        &self.0[28..28 + usize::from(self.hlen().min(16))]
+    }
+
+    /// Client hardware address, if it is an Ethernet address.
+    pub fn chaddr(&self) -> Option<HwAddr> {
+        if self.htype() == 1 && self.hlen() == 6 {
+            Some(HwAddr::from(&self.0[28..34]))
+        } else {
+            None
+        }
+    }
+
+    pub fn sname(&self) -> String {
+        c_string(&self.0[44..108])
+    }
+
+    pub fn file(&self) -> String {
+        c_string(&self.0[108..236])
+    }
+
+    /// Whether the message carries DHCP options after the BOOTP header.
+    pub fn is_dhcp(&self) -> bool {
+        self.0.len() >= BOOTP_LEN + 4 && self.0[BOOTP_LEN..BOOTP_LEN + 4] == MAGIC_COOKIE
+    }
+
+    pub fn options(&self) -> Result<Vec<DhcpOption<'a>>, FrameError> {
+        if !self.is_dhcp() {
+            return Ok(Vec::new());
+        }
+        let mut reader = ByteReader::new(&self.0[BOOTP_LEN + 4..], "DHCP options");
+        let mut options = Vec::new();
+        while !reader.is_empty() {
+            match reader.u8()? {
+                0 => continue,
+                255 => break,
+                code => {
+                    let len = usize::from(reader.u8()?);
+                    options.push(read_option(code, reader.bytes(len)?)?);
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// Value of the DHCP message type option.
+    pub fn message_type(&self) -> Option<DhcpMessageType> {
+        self.options().ok()?.into_iter().find_map(|opt| match opt {
+            DhcpOption::MessageType(typ) => Some(typ),
+            _ => None,
+        })
+    }
+}
+
+fn c_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&c| c == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+impl<'a> TryFrom<&'a [u8]> for DhcpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "BOOTP header");
+        reader.bytes(BOOTP_LEN)?;
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for DhcpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -0,0 +1,491 @@
+use std::net::Ipv6Addr;
+
+use crate::netframe::datalink::HwAddr;
+
+use super::dns_frame::read_name;
+use super::*;
+
+/// Relay agents drop messages that went through more relays than this (RFC 8415).
+const HOP_COUNT_LIMIT: u8 = 32;
+/// Nesting levels of options and relay messages parsed before giving up, leaving room
+/// for the options of the deepest relayed message.
+const MAX_DEPTH: u8 = HOP_COUNT_LIMIT + 8;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Dhcpv6MessageType {
+    Solicit,            // 1
+    Advertise,          // 2
+    Request,            // 3
+    Confirm,            // 4
+    Renew,              // 5
+    Rebind,             // 6
+    Reply,              // 7
+    Release,            // 8
+    Decline,            // 9
+    Reconfigure,        // 10
+    InformationRequest, // 11
+    RelayForward,       // 12
+    RelayReply,         // 13
+    Other(u8),
+}
+
+impl From<u8> for Dhcpv6MessageType {
+    fn from(val: u8) -> Self {
+        match val {
+            1 => Self::Solicit,
+            2 => Self::Advertise,
+            3 => Self::Request,
+            4 => Self::Confirm,
+            5 => Self::Renew,
+            6 => Self::Rebind,
+            7 => Self::Reply,
+            8 => Self::Release,
+            9 => Self::Decline,
+            10 => Self::Reconfigure,
+            11 => Self::InformationRequest,
+            12 => Self::RelayForward,
+            13 => Self::RelayReply,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for Dhcpv6MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::Solicit => "Solicit",
+            Self::Advertise => "Advertise",
+            Self::Request => "Request",
+            Self::Confirm => "Confirm",
+            Self::Renew => "Renew",
+            Self::Rebind => "Rebind",
+            Self::Reply => "Reply",
+            Self::Release => "Release",
+            Self::Decline => "Decline",
+            Self::Reconfigure => "Reconfigure",
+            Self::InformationRequest => "Information-request",
+            Self::RelayForward => "Relay-forward",
+            Self::RelayReply => "Relay-reply",
+            Self::Other(val) => return write!(f, "Unknown ({})", val),
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+/// DHCP Unique Identifier of a client or server.
+pub enum Duid<'a> {
+    /// Link-layer address plus time, in seconds since 2000-01-01 UTC.
+    LinkLayerTime {
+        htype: u16,
+        time: u32,
+        addr: &'a [u8],
+    }, // 1
+    EnterpriseNumber {
+        enterprise: u32,
+        id: &'a [u8],
+    }, // 2
+    LinkLayer {
+        htype: u16,
+        addr: &'a [u8],
+    }, // 3
+    Uuid(&'a [u8]), // 4
+    Other(u16, &'a [u8]),
+}
+
+impl<'a> Duid<'a> {
+    fn read(data: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(data, "DUID");
+        Ok(match reader.u16()? {
+            1 => Self::LinkLayerTime {
+                htype: reader.u16()?,
+                time: reader.u32()?,
+                addr: reader.rest(),
+            },
+            2 => Self::EnterpriseNumber {
+                enterprise: reader.u32()?,
+                id: reader.rest(),
+            },
+            3 => Self::LinkLayer {
+                htype: reader.u16()?,
+                addr: reader.rest(),
+            },
+            4 => Self::Uuid(reader.bytes(16)?),
+            other => Self::Other(other, reader.rest()),
+        })
+    }
+}
+
+fn link_layer_addr(htype: u16, addr: &[u8]) -> String {
+    match (htype, addr.len()) {
+        (1, 6) => HwAddr::from(addr).to_string(),
+        _ => to_hex(addr),
+    }
+}
+
+impl<'a> fmt::Display for Duid<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LinkLayerTime { htype, time, addr } => write!(
+                f,
+                "DUID-LLT, time {}, {}",
+                time,
+                link_layer_addr(*htype, addr)
+            ),
+            Self::EnterpriseNumber { enterprise, id } => {
+                write!(f, "DUID-EN, enterprise {}, {}", enterprise, to_hex(id))
+            }
+            Self::LinkLayer { htype, addr } => {
+                write!(f, "DUID-LL, {}", link_layer_addr(*htype, addr))
+            }
+            Self::Uuid(uuid) => write!(f, "DUID-UUID, {}", to_hex(uuid)),
+            Self::Other(typ, value) => write!(f, "DUID type {}, {}", typ, to_hex(value)),
+        }
+    }
+}
+
+pub fn status_code_name(code: u16) -> &'static str {
+    match code {
+        0 => "Success",
+        1 => "UnspecFail",
+        2 => "NoAddrsAvail",
+        3 => "NoBinding",
+        4 => "NotOnLink",
+        5 => "UseMulticast",
+        6 => "NoPrefixAvail",
+        _ => "Unknown",
+    }
+}
+
+pub enum Dhcpv6Option<'a> {
+    ClientId(Duid<'a>), // 1
+    ServerId(Duid<'a>), // 2
+    IaNa {
+        iaid: u32,
+        t1: u32,
+        t2: u32,
+        options: Vec<Dhcpv6Option<'a>>,
+    }, // 3
+    IaTa {
+        iaid: u32,
+        options: Vec<Dhcpv6Option<'a>>,
+    }, // 4
+    IaAddr {
+        addr: Ipv6Addr,
+        preferred: u32,
+        valid: u32,
+        options: Vec<Dhcpv6Option<'a>>,
+    }, // 5
+    OptionRequest(Vec<u16>), // 6
+    Preference(u8),     // 7
+    /// Time since the client began the exchange, in hundredths of a second.
+    ElapsedTime(u16), // 8
+    RelayMessage(Dhcpv6Frame<'a>), // 9
+    StatusCode {
+        code: u16,
+        message: String,
+    }, // 13
+    RapidCommit,        // 14
+    InterfaceId(&'a [u8]), // 18
+    DnsServers(Vec<Ipv6Addr>), // 23
+    DomainList(Vec<String>), // 24
+    IaPd {
+        iaid: u32,
+        t1: u32,
+        t2: u32,
+        options: Vec<Dhcpv6Option<'a>>,
+    }, // 25
+    IaPrefix {
+        preferred: u32,
+        valid: u32,
+        prefix_len: u8,
+        prefix: Ipv6Addr,
+        options: Vec<Dhcpv6Option<'a>>,
+    }, // 26
+    Other(u16, &'a [u8]),
+}
+
+impl<'a> Dhcpv6Option<'a> {
+    /// Options encapsulated by an IA or an address/prefix option.
+    pub fn sub_options(&self) -> &[Dhcpv6Option<'a>] {
+        match self {
+            Self::IaNa { options, .. }
+            | Self::IaTa { options, .. }
+            | Self::IaAddr { options, .. }
+            | Self::IaPd { options, .. }
+            | Self::IaPrefix { options, .. } => options,
+            _ => &[],
+        }
+    }
+}
+
+/// Only the option itself is written; encapsulated options are left to
+/// [`Dhcpv6Option::sub_options`].
+impl<'a> fmt::Display for Dhcpv6Option<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClientId(duid) => write!(f, "(1) Client identifier: {}", duid),
+            Self::ServerId(duid) => write!(f, "(2) Server identifier: {}", duid),
+            Self::IaNa { iaid, t1, t2, .. } => write!(
+                f,
+                "(3) Identity association for non-temporary addresses: IAID 0x{:08x}, T1 {} s, T2 {} s",
+                iaid, t1, t2
+            ),
+            Self::IaTa { iaid, .. } => write!(
+                f,
+                "(4) Identity association for temporary addresses: IAID 0x{:08x}",
+                iaid
+            ),
+            Self::IaAddr {
+                addr,
+                preferred,
+                valid,
+                ..
+            } => write!(
+                f,
+                "(5) IA address: {}, preferred lifetime {} s, valid lifetime {} s",
+                addr, preferred, valid
+            ),
+            Self::OptionRequest(codes) => {
+                let codes: Vec<_> = codes.iter().map(u16::to_string).collect();
+                write!(f, "(6) Option request: {}", codes.join(", "))
+            }
+            Self::Preference(pref) => write!(f, "(7) Preference: {}", pref),
+            Self::ElapsedTime(time) => write!(f, "(8) Elapsed time: {} ms", u32::from(*time) * 10),
+            Self::RelayMessage(frame) => write!(f, "(9) Relay message: {}", frame.msg_type()),
+            Self::StatusCode { code, message } => write!(
+                f,
+                "(13) Status code: {} ({}) {}",
+                status_code_name(*code),
+                code,
+                message
+            ),
+            Self::RapidCommit => write!(f, "(14) Rapid commit"),
+            Self::InterfaceId(id) => write!(f, "(18) Interface ID: {}", to_hex(id)),
+            Self::DnsServers(addrs) => {
+                let addrs: Vec<_> = addrs.iter().map(Ipv6Addr::to_string).collect();
+                write!(f, "(23) DNS recursive name server: {}", addrs.join(", "))
+            }
+            Self::DomainList(names) => write!(f, "(24) Domain search list: {}", names.join(", ")),
+            Self::IaPd { iaid, t1, t2, .. } => write!(
+                f,
+                "(25) Identity association for prefix delegation: IAID 0x{:08x}, T1 {} s, T2 {} s",
+                iaid, t1, t2
+            ),
+            Self::IaPrefix {
+                preferred,
+                valid,
+                prefix_len,
+                prefix,
+                ..
+            } => write!(
+                f,
+                "(26) IA prefix: {}/{}, preferred lifetime {} s, valid lifetime {} s",
+                prefix, prefix_len, preferred, valid
+            ),
+            Self::Other(code, value) => write!(f, "({}) {}", code, to_hex(value)),
+        }
+    }
+}
+
+fn read_addr(reader: &mut ByteReader) -> Result<Ipv6Addr, FrameError> {
+    Ok(Ipv6Addr::from(clone_into_array::<[u8; 16], u8>(
+        reader.bytes(16)?,
+    )))
+}
+
+fn read_options<'a>(data: &'a [u8], depth: u8) -> Result<Vec<Dhcpv6Option<'a>>, FrameError> {
+    let mut reader = ByteReader::new(data, "DHCPv6 options");
+    let mut options = Vec::new();
+    while !reader.is_empty() {
+        let code = reader.u16()?;
+        let len = usize::from(reader.u16()?);
+        let value = reader.bytes(len)?;
+        options.push(read_option(code, value, depth)?);
+    }
+    Ok(options)
+}
+
+/// Reads the options encapsulated in an option at the nesting level `depth`.
+fn read_nested<'a>(
+    reader: &mut ByteReader<'a>,
+    depth: u8,
+) -> Result<Vec<Dhcpv6Option<'a>>, FrameError> {
+    if depth >= MAX_DEPTH {
+        return Err(reader.malformed("too many nested options"));
+    }
+    read_options(reader.rest(), depth + 1)
+}
+
+fn read_option<'a>(code: u16, value: &'a [u8], depth: u8) -> Result<Dhcpv6Option<'a>, FrameError> {
+    let mut reader = ByteReader::new(value, "DHCPv6 option");
+    Ok(match code {
+        1 => Dhcpv6Option::ClientId(Duid::read(value)?),
+        2 => Dhcpv6Option::ServerId(Duid::read(value)?),
+        3 => Dhcpv6Option::IaNa {
+            iaid: reader.u32()?,
+            t1: reader.u32()?,
+            t2: reader.u32()?,
+            options: read_nested(&mut reader, depth)?,
+        },
+        4 => Dhcpv6Option::IaTa {
+            iaid: reader.u32()?,
+            options: read_nested(&mut reader, depth)?,
+        },
+        5 => Dhcpv6Option::IaAddr {
+            addr: read_addr(&mut reader)?,
+            preferred: reader.u32()?,
+            valid: reader.u32()?,
+            options: read_nested(&mut reader, depth)?,
+        },
+        6 => {
+            let mut codes = Vec::new();
+            while !reader.is_empty() {
+                codes.push(reader.u16()?);
+            }
+            Dhcpv6Option::OptionRequest(codes)
+        }
+        7 => Dhcpv6Option::Preference(reader.u8()?),
+        8 => Dhcpv6Option::ElapsedTime(reader.u16()?),
+        9 => {
+            if depth >= HOP_COUNT_LIMIT {
+                return Err(reader.malformed("too many nested relay messages"));
+            }
+            Dhcpv6Option::RelayMessage(Dhcpv6Frame::parse(value, depth + 1)?)
+        }
+        13 => Dhcpv6Option::StatusCode {
+            code: reader.u16()?,
+            message: String::from_utf8_lossy(reader.rest()).into_owned(),
+        },
+        14 => Dhcpv6Option::RapidCommit,
+        18 => Dhcpv6Option::InterfaceId(value),
+        23 => {
+            let mut addrs = Vec::new();
+            while !reader.is_empty() {
+                addrs.push(read_addr(&mut reader)?);
+            }
+            Dhcpv6Option::DnsServers(addrs)
+        }
+        24 => {
+            // Names are DNS encoded, without compression
+            let mut names = Vec::new();
+            while !reader.is_empty() {
+                names.push(read_name(&mut reader)?);
+            }
+            Dhcpv6Option::DomainList(names)
+        }
+        25 => Dhcpv6Option::IaPd {
+            iaid: reader.u32()?,
+            t1: reader.u32()?,
+            t2: reader.u32()?,
+            options: read_nested(&mut reader, depth)?,
+        },
+        26 => Dhcpv6Option::IaPrefix {
+            preferred: reader.u32()?,
+            valid: reader.u32()?,
+            prefix_len: reader.u8()?,
+            prefix: read_addr(&mut reader)?,
+            options: read_nested(&mut reader, depth)?,
+        },
+        other => Dhcpv6Option::Other(other, value),
+    })
+}
+
+/// DHCPv6 message, either exchanged between a client and a server or
+/// encapsulated by a relay agent.
+pub struct Dhcpv6Frame<'a> {
+    data: &'a [u8],
+    // Relay messages enclosing this one
+    depth: u8,
+}
+
+impl<'a> Dhcpv6Frame<'a> {
+    fn parse(data: &'a [u8], depth: u8) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(data, "DHCPv6 header");
+        let header_len = if Self::is_relay_type(reader.u8()?) {
+            34
+        } else {
+            4
+        };
+        reader.seek(header_len)?;
+        Ok(Self { data, depth })
+    }
+
+    fn is_relay_type(msg_type: u8) -> bool {
+        msg_type == 12 || msg_type == 13
+    }
+
+    pub fn msg_type(&self) -> Dhcpv6MessageType {
+        Dhcpv6MessageType::from(self.data[0])
+    }
+
+    pub fn is_relay(&self) -> bool {
+        Self::is_relay_type(self.data[0])
+    }
+
+    /// Transaction ID of client/server messages.
+    pub fn transaction_id(&self) -> Option<u32> {
+        if self.is_relay() {
+            None
+        } else {
+            Some(u32::from_be_bytes([
+                0,
+                self.data[1],
+                self.data[2],
+                self.data[3],
+            ]))
+        }
+    }
+
+    /// Relay agents the message went through, for relay messages.
+    pub fn hop_count(&self) -> Option<u8> {
+        if self.is_relay() {
+            Some(self.data[1])
+        } else {
+            None
+        }
+    }
+
+    /// Address identifying the link of the client, for relay messages.
+    pub fn link_addr(&self) -> Option<Ipv6Addr> {
+        if self.is_relay() {
+            Some(Ipv6Addr::from(clone_into_array::<[u8; 16], u8>(
+                &self.data[2..18],
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// Address of the client or relay the message was received from, for relay
+    /// messages.
+    pub fn peer_addr(&self) -> Option<Ipv6Addr> {
+        if self.is_relay() {
+            Some(Ipv6Addr::from(clone_into_array::<[u8; 16], u8>(
+                &self.data[18..34],
+            )))
+        } else {
+            None
+        }
+    }
+
+    pub fn options(&self) -> Result<Vec<Dhcpv6Option<'a>>, FrameError> {
+        let header_len = if self.is_relay() { 34 } else { 4 };
+        read_options(&self.data[header_len..], self.depth)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Dhcpv6Frame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::parse(slice, 0)
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for Dhcpv6Frame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -0,0 +1,572 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::*;
+
+/// Longest name allowed by RFC 1035, in wire format.
+const MAX_NAME_LEN: usize = 255;
+const DNS_HEADER_LEN: usize = 12;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DnsType {
+    A,     // 1
+    Ns,    // 2
+    Cname, // 5
+    Soa,   // 6
+    Ptr,   // 12
+    Mx,    // 15
+    Txt,   // 16
+    Aaaa,  // 28
+    Srv,   // 33
+    Opt,   // 41
+    Any,   // 255
+    Other(u16),
+}
+
+impl From<u16> for DnsType {
+    fn from(val: u16) -> Self {
+        match val {
+            1 => Self::A,
+            2 => Self::Ns,
+            5 => Self::Cname,
+            6 => Self::Soa,
+            12 => Self::Ptr,
+            15 => Self::Mx,
+            16 => Self::Txt,
+            28 => Self::Aaaa,
+            33 => Self::Srv,
+            41 => Self::Opt,
+            255 => Self::Any,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for DnsType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::A => "A",
+            Self::Ns => "NS",
+            Self::Cname => "CNAME",
+            Self::Soa => "SOA",
+            Self::Ptr => "PTR",
+            Self::Mx => "MX",
+            Self::Txt => "TXT",
+            Self::Aaaa => "AAAA",
+            Self::Srv => "SRV",
+            Self::Opt => "OPT",
+            Self::Any => "ANY",
+            Self::Other(val) => return write!(f, "TYPE{}", val),
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+pub enum DnsRcode {
+    NoError,  // 0
+    FormErr,  // 1
+    ServFail, // 2
+    NxDomain, // 3
+    NotImp,   // 4
+    Refused,  // 5
+    Other(u8),
+}
+
+impl From<u8> for DnsRcode {
+    fn from(val: u8) -> Self {
+        match val {
+            0 => Self::NoError,
+            1 => Self::FormErr,
+            2 => Self::ServFail,
+            3 => Self::NxDomain,
+            4 => Self::NotImp,
+            5 => Self::Refused,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for DnsRcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoError => write!(f, "No error"),
+            Self::FormErr => write!(f, "Format error"),
+            Self::ServFail => write!(f, "Server failure"),
+            Self::NxDomain => write!(f, "No such name"),
+            Self::NotImp => write!(f, "Not implemented"),
+            Self::Refused => write!(f, "Refused"),
+            Self::Other(code) => write!(f, "Unknown ({})", code),
+        }
+    }
+}
+
+pub struct DnsQuestion {
+    name: String,
+    qtype: DnsType,
+    qclass: u16,
+}
+
+impl DnsQuestion {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn qtype(&self) -> DnsType {
+        self.qtype
+    }
+
+    pub fn qclass(&self) -> u16 {
+        self.qclass
+    }
+}
+
+impl fmt::Display for DnsQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.name,
+            class_name(self.qclass),
+            self.qtype
+        )
+    }
+}
+
+pub enum DnsRData<'a> {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Ns(String),
+    Ptr(String),
+    Mx {
+        preference: u16,
+        exchange: String,
+    },
+    Soa {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    Txt(Vec<String>),
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    /// EDNS(0) pseudo-record. The requestor UDP payload size, extended RCODE and flags
+    /// are carried in the class and TTL fields of the record.
+    Opt {
+        udp_size: u16,
+        ext_rcode: u8,
+        version: u8,
+        dnssec_ok: bool,
+        options: Vec<(u16, &'a [u8])>,
+    },
+    Other(&'a [u8]),
+}
+
+impl<'a> fmt::Display for DnsRData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::A(addr) => write!(f, "{}", addr),
+            Self::Aaaa(addr) => write!(f, "{}", addr),
+            Self::Cname(name) | Self::Ns(name) | Self::Ptr(name) => write!(f, "{}", name),
+            Self::Mx {
+                preference,
+                exchange,
+            } => write!(f, "{} {}", preference, exchange),
+            Self::Soa {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            Self::Txt(strings) => {
+                let quoted: Vec<_> = strings.iter().map(|s| format!("{:?}", s)).collect();
+                write!(f, "{}", quoted.join(" "))
+            }
+            Self::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            Self::Opt {
+                udp_size,
+                ext_rcode,
+                version,
+                dnssec_ok,
+                options,
+            } => write!(
+                f,
+                "EDNS{} UDP payload {} bytes, extended RCODE {}, DO {}, {} options",
+                version,
+                udp_size,
+                ext_rcode,
+                dnssec_ok,
+                options.len()
+            ),
+            Self::Other(data) => write!(f, "{} bytes", data.len()),
+        }
+    }
+}
+
+pub struct DnsRecord<'a> {
+    name: String,
+    rtype: DnsType,
+    class: u16,
+    ttl: u32,
+    data: DnsRData<'a>,
+}
+
+impl<'a> DnsRecord<'a> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rtype(&self) -> DnsType {
+        self.rtype
+    }
+
+    pub fn class(&self) -> u16 {
+        self.class
+    }
+
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    pub fn data(&self) -> &DnsRData<'a> {
+        &self.data
+    }
+}
+
+impl<'a> fmt::Display for DnsRecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rtype {
+            DnsType::Opt => write!(f, "{} {}", self.rtype, self.data),
+            _ => write!(
+                f,
+                "{} {} {} {} {}",
+                self.name,
+                self.ttl,
+                class_name(self.class),
+                self.rtype,
+                self.data
+            ),
+        }
+    }
+}
+
+pub struct DnsSections<'a> {
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord<'a>>,
+    pub authorities: Vec<DnsRecord<'a>>,
+    pub additionals: Vec<DnsRecord<'a>>,
+}
+
+fn class_name(class: u16) -> String {
+    match class {
+        1 => String::from("IN"),
+        3 => String::from("CH"),
+        4 => String::from("HS"),
+        255 => String::from("ANY"),
+        other => format!("CLASS{}", other),
+    }
+}
+
+/// Reads a possibly compressed domain name starting at the reader position.
+pub(super) fn read_name(reader: &mut ByteReader) -> Result<String, FrameError> {
+    let message = reader.data();
+    let mut labels: Vec<String> = Vec::new();
+    let mut wire_len = 0;
+    let mut pos = reader.pos();
+    // Position to resume from once the first compression pointer has been followed
+    let mut resume = None;
+    loop {
+        let mut cursor = ByteReader::new(message, "DNS name");
+        cursor.seek(pos)?;
+        let len = cursor.u8()?;
+        match len & 0xC0 {
+            0xC0 => {
+                let target = usize::from(u16::from_be_bytes([len & 0x3F, cursor.u8()?]));
+                // Only backward pointers are valid, which also rules out loops
+                if target >= pos {
+                    return Err(cursor.malformed("forward compression pointer"));
+                }
+                resume.get_or_insert(cursor.pos());
+                pos = target;
+            }
+            0x00 if len == 0 => {
+                reader.seek(resume.unwrap_or_else(|| cursor.pos()))?;
+                break;
+            }
+            0x00 => {
+                wire_len += usize::from(len) + 1;
+                if wire_len > MAX_NAME_LEN {
+                    return Err(cursor.malformed("name too long"));
+                }
+                let label = cursor.bytes(usize::from(len))?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                pos = cursor.pos();
+            }
+            _ => return Err(cursor.malformed("unsupported label type")),
+        }
+    }
+    if labels.is_empty() {
+        Ok(String::from("<Root>"))
+    } else {
+        Ok(labels.join("."))
+    }
+}
+
+fn read_question(reader: &mut ByteReader) -> Result<DnsQuestion, FrameError> {
+    Ok(DnsQuestion {
+        name: read_name(reader)?,
+        qtype: DnsType::from(reader.u16()?),
+        qclass: reader.u16()?,
+    })
+}
+
+fn read_record<'a>(reader: &mut ByteReader<'a>) -> Result<DnsRecord<'a>, FrameError> {
+    let name = read_name(reader)?;
+    let rtype = DnsType::from(reader.u16()?);
+    let class = reader.u16()?;
+    let ttl = reader.u32()?;
+    let rdlength = usize::from(reader.u16()?);
+    let rdata_start = reader.pos();
+    let mut rdata = reader.sub(rdlength)?;
+    // Names inside the RDATA may point anywhere in the message
+    let mut names = reader.clone();
+    names.seek(rdata_start)?;
+
+    let data = match rtype {
+        DnsType::A if rdlength == 4 => DnsRData::A(Ipv4Addr::from(
+            clone_into_array::<[u8; 4], u8>(rdata.rest()),
+        )),
+        DnsType::Aaaa if rdlength == 16 => DnsRData::Aaaa(Ipv6Addr::from(clone_into_array::<
+            [u8; 16],
+            u8,
+        >(rdata.rest()))),
+        DnsType::Cname => DnsRData::Cname(read_name(&mut names)?),
+        DnsType::Ns => DnsRData::Ns(read_name(&mut names)?),
+        DnsType::Ptr => DnsRData::Ptr(read_name(&mut names)?),
+        DnsType::Mx => {
+            let preference = names.u16()?;
+            DnsRData::Mx {
+                preference,
+                exchange: read_name(&mut names)?,
+            }
+        }
+        DnsType::Soa => {
+            let mname = read_name(&mut names)?;
+            let rname = read_name(&mut names)?;
+            DnsRData::Soa {
+                mname,
+                rname,
+                serial: names.u32()?,
+                refresh: names.u32()?,
+                retry: names.u32()?,
+                expire: names.u32()?,
+                minimum: names.u32()?,
+            }
+        }
+        DnsType::Txt => {
+            let mut strings = Vec::new();
+            while !rdata.is_empty() {
+                let len = usize::from(rdata.u8()?);
+                strings.push(String::from_utf8_lossy(rdata.bytes(len)?).into_owned());
+            }
+            DnsRData::Txt(strings)
+        }
+        DnsType::Srv => {
+            let priority = names.u16()?;
+            let weight = names.u16()?;
+            let port = names.u16()?;
+            DnsRData::Srv {
+                priority,
+                weight,
+                port,
+                target: read_name(&mut names)?,
+            }
+        }
+        DnsType::Opt => {
+            let mut options = Vec::new();
+            while !rdata.is_empty() {
+                let code = rdata.u16()?;
+                let len = usize::from(rdata.u16()?);
+                options.push((code, rdata.bytes(len)?));
+            }
+            DnsRData::Opt {
+                udp_size: class,
+                ext_rcode: (ttl >> 24) as u8,
+                version: (ttl >> 16) as u8,
+                dnssec_ok: ttl & 0x8000 != 0,
+                options,
+            }
+        }
+        _ => DnsRData::Other(rdata.rest()),
+    };
+    Ok(DnsRecord {
+        name,
+        rtype,
+        class,
+        ttl,
+        data,
+    })
+}
+
+/// DNS message, along with the number of its bytes left for the next segments when
+/// it is sent over TCP.
+pub struct DnsFrame<'a>(&'a [u8], usize);
+
+impl<'a> DnsFrame<'a> {
+    /// Whether `data` begins with the length prefix and a plausible header of a
+    /// message over TCP, which tells the start of a message apart from its
+    /// continuation.
+    pub fn is_dns_over_tcp(data: &[u8]) -> bool {
+        let (len, header) = match data {
+            [high, low, header @ ..] if header.len() >= DNS_HEADER_LEN => {
+                (usize::from(u16::from_be_bytes([*high, *low])), header)
+            }
+            _ => return false,
+        };
+        let opcode = (header[2] >> 3) & 0x0F;
+        let qdcount = u16::from_be_bytes([header[4], header[5]]);
+        len >= DNS_HEADER_LEN
+            && matches!(opcode, 0 | 1 | 2 | 4 | 5 | 6)
+            && header[3] & 0x40 == 0
+            && qdcount <= 1
+    }
+
+    /// Bytes of a message over TCP continued in the next segments, the message
+    /// being incomplete rather than malformed when its sections cannot be read.
+    pub fn missing(&self) -> usize {
+        self.1
+    }
+
+    pub fn raw_message(&self) -> &'a [u8] {
+        self.0
+    }
+
+    pub fn id(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[0..2]))
+    }
+
+    pub fn is_response(&self) -> bool {
+        self.0[2] & 0b1000_0000 != 0
+    }
+
+    pub fn opcode(&self) -> u4 {
+        u4::new((self.0[2] >> 3) & 0x0F)
+    }
+
+    pub fn authoritative(&self) -> bool {
+        self.0[2] & 0b0000_0100 != 0
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.0[2] & 0b0000_0010 != 0
+    }
+
+    pub fn recursion_desired(&self) -> bool {
+        self.0[2] & 0b0000_0001 != 0
+    }
+
+    pub fn recursion_available(&self) -> bool {
+        self.0[3] & 0b1000_0000 != 0
+    }
+
+    pub fn authentic_data(&self) -> bool {
+        self.0[3] & 0b0010_0000 != 0
+    }
+
+    pub fn checking_disabled(&self) -> bool {
+        self.0[3] & 0b0001_0000 != 0
+    }
+
+    pub fn rcode(&self) -> DnsRcode {
+        DnsRcode::from(self.0[3] & 0x0F)
+    }
+
+    pub fn qdcount(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[4..6]))
+    }
+
+    pub fn ancount(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[6..8]))
+    }
+
+    pub fn nscount(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[8..10]))
+    }
+
+    pub fn arcount(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[10..12]))
+    }
+
+    /// Parses the question, answer, authority and additional sections.
+    pub fn sections(&self) -> Result<DnsSections<'a>, FrameError> {
+        let mut reader = ByteReader::new(self.0, "DNS message");
+        reader.seek(12)?;
+        let questions = (0..self.qdcount())
+            .map(|_| read_question(&mut reader))
+            .collect::<Result<_, _>>()?;
+        let mut records = |count| {
+            (0..count)
+                .map(|_| read_record(&mut reader))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(DnsSections {
+            questions,
+            answers: records(self.ancount())?,
+            authorities: records(self.nscount())?,
+            additionals: records(self.arcount())?,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for DnsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        ByteReader::new(slice, "DNS header").bytes(DNS_HEADER_LEN)?;
+        Ok(Self(slice, 0))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for DnsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for DnsFrame<'a> {
+    type Error = FrameError;
+
+    /// Messages over TCP are prefixed by their length. Only segments starting with a
+    /// message are supported, and a message may continue in the next segments.
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(frame.payload(), "DNS over TCP");
+        let len = usize::from(reader.u16()?);
+        let message = reader.rest();
+        if message.len() >= len {
+            return Self::try_from(&message[..len]);
+        }
+        let frame = Self::try_from(message)?;
+        Ok(Self(frame.0, len - message.len()))
+    }
+}
@@ -0,0 +1,118 @@
+use super::*;
+
+const GENEVE_LEN: usize = 8;
+
+/// Tunnel option, whose meaning is defined by its class.
+pub struct GeneveOption<'a> {
+    pub class: u16,
+    pub typ: u8,
+    pub data: &'a [u8],
+}
+
+impl<'a> GeneveOption<'a> {
+    /// Whether a tunnel endpoint that does not understand the option must drop
+    /// the packet.
+    pub fn is_critical(&self) -> bool {
+        self.typ & 0x80 != 0
+    }
+}
+
+impl<'a> fmt::Display for GeneveOption<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Class 0x{:04X}, type 0x{:02X}{}: {}",
+            self.class,
+            self.typ,
+            if self.is_critical() {
+                " (critical)"
+            } else {
+                ""
+            },
+            to_hex(self.data)
+        )
+    }
+}
+
+/// Generic Network Virtualization Encapsulation header (RFC 8926).
+pub struct GeneveFrame<'a> {
+    header: &'a [u8],
+    options: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> GeneveFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0] >> 6
+    }
+
+    /// Whether the packet carries a control message instead of user data.
+    pub fn is_oam(&self) -> bool {
+        self.header[1] & 0x80 != 0
+    }
+
+    /// Whether at least one of the options is critical.
+    pub fn has_critical_options(&self) -> bool {
+        self.header[1] & 0x40 != 0
+    }
+
+    /// EtherType of the payload.
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    /// Virtual network identifier of the inner frame.
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.header[4], self.header[5], self.header[6]])
+    }
+
+    pub fn options(&self) -> Result<Vec<GeneveOption<'a>>, FrameError> {
+        let mut reader = ByteReader::new(self.options, "Geneve option");
+        let mut options = Vec::new();
+        while !reader.is_empty() {
+            let class = reader.u16()?;
+            let typ = reader.u8()?;
+            let len = usize::from(reader.u8()? & 0x1F) * 4;
+            options.push(GeneveOption {
+                class,
+                typ,
+                data: reader.bytes(len)?,
+            });
+        }
+        Ok(options)
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> Encapsulated<'a> {
+        Encapsulated::from_ether_type(self.protocol_type(), self.payload)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for GeneveFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "Geneve header");
+        let header = reader.bytes(GENEVE_LEN)?;
+        if header[0] >> 6 != 0 {
+            return Err(reader.malformed("unsupported version"));
+        }
+        let options = reader.bytes(usize::from(header[0] & 0x3F) * 4)?;
+        Ok(Self {
+            header,
+            options,
+            payload: reader.rest(),
+        })
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for GeneveFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
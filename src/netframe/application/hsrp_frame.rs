@@ -0,0 +1,252 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::netframe::datalink::HwAddr;
+
+use super::*;
+
+const HSRPV1_LEN: usize = 20;
+/// Type and length of the group state TLV starting HSRPv2 messages.
+const GROUP_STATE_TLV: u8 = 1;
+const GROUP_STATE_LEN: usize = 40;
+const TEXT_AUTH_TLV: u8 = 3;
+const MD5_AUTH_TLV: u8 = 4;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HsrpOpCode {
+    Hello,  // 0
+    Coup,   // 1
+    Resign, // 2
+    Other(u8),
+}
+
+impl From<u8> for HsrpOpCode {
+    fn from(op_code: u8) -> Self {
+        match op_code {
+            0 => HsrpOpCode::Hello,
+            1 => HsrpOpCode::Coup,
+            2 => HsrpOpCode::Resign,
+            other => HsrpOpCode::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for HsrpOpCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HsrpOpCode::Hello => write!(f, "Hello"),
+            HsrpOpCode::Coup => write!(f, "Coup"),
+            HsrpOpCode::Resign => write!(f, "Resign"),
+            HsrpOpCode::Other(op_code) => write!(f, "Unknown op code ({})", op_code),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HsrpState {
+    /// HSRPv2 only.
+    Disabled,
+    Initial, // 0
+    Learn,   // 1
+    Listen,  // 2
+    Speak,   // 4
+    Standby, // 8
+    Active,  // 16
+    Other(u8),
+}
+
+impl HsrpState {
+    /// State numbered `state` by HSRP version `version`, HSRPv2 numbering them
+    /// sequentially instead of as bits.
+    fn new(version: u8, state: u8) -> Self {
+        match (version, state) {
+            (0, 0) | (2, 1) => HsrpState::Initial,
+            (0, 1) | (2, 2) => HsrpState::Learn,
+            (0, 2) | (2, 3) => HsrpState::Listen,
+            (0, 4) | (2, 4) => HsrpState::Speak,
+            (0, 8) | (2, 5) => HsrpState::Standby,
+            (0, 16) | (2, 6) => HsrpState::Active,
+            (2, 0) => HsrpState::Disabled,
+            (_, other) => HsrpState::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for HsrpState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HsrpState::Disabled => write!(f, "Disabled"),
+            HsrpState::Initial => write!(f, "Initial"),
+            HsrpState::Learn => write!(f, "Learn"),
+            HsrpState::Listen => write!(f, "Listen"),
+            HsrpState::Speak => write!(f, "Speak"),
+            HsrpState::Standby => write!(f, "Standby"),
+            HsrpState::Active => write!(f, "Active"),
+            HsrpState::Other(state) => write!(f, "Unknown state ({})", state),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HsrpAuth<'a> {
+    None,
+    /// Password sent in clear text, "cisco" unless configured.
+    Text(&'a [u8]),
+    /// Keyed MD5 digest of HSRPv2.
+    Md5(&'a [u8]),
+}
+
+/// Cisco Hot Standby Router Protocol message, version 1 (RFC 2281) or version 2.
+/// It has no checksum of its own, relying on the one of UDP.
+pub struct HsrpFrame<'a> {
+    version: u8,
+    op_code: HsrpOpCode,
+    state: HsrpState,
+    group: u16,
+    priority: u32,
+    hello_time: Duration,
+    hold_time: Duration,
+    virtual_ip: Option<IpAddr>,
+    identifier: Option<HwAddr>,
+    auth: HsrpAuth<'a>,
+}
+
+impl<'a> HsrpFrame<'a> {
+    /// Version of the protocol, 0 for HSRPv1 and 2 for HSRPv2.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn op_code(&self) -> HsrpOpCode {
+        self.op_code
+    }
+
+    pub fn state(&self) -> HsrpState {
+        self.state
+    }
+
+    pub fn group(&self) -> u16 {
+        self.group
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn hello_time(&self) -> Duration {
+        self.hello_time
+    }
+
+    pub fn hold_time(&self) -> Duration {
+        self.hold_time
+    }
+
+    /// Virtual address of the group, unset until learned from the active router.
+    pub fn virtual_ip(&self) -> Option<IpAddr> {
+        self.virtual_ip
+    }
+
+    /// Address of the interface of the sender, HSRPv2 only.
+    pub fn identifier(&self) -> Option<HwAddr> {
+        self.identifier
+    }
+
+    pub fn auth(&self) -> HsrpAuth<'a> {
+        self.auth
+    }
+
+    fn parse_v1(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let version = reader.u8()?;
+        let op_code = HsrpOpCode::from(reader.u8()?);
+        let state = HsrpState::new(version, reader.u8()?);
+        let hello_time = Duration::from_secs(u64::from(reader.u8()?));
+        let hold_time = Duration::from_secs(u64::from(reader.u8()?));
+        let priority = u32::from(reader.u8()?);
+        let group = u16::from(reader.u8()?);
+        reader.u8()?;
+        let auth = HsrpAuth::Text(reader.bytes(8)?);
+        let virtual_ip = IpAddr::from(clone_into_array::<[u8; 4], u8>(reader.bytes(4)?));
+        Ok(Self {
+            version,
+            op_code,
+            state,
+            group,
+            priority,
+            hello_time,
+            hold_time,
+            virtual_ip: Some(virtual_ip).filter(|addr| !addr.is_unspecified()),
+            identifier: None,
+            auth,
+        })
+    }
+
+    fn parse_v2(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let mut frame = None;
+        let mut auth = HsrpAuth::None;
+        while !reader.is_empty() {
+            let typ = reader.u8()?;
+            let len = usize::from(reader.u8()?);
+            let mut value = reader.sub(len)?;
+            match typ {
+                GROUP_STATE_TLV => {
+                    let version = value.u8()?;
+                    let op_code = HsrpOpCode::from(value.u8()?);
+                    let state = HsrpState::new(version, value.u8()?);
+                    let ip_version = value.u8()?;
+                    let group = value.u16()?;
+                    let identifier = HwAddr::from(value.bytes(6)?);
+                    let priority = value.u32()?;
+                    let hello_time = Duration::from_millis(u64::from(value.u32()?));
+                    let hold_time = Duration::from_millis(u64::from(value.u32()?));
+                    let addr = value.bytes(16)?;
+                    let virtual_ip = match ip_version {
+                        4 => IpAddr::from(clone_into_array::<[u8; 4], u8>(&addr[..4])),
+                        _ => IpAddr::from(clone_into_array::<[u8; 16], u8>(addr)),
+                    };
+                    frame = Some(Self {
+                        version,
+                        op_code,
+                        state,
+                        group,
+                        priority,
+                        hello_time,
+                        hold_time,
+                        virtual_ip: Some(virtual_ip).filter(|addr| !addr.is_unspecified()),
+                        identifier: Some(identifier),
+                        auth: HsrpAuth::None,
+                    });
+                }
+                TEXT_AUTH_TLV => auth = HsrpAuth::Text(value.rest()),
+                MD5_AUTH_TLV => auth = HsrpAuth::Md5(value.rest()),
+                _ => {}
+            }
+        }
+        let mut frame = frame.ok_or_else(|| reader.malformed("missing group state TLV"))?;
+        frame.auth = auth;
+        Ok(frame)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for HsrpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "HSRP message");
+        // HSRPv1 starts with its version, 0, where HSRPv2 starts with a TLV type
+        match slice {
+            [0, ..] if slice.len() >= HSRPV1_LEN => Self::parse_v1(&mut reader),
+            [GROUP_STATE_TLV, len, ..] if usize::from(*len) == GROUP_STATE_LEN => {
+                Self::parse_v2(&mut reader)
+            }
+            _ => Err(reader.malformed("unsupported version")),
+        }
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for HsrpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
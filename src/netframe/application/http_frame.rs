@@ -0,0 +1,218 @@
+use std::str;
+
+use super::*;
+
+const METHODS: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+];
+
+pub enum HttpStartLine<'a> {
+    Request {
+        method: &'a str,
+        uri: &'a str,
+        version: &'a str,
+    },
+    Response {
+        version: &'a str,
+        status: u16,
+        reason: &'a str,
+    },
+}
+
+impl<'a> fmt::Display for HttpStartLine<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request {
+                method,
+                uri,
+                version,
+            } => write!(f, "{} {} {}", method, uri, version),
+            Self::Response {
+                version,
+                status,
+                reason,
+            } => write!(f, "{} {} {}", version, status, reason),
+        }
+    }
+}
+
+/// Chunk of a body sent with the chunked transfer coding.
+pub struct HttpChunk {
+    /// Position of the chunk data from the start of the body.
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// Start of an HTTP/1.x message. The message is parsed from a single TCP payload,
+/// so headers split across segments are reported as incomplete; pass a reassembled
+/// stream to `try_from` to see the whole message.
+pub struct HttpFrame<'a> {
+    start: HttpStartLine<'a>,
+    headers: Vec<(&'a str, &'a str)>,
+    complete: bool,
+    body: &'a [u8],
+}
+
+impl<'a> HttpFrame<'a> {
+    /// Whether `data` begins with a request or status line, which tells the start of
+    /// a message apart from the continuation of a body.
+    pub fn is_http(data: &[u8]) -> bool {
+        data.starts_with(b"HTTP/1.")
+            || METHODS.iter().any(|method| {
+                data.starts_with(method.as_bytes()) && data.get(method.len()) == Some(&b' ')
+            })
+    }
+
+    pub fn start_line(&self) -> &HttpStartLine<'a> {
+        &self.start
+    }
+
+    pub fn is_request(&self) -> bool {
+        matches!(self.start, HttpStartLine::Request { .. })
+    }
+
+    /// Header fields in the order they were sent.
+    pub fn headers(&self) -> &[(&'a str, &'a str)] {
+        &self.headers
+    }
+
+    /// Value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+
+    /// Whether the empty line ending the header block was found in the payload.
+    pub fn headers_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("Content-Length")?.parse().ok()
+    }
+
+    pub fn is_chunked(&self) -> bool {
+        self.header("Transfer-Encoding")
+            .and_then(|codings| codings.rsplit(',').next())
+            .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+    }
+
+    /// Part of the body found after the header block.
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// Boundaries of the chunks of a chunked body, up to the last chunk, or up to the
+    /// first chunk size line cut by the end of the payload. The last chunk has a
+    /// length of zero.
+    pub fn chunks(&self) -> Result<Vec<HttpChunk>, FrameError> {
+        let mut chunks = Vec::new();
+        if !self.is_chunked() {
+            return Ok(chunks);
+        }
+        let mut reader = ByteReader::new(self.body, "HTTP chunk");
+        while let Some(line) = read_line(&mut reader) {
+            let size = line.split(|&c| c == b';').next().unwrap_or_default();
+            let len = str::from_utf8(size)
+                .ok()
+                .and_then(|size| usize::from_str_radix(size.trim(), 16).ok())
+                .ok_or_else(|| reader.malformed("invalid chunk size"))?;
+            let end = reader
+                .pos()
+                .checked_add(len)
+                .and_then(|end| end.checked_add(2))
+                .ok_or_else(|| reader.malformed("chunk size too large"))?;
+            chunks.push(HttpChunk {
+                offset: reader.pos(),
+                len,
+            });
+            // Skip the data and its trailing CRLF
+            if len == 0 || reader.seek(end).is_err() {
+                break;
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+/// Reads a line ended by CRLF or LF, without the line terminator. Returns `None`
+/// when no terminator is left.
+pub(super) fn read_line<'a>(reader: &mut ByteReader<'a>) -> Option<&'a [u8]> {
+    let rest = &reader.data()[reader.pos()..];
+    let end = rest.iter().position(|&c| c == b'\n')?;
+    let line = reader.bytes(end + 1).ok()?;
+    let line = &line[..end];
+    Some(line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+pub(super) fn read_text<'a>(reader: &mut ByteReader<'a>) -> Result<Option<&'a str>, FrameError> {
+    match read_line(reader) {
+        Some(line) => str::from_utf8(line)
+            .map(Some)
+            .map_err(|_| reader.malformed("non UTF-8 header line")),
+        None => Ok(None),
+    }
+}
+
+fn read_start_line<'a>(reader: &mut ByteReader<'a>) -> Result<HttpStartLine<'a>, FrameError> {
+    let line = read_text(reader)?.ok_or_else(|| reader.malformed("start line not terminated"))?;
+    let mut parts = line.splitn(3, ' ');
+    let (first, second, third) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(first), Some(second), third) => (first, second, third.unwrap_or_default()),
+        _ => return Err(reader.malformed("incomplete start line")),
+    };
+    if first.starts_with("HTTP/") {
+        Ok(HttpStartLine::Response {
+            version: first,
+            status: second
+                .parse()
+                .map_err(|_| reader.malformed("invalid status code"))?,
+            reason: third,
+        })
+    } else if third.starts_with("HTTP/") {
+        Ok(HttpStartLine::Request {
+            method: first,
+            uri: second,
+            version: third,
+        })
+    } else {
+        Err(reader.malformed("missing HTTP version"))
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for HttpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "HTTP header");
+        let start = read_start_line(&mut reader)?;
+        let mut headers = Vec::new();
+        let mut complete = false;
+        while let Some(line) = read_text(&mut reader)? {
+            if line.is_empty() {
+                complete = true;
+                break;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| reader.malformed("header line without a colon"))?;
+            headers.push((name, value.trim()));
+        }
+        Ok(Self {
+            start,
+            headers,
+            complete,
+            body: if complete { reader.rest() } else { &[] },
+        })
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for HttpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
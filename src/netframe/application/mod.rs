@@ -0,0 +1,124 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use ux::*;
+
+pub use bgp_frame::*;
+pub use dhcp_frame::*;
+pub use dhcpv6_frame::*;
+pub use dns_frame::*;
+pub use geneve_frame::*;
+pub use hsrp_frame::*;
+pub use http_frame::*;
+pub use ntp_frame::*;
+pub use quic_frame::*;
+pub use rip_frame::*;
+pub use rtp_frame::*;
+pub use sdp::*;
+pub use sip_frame::*;
+pub use snmp_frame::*;
+pub use syslog_frame::*;
+pub use tls_frame::*;
+pub use vxlan_frame::*;
+pub use x509::*;
+
+use crate::clone_into_array;
+use crate::netframe::{
+    BerClass, BerElement, BerReader, BerTag, ByteReader, FrameError, UnknownFrame,
+};
+
+use super::internet::Encapsulated;
+use super::transport::*;
+
+mod bgp_frame;
+mod dhcp_frame;
+mod dhcpv6_frame;
+mod dns_frame;
+mod geneve_frame;
+mod hsrp_frame;
+mod http_frame;
+mod ntp_frame;
+mod quic_frame;
+mod rip_frame;
+mod rtp_frame;
+mod sdp;
+mod sip_frame;
+mod snmp_frame;
+mod syslog_frame;
+mod tls_frame;
+mod vxlan_frame;
+mod x509;
+
+/// Application protocol found by the well-known port of a transport frame.
+pub enum AppProtocol<'a> {
+    Dns(DnsFrame<'a>),
+    Dhcp(DhcpFrame<'a>),
+    Dhcpv6(Dhcpv6Frame<'a>),
+    Http(HttpFrame<'a>),
+    Tls(TlsFrame<'a>),
+    Quic(QuicFrame<'a>),
+    Ntp(NtpFrame<'a>),
+    Snmp(SnmpFrame<'a>),
+    Syslog(SyslogFrame<'a>),
+    Sip(SipFrame<'a>),
+    Rtp(RtpFrame<'a>),
+    Rtcp(RtcpFrame<'a>),
+    Vxlan(VxlanFrame<'a>),
+    Geneve(GeneveFrame<'a>),
+    Rip(RipFrame<'a>),
+    Bgp(BgpFrame<'a>),
+    Hsrp(HsrpFrame<'a>),
+    /// The port matched a known protocol, but the payload could not be parsed.
+    Malformed(&'static str, FrameError),
+    Other(UnknownFrame<'a>),
+}
+
+impl<'a> fmt::Display for AppProtocol<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppProtocol::Dns(_) => write!(f, "DNS"),
+            AppProtocol::Dhcp(_) => write!(f, "DHCP"),
+            AppProtocol::Dhcpv6(_) => write!(f, "DHCPv6"),
+            AppProtocol::Http(_) => write!(f, "HTTP"),
+            AppProtocol::Tls(_) => write!(f, "TLS"),
+            AppProtocol::Quic(_) => write!(f, "QUIC"),
+            AppProtocol::Ntp(_) => write!(f, "NTP"),
+            AppProtocol::Snmp(_) => write!(f, "SNMP"),
+            AppProtocol::Syslog(_) => write!(f, "Syslog"),
+            AppProtocol::Sip(_) => write!(f, "SIP"),
+            AppProtocol::Rtp(_) => write!(f, "RTP"),
+            AppProtocol::Rtcp(_) => write!(f, "RTCP"),
+            AppProtocol::Vxlan(_) => write!(f, "VXLAN"),
+            AppProtocol::Geneve(_) => write!(f, "Geneve"),
+            AppProtocol::Rip(_) => write!(f, "RIP"),
+            AppProtocol::Bgp(_) => write!(f, "BGP"),
+            AppProtocol::Hsrp(_) => write!(f, "HSRP"),
+            AppProtocol::Malformed(name, _) => write!(f, "{} (malformed)", name),
+            AppProtocol::Other(port) => write!(f, "Unknown (port {})", port.type_id()),
+        }
+    }
+}
+
+impl<'a> AppProtocol<'a> {
+    /// Wraps the result of a fallible conversion into the variant `variant`.
+    pub(crate) fn parse<T, F>(name: &'static str, frame: Result<T, FrameError>, variant: F) -> Self
+    where
+        F: FnOnce(T) -> Self,
+    {
+        match frame {
+            Ok(frame) => variant(frame),
+            Err(err) => AppProtocol::Malformed(name, err),
+        }
+    }
+}
+
+/// Colon separated hex dump of opaque fields, such as identifiers.
+pub fn to_hex(data: &[u8]) -> String {
+    let hex: Vec<_> = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+    hex.join(":")
+}
+
+/// Port identifying the service of a connection, assumed to be the lowest one.
+pub fn service_port(src_port: u16, dest_port: u16) -> u16 {
+    src_port.min(dest_port)
+}
@@ -0,0 +1,372 @@
+use std::net::Ipv4Addr;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::*;
+
+/// Length of the header of time synchronization messages (modes 1 to 5).
+const NTP_LEN: usize = 48;
+const CONTROL_LEN: usize = 12;
+const PRIVATE_LEN: usize = 8;
+/// Seconds from the NTP epoch (1900-01-01) to the Unix epoch.
+const UNIX_OFFSET: i64 = 2_208_988_800;
+/// Seconds in an NTP era, after which the 32 bit seconds field wraps around.
+const ERA_LEN: i64 = 1 << 32;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NtpLeapIndicator {
+    NoWarning,      // 0
+    LastMinute61,   // 1
+    LastMinute59,   // 2
+    Unsynchronized, // 3
+}
+
+impl From<u8> for NtpLeapIndicator {
+    fn from(val: u8) -> Self {
+        match val & 0b11 {
+            0 => Self::NoWarning,
+            1 => Self::LastMinute61,
+            2 => Self::LastMinute59,
+            _ => Self::Unsynchronized,
+        }
+    }
+}
+
+impl fmt::Display for NtpLeapIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::NoWarning => "No warning",
+            Self::LastMinute61 => "Last minute of the day has 61 seconds",
+            Self::LastMinute59 => "Last minute of the day has 59 seconds",
+            Self::Unsynchronized => "Clock unsynchronized",
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NtpMode {
+    Reserved,         // 0
+    SymmetricActive,  // 1
+    SymmetricPassive, // 2
+    Client,           // 3
+    Server,           // 4
+    Broadcast,        // 5
+    Control,          // 6
+    Private,          // 7
+}
+
+impl From<u8> for NtpMode {
+    fn from(val: u8) -> Self {
+        match val & 0b111 {
+            1 => Self::SymmetricActive,
+            2 => Self::SymmetricPassive,
+            3 => Self::Client,
+            4 => Self::Server,
+            5 => Self::Broadcast,
+            6 => Self::Control,
+            7 => Self::Private,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+impl fmt::Display for NtpMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::Reserved => "Reserved",
+            Self::SymmetricActive => "Symmetric active",
+            Self::SymmetricPassive => "Symmetric passive",
+            Self::Client => "Client",
+            Self::Server => "Server",
+            Self::Broadcast => "Broadcast",
+            Self::Control => "Control",
+            Self::Private => "Private",
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+/// Source of the time of a server, whose meaning depends on the stratum.
+pub enum NtpReferenceId {
+    /// Kiss-o'-Death code sent with stratum 0, such as `RATE` or `DENY`.
+    KissCode(String),
+    /// Reference clock of a primary server, such as `GPS` or `PPS`.
+    Clock(String),
+    /// Upstream server of secondary servers. Over IPv6 this is the start of the
+    /// MD5 hash of the address instead.
+    Address(Ipv4Addr),
+}
+
+impl fmt::Display for NtpReferenceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KissCode(code) => write!(f, "Kiss-o'-Death {}", code),
+            Self::Clock(clock) => write!(f, "{}", clock),
+            Self::Address(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+/// Converts a 64 bit timestamp, with 32 bits of seconds since 1900 and 32 bits of
+/// fraction, to UTC. A timestamp of zero means the time is unknown.
+///
+/// Seconds with the most significant bit cleared are assumed to be in era 1, which
+/// starts in 2036, as the protocol predates the end of era 0 in that range.
+pub(super) fn ntp_time(timestamp: u64) -> Option<DateTime<Utc>> {
+    if timestamp == 0 {
+        return None;
+    }
+    let secs = (timestamp >> 32) as i64;
+    let secs = if secs & 0x8000_0000 == 0 {
+        secs + ERA_LEN
+    } else {
+        secs
+    };
+    let nanos = ((timestamp & 0xFFFF_FFFF) * 1_000_000_000) >> 32;
+    Utc.timestamp_opt(secs - UNIX_OFFSET, nanos as u32).single()
+}
+
+/// Converts a 32 bit interval, with 16 bits of seconds and 16 bits of fraction.
+fn ntp_short(val: u32) -> f64 {
+    f64::from(val) / 65536.0
+}
+
+/// Time synchronization message exchanged between clients and servers (modes 1
+/// to 5).
+pub struct NtpPacket<'a>(&'a [u8]);
+
+impl<'a> NtpPacket<'a> {
+    /// 0 for unspecified or Kiss-o'-Death, 1 for primary servers and up to 15 for
+    /// secondary servers.
+    pub fn stratum(&self) -> u8 {
+        self.0[1]
+    }
+
+    /// Log2 of the maximum interval between messages, in seconds.
+    pub fn poll(&self) -> i8 {
+        self.0[2] as i8
+    }
+
+    /// Log2 of the precision of the system clock, in seconds.
+    pub fn precision(&self) -> i8 {
+        self.0[3] as i8
+    }
+
+    /// Round-trip delay to the reference clock, in seconds.
+    pub fn root_delay(&self) -> f64 {
+        ntp_short(u32::from_be_bytes(clone_into_array(&self.0[4..8])))
+    }
+
+    /// Maximum error relative to the reference clock, in seconds.
+    pub fn root_dispersion(&self) -> f64 {
+        ntp_short(u32::from_be_bytes(clone_into_array(&self.0[8..12])))
+    }
+
+    pub fn raw_reference_id(&self) -> &'a [u8] {
+        &self.0[12..16]
+    }
+
+    pub fn reference_id(&self) -> NtpReferenceId {
+        let ascii = || {
+            let id = self.raw_reference_id();
+            let end = id.iter().position(|&c| c == 0).unwrap_or(id.len());
+            String::from_utf8_lossy(&id[..end]).into_owned()
+        };
+        match self.stratum() {
+            0 => NtpReferenceId::KissCode(ascii()),
+            1 => NtpReferenceId::Clock(ascii()),
+            _ => NtpReferenceId::Address(Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(
+                self.raw_reference_id(),
+            ))),
+        }
+    }
+
+    fn timestamp(&self, offset: usize) -> u64 {
+        u64::from_be_bytes(clone_into_array(&self.0[offset..offset + 8]))
+    }
+
+    /// Time the system clock was last set or corrected.
+    pub fn reference_timestamp(&self) -> Option<DateTime<Utc>> {
+        ntp_time(self.timestamp(16))
+    }
+
+    /// Time the request left the client, copied by the server into its reply.
+    pub fn origin_timestamp(&self) -> Option<DateTime<Utc>> {
+        ntp_time(self.timestamp(24))
+    }
+
+    /// Time the request arrived at the server.
+    pub fn receive_timestamp(&self) -> Option<DateTime<Utc>> {
+        ntp_time(self.timestamp(32))
+    }
+
+    /// Time the message left the sender. Clients may send random values here to
+    /// match replies, so this is only a time in server messages.
+    pub fn transmit_timestamp(&self) -> Option<DateTime<Utc>> {
+        ntp_time(self.timestamp(40))
+    }
+
+    /// Whether the server sent a Kiss-o'-Death message to deny or slow down the
+    /// client.
+    pub fn is_kiss_of_death(&self) -> bool {
+        self.stratum() == 0 && NtpMode::from(self.0[0]) == NtpMode::Server
+    }
+
+    /// Key ID and message digest of symmetric key authentication, found after the
+    /// header when no extension fields are present.
+    pub fn authenticator(&self) -> Option<(u32, &'a [u8])> {
+        match &self.0[NTP_LEN..] {
+            mac if mac.len() == 20 || mac.len() == 24 => {
+                Some((u32::from_be_bytes(clone_into_array(&mac[..4])), &mac[4..]))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Control message (mode 6), used by `ntpq` to read and write variables.
+pub struct NtpControl<'a>(&'a [u8]);
+
+impl<'a> NtpControl<'a> {
+    pub fn is_response(&self) -> bool {
+        self.0[1] & 0b1000_0000 != 0
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.0[1] & 0b0100_0000 != 0
+    }
+
+    /// Whether more fragments follow.
+    pub fn more(&self) -> bool {
+        self.0[1] & 0b0010_0000 != 0
+    }
+
+    pub fn opcode(&self) -> u8 {
+        self.0[1] & 0b0001_1111
+    }
+
+    pub fn sequence(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[2..4]))
+    }
+
+    pub fn status(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[4..6]))
+    }
+
+    pub fn association_id(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[6..8]))
+    }
+
+    pub fn offset(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[8..10]))
+    }
+
+    pub fn count(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[10..12]))
+    }
+
+    /// Data of the message, usually comma separated `name=value` pairs.
+    pub fn data(&self) -> &'a [u8] {
+        let end = (CONTROL_LEN + usize::from(self.count())).min(self.0.len());
+        &self.0[CONTROL_LEN..end]
+    }
+}
+
+/// Implementation specific message (mode 7), used by the deprecated `ntpdc`. Only
+/// the header is decoded.
+pub struct NtpPrivate<'a>(&'a [u8]);
+
+impl<'a> NtpPrivate<'a> {
+    pub fn is_response(&self) -> bool {
+        self.0[0] & 0b1000_0000 != 0
+    }
+
+    /// Whether more fragments follow.
+    pub fn more(&self) -> bool {
+        self.0[0] & 0b0100_0000 != 0
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.0[1] & 0b1000_0000 != 0
+    }
+
+    pub fn sequence(&self) -> u8 {
+        self.0[1] & 0b0111_1111
+    }
+
+    pub fn implementation(&self) -> u8 {
+        self.0[2]
+    }
+
+    pub fn request_code(&self) -> u8 {
+        self.0[3]
+    }
+
+    pub fn error(&self) -> u8 {
+        self.0[4] >> 4
+    }
+
+    pub fn item_count(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[4..6])) & 0x0FFF
+    }
+
+    pub fn item_size(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.0[6..8])) & 0x0FFF
+    }
+}
+
+pub enum NtpMessage<'a> {
+    Packet(NtpPacket<'a>),
+    Control(NtpControl<'a>),
+    Private(NtpPrivate<'a>),
+}
+
+/// NTP or SNTP message. Both share the same format, SNTP clients just fill fewer
+/// fields.
+pub struct NtpFrame<'a>(&'a [u8]);
+
+impl<'a> NtpFrame<'a> {
+    pub fn leap_indicator(&self) -> NtpLeapIndicator {
+        NtpLeapIndicator::from(self.0[0] >> 6)
+    }
+
+    pub fn version(&self) -> u8 {
+        (self.0[0] >> 3) & 0b111
+    }
+
+    pub fn mode(&self) -> NtpMode {
+        NtpMode::from(self.0[0])
+    }
+
+    pub fn message(&self) -> NtpMessage<'a> {
+        match self.mode() {
+            NtpMode::Control => NtpMessage::Control(NtpControl(self.0)),
+            NtpMode::Private => NtpMessage::Private(NtpPrivate(self.0)),
+            _ => NtpMessage::Packet(NtpPacket(self.0)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for NtpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "NTP header");
+        let len = match NtpMode::from(reader.u8()?) {
+            NtpMode::Control => CONTROL_LEN,
+            NtpMode::Private => PRIVATE_LEN,
+            _ => NTP_LEN,
+        };
+        reader.seek(len)?;
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for NtpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -0,0 +1,616 @@
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use aes::Aes128;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes128Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use super::*;
+
+const VERSION_1: u32 = 0x0000_0001;
+const VERSION_2: u32 = 0x6b33_43cf;
+const INITIAL_SALT_V1: [u8; 20] = [
+    0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad,
+    0xcc, 0xbb, 0x7f, 0x0a,
+];
+const INITIAL_SALT_V2: [u8; 20] = [
+    0x0d, 0xed, 0xe3, 0xde, 0xf7, 0x00, 0xa6, 0xdb, 0x81, 0x93, 0x81, 0xbe, 0x6e, 0x26, 0x9d, 0xcb,
+    0xf9, 0xbd, 0x2e, 0xd9,
+];
+/// Bytes of ciphertext sampled for header protection, from 4 bytes past the start
+/// of the packet number.
+const SAMPLE_LEN: usize = 16;
+
+/// Reads a variable-length integer, whose two most significant bits give its length.
+fn read_varint(reader: &mut ByteReader) -> Result<u64, FrameError> {
+    let first = reader.u8()?;
+    let len = 1 << (first >> 6);
+    let mut val = u64::from(first & 0x3F);
+    for &byte in reader.bytes(len - 1)? {
+        val = val << 8 | u64::from(byte);
+    }
+    Ok(val)
+}
+
+fn read_len(reader: &mut ByteReader) -> Result<usize, FrameError> {
+    usize::try_from(read_varint(reader)?).map_err(|_| reader.malformed("length too large"))
+}
+
+pub fn quic_version_name(version: u32) -> &'static str {
+    match version {
+        0 => "Version Negotiation",
+        VERSION_1 => "QUIC v1",
+        VERSION_2 => "QUIC v2",
+        val if val & 0x0F0F_0F0F == 0x0A0A_0A0A => "Reserved (forcing negotiation)",
+        val if val >> 8 == 0xFF_0000 => "IETF draft",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum QuicPacketType {
+    Initial,
+    ZeroRtt,
+    Handshake,
+    Retry,
+    VersionNegotiation,
+    /// Short header packet, protected with the 1-RTT keys.
+    OneRtt,
+}
+
+impl fmt::Display for QuicPacketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::Initial => "Initial",
+            Self::ZeroRtt => "0-RTT",
+            Self::Handshake => "Handshake",
+            Self::Retry => "Retry",
+            Self::VersionNegotiation => "Version Negotiation",
+            Self::OneRtt => "1-RTT",
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+/// Frames of a decrypted packet. Decoding stops at the first frame type that is
+/// not supported, as its length is unknown.
+pub enum QuicFrameItem<'a> {
+    Padding(usize), // 0x00
+    Ping,           // 0x01
+    Ack {
+        largest: u64,
+        delay: u64,
+        ranges: u64,
+    }, // 0x02-0x03
+    Crypto {
+        offset: u64,
+        data: &'a [u8],
+    }, // 0x06
+    ConnectionClose {
+        error_code: u64,
+        reason: String,
+    }, // 0x1c-0x1d
+    Other(u64),
+}
+
+impl<'a> fmt::Display for QuicFrameItem<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Padding(len) => write!(f, "PADDING ({} bytes)", len),
+            Self::Ping => write!(f, "PING"),
+            Self::Ack {
+                largest,
+                delay,
+                ranges,
+            } => write!(
+                f,
+                "ACK: largest {}, delay {}, {} additional ranges",
+                largest, delay, ranges
+            ),
+            Self::Crypto { offset, data } => {
+                write!(f, "CRYPTO: offset {}, {} bytes", offset, data.len())
+            }
+            Self::ConnectionClose { error_code, reason } => {
+                write!(f, "CONNECTION_CLOSE: error 0x{:x} {}", error_code, reason)
+            }
+            Self::Other(typ) => write!(f, "Unsupported frame type 0x{:02x}", typ),
+        }
+    }
+}
+
+fn read_frames(payload: &[u8]) -> Result<Vec<QuicFrameItem<'_>>, FrameError> {
+    let mut reader = ByteReader::new(payload, "QUIC frame");
+    let mut frames = Vec::new();
+    while !reader.is_empty() {
+        let frame = match read_varint(&mut reader)? {
+            0x00 => {
+                let mut len = 1;
+                while reader.data().get(reader.pos()) == Some(&0) {
+                    reader.u8()?;
+                    len += 1;
+                }
+                QuicFrameItem::Padding(len)
+            }
+            0x01 => QuicFrameItem::Ping,
+            typ @ 0x02..=0x03 => {
+                let largest = read_varint(&mut reader)?;
+                let delay = read_varint(&mut reader)?;
+                let ranges = read_varint(&mut reader)?;
+                read_varint(&mut reader)?;
+                for _ in 0..ranges {
+                    read_varint(&mut reader)?;
+                    read_varint(&mut reader)?;
+                }
+                // ECN counts
+                if typ == 0x03 {
+                    for _ in 0..3 {
+                        read_varint(&mut reader)?;
+                    }
+                }
+                QuicFrameItem::Ack {
+                    largest,
+                    delay,
+                    ranges,
+                }
+            }
+            0x06 => {
+                let offset = read_varint(&mut reader)?;
+                let len = read_len(&mut reader)?;
+                QuicFrameItem::Crypto {
+                    offset,
+                    data: reader.bytes(len)?,
+                }
+            }
+            typ @ 0x1c..=0x1d => {
+                let error_code = read_varint(&mut reader)?;
+                // Only the transport variant names the frame that caused the error
+                if typ == 0x1c {
+                    read_varint(&mut reader)?;
+                }
+                let len = read_len(&mut reader)?;
+                QuicFrameItem::ConnectionClose {
+                    error_code,
+                    reason: String::from_utf8_lossy(reader.bytes(len)?).into_owned(),
+                }
+            }
+            other => {
+                frames.push(QuicFrameItem::Other(other));
+                break;
+            }
+        };
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+/// HKDF-Expand-Label from TLS 1.3, with an empty context.
+fn expand_label(secret: &[u8], label: &str, out: &mut [u8]) {
+    let label = format!("tls13 {}", label);
+    let mut info = Vec::with_capacity(4 + label.len());
+    info.extend_from_slice(&(out.len() as u16).to_be_bytes());
+    info.push(label.len() as u8);
+    info.extend_from_slice(label.as_bytes());
+    info.push(0);
+    // Secrets are SHA-256 outputs, so neither call can fail
+    Hkdf::<Sha256>::from_prk(secret)
+        .expect("secret shorter than a SHA-256 hash")
+        .expand(&info, out)
+        .expect("output too long for HKDF-SHA256");
+}
+
+/// Packet protection keys of one direction of a connection.
+pub struct QuicKeys {
+    key: [u8; 16],
+    iv: [u8; 12],
+    hp: [u8; 16],
+}
+
+impl QuicKeys {
+    /// Initial keys of the client, derived from the destination connection ID of
+    /// its first Initial packet (RFC 9001, section 5.2).
+    pub fn client_initial(version: u32, dcid: &[u8]) -> Option<Self> {
+        Self::initial(version, dcid, "client in")
+    }
+
+    /// Initial keys of the server, derived from the same connection ID as the
+    /// client keys, which is not the one found in the server packets.
+    pub fn server_initial(version: u32, original_dcid: &[u8]) -> Option<Self> {
+        Self::initial(version, original_dcid, "server in")
+    }
+
+    fn initial(version: u32, dcid: &[u8], label: &str) -> Option<Self> {
+        let (salt, prefix) = match version {
+            VERSION_1 => (&INITIAL_SALT_V1, "quic"),
+            VERSION_2 => (&INITIAL_SALT_V2, "quicv2"),
+            _ => return None,
+        };
+        let (initial_secret, _) = Hkdf::<Sha256>::extract(Some(salt), dcid);
+        let mut secret = [0; 32];
+        expand_label(&initial_secret, label, &mut secret);
+        let mut keys = Self {
+            key: [0; 16],
+            iv: [0; 12],
+            hp: [0; 16],
+        };
+        expand_label(&secret, &format!("{} key", prefix), &mut keys.key);
+        expand_label(&secret, &format!("{} iv", prefix), &mut keys.iv);
+        expand_label(&secret, &format!("{} hp", prefix), &mut keys.hp);
+        Some(keys)
+    }
+}
+
+/// Decrypted payload of a packet.
+pub struct QuicPlaintext {
+    packet_number: u64,
+    payload: Vec<u8>,
+}
+
+impl QuicPlaintext {
+    /// Packet number as truncated on the wire.
+    pub fn packet_number(&self) -> u64 {
+        self.packet_number
+    }
+
+    pub fn frames(&self) -> Result<Vec<QuicFrameItem<'_>>, FrameError> {
+        read_frames(&self.payload)
+    }
+
+    /// Data of the CRYPTO frames that is contiguous from offset 0, which holds the
+    /// start of the TLS handshake.
+    pub fn crypto(&self) -> Result<Vec<u8>, FrameError> {
+        let mut chunks: Vec<_> = self
+            .frames()?
+            .into_iter()
+            .filter_map(|frame| match frame {
+                QuicFrameItem::Crypto { offset, data } => Some((offset, data)),
+                _ => None,
+            })
+            .collect();
+        chunks.sort_by_key(|(offset, _)| *offset);
+        let mut stream = Vec::new();
+        for (offset, data) in chunks {
+            let end = offset + data.len() as u64;
+            if offset > stream.len() as u64 {
+                break;
+            }
+            if end > stream.len() as u64 {
+                stream.extend_from_slice(&data[(stream.len() as u64 - offset) as usize..]);
+            }
+        }
+        Ok(stream)
+    }
+}
+
+pub struct QuicPacket<'a> {
+    packet_type: QuicPacketType,
+    version: u32,
+    dcid: &'a [u8],
+    scid: &'a [u8],
+    token: &'a [u8],
+    // Whole packet, and the offset of the packet number in it
+    data: &'a [u8],
+    pn_offset: usize,
+}
+
+impl<'a> QuicPacket<'a> {
+    pub fn packet_type(&self) -> QuicPacketType {
+        self.packet_type
+    }
+
+    pub fn is_long_header(&self) -> bool {
+        self.data[0] & 0x80 != 0
+    }
+
+    /// Version of long header packets; 0 for short header packets.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Destination connection ID. Short header packets do not encode its length,
+    /// so it is left empty for them.
+    pub fn dcid(&self) -> &'a [u8] {
+        self.dcid
+    }
+
+    pub fn scid(&self) -> &'a [u8] {
+        self.scid
+    }
+
+    /// Address validation token of Initial packets.
+    pub fn token(&self) -> &'a [u8] {
+        self.token
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Versions offered by a Version Negotiation packet.
+    pub fn supported_versions(&self) -> Vec<u32> {
+        if self.packet_type != QuicPacketType::VersionNegotiation {
+            return Vec::new();
+        }
+        self.data[self.pn_offset..]
+            .chunks_exact(4)
+            .map(|version| u32::from_be_bytes(clone_into_array(version)))
+            .collect()
+    }
+
+    /// Removes header protection and decrypts the payload with `keys`.
+    pub fn decrypt(&self, keys: &QuicKeys) -> Result<QuicPlaintext, FrameError> {
+        let reader = ByteReader::new(self.data, "QUIC packet");
+        let sample = self
+            .data
+            .get(self.pn_offset + 4..self.pn_offset + 4 + SAMPLE_LEN)
+            .ok_or_else(|| reader.malformed("packet too short to sample"))?;
+        let mut mask = GenericArray::clone_from_slice(sample);
+        Aes128::new(GenericArray::from_slice(&keys.hp)).encrypt_block(&mut mask);
+
+        let mut header = self.data[..self.pn_offset].to_vec();
+        header[0] ^= mask[0] & if self.is_long_header() { 0x0F } else { 0x1F };
+        let pn_len = usize::from(header[0] & 0x03) + 1;
+        let mut packet_number = 0;
+        for (i, byte) in self.data[self.pn_offset..self.pn_offset + pn_len]
+            .iter()
+            .enumerate()
+        {
+            let byte = byte ^ mask[1 + i];
+            header.push(byte);
+            packet_number = packet_number << 8 | u64::from(byte);
+        }
+
+        let mut nonce = keys.iv;
+        for (n, pn) in nonce[4..].iter_mut().zip(&packet_number.to_be_bytes()) {
+            *n ^= pn;
+        }
+        let payload = Aes128Gcm::new(GenericArray::from_slice(&keys.key))
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &self.data[self.pn_offset + pn_len..],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| reader.malformed("decryption failed"))?;
+        Ok(QuicPlaintext {
+            packet_number,
+            payload,
+        })
+    }
+
+    /// Decrypts a client Initial packet with the keys derived from its destination
+    /// connection ID.
+    pub fn decrypt_initial(&self) -> Result<QuicPlaintext, FrameError> {
+        let reader = ByteReader::new(self.data, "QUIC Initial");
+        if self.packet_type != QuicPacketType::Initial {
+            return Err(reader.malformed("not an Initial packet"));
+        }
+        let keys = QuicKeys::client_initial(self.version, self.dcid)
+            .ok_or_else(|| reader.malformed("unsupported version"))?;
+        self.decrypt(&keys)
+    }
+
+    /// Reads the packet at the start of `reader`, which ends the datagram unless it
+    /// has a long header with a length.
+    fn read(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let start = reader.pos();
+        let first = reader.u8()?;
+        if first & 0x80 == 0 {
+            let data = &reader.data()[start..];
+            reader.rest();
+            return Ok(Self {
+                packet_type: QuicPacketType::OneRtt,
+                version: 0,
+                dcid: &[],
+                scid: &[],
+                token: &[],
+                data,
+                pn_offset: 1,
+            });
+        }
+        let version = reader.u32()?;
+        let len = usize::from(reader.u8()?);
+        let dcid = reader.bytes(len)?;
+        let len = usize::from(reader.u8()?);
+        let scid = reader.bytes(len)?;
+        // QUIC v2 shifts the type codes of v1 by one (RFC 9369, section 3.2)
+        let long_type = match version {
+            VERSION_2 => ((first >> 4) + 3) & 0x03,
+            _ => (first >> 4) & 0x03,
+        };
+        let packet_type = match (version, long_type) {
+            (0, _) => QuicPacketType::VersionNegotiation,
+            (_, 0) => QuicPacketType::Initial,
+            (_, 1) => QuicPacketType::ZeroRtt,
+            (_, 2) => QuicPacketType::Handshake,
+            _ => QuicPacketType::Retry,
+        };
+        let mut token: &[u8] = &[];
+        match packet_type {
+            QuicPacketType::VersionNegotiation | QuicPacketType::Retry => {
+                let pn_offset = reader.pos() - start;
+                reader.rest();
+                return Ok(Self {
+                    packet_type,
+                    version,
+                    dcid,
+                    scid,
+                    token,
+                    data: &reader.data()[start..],
+                    pn_offset,
+                });
+            }
+            QuicPacketType::Initial => {
+                let len = read_len(reader)?;
+                token = reader.bytes(len)?;
+            }
+            _ => {}
+        }
+        let len = read_len(reader)?;
+        let pn_offset = reader.pos() - start;
+        reader.bytes(len)?;
+        Ok(Self {
+            packet_type,
+            version,
+            dcid,
+            scid,
+            token,
+            data: &reader.data()[start..reader.pos()],
+            pn_offset,
+        })
+    }
+}
+
+/// UDP datagram holding one or more coalesced QUIC packets.
+pub struct QuicFrame<'a>(&'a [u8]);
+
+impl<'a> QuicFrame<'a> {
+    /// Whether `data` can start a QUIC packet: the fixed bit is set, except in
+    /// Version Negotiation packets.
+    pub fn is_quic(data: &[u8]) -> bool {
+        match data {
+            [first, 0, 0, 0, 0, ..] if first & 0x80 != 0 => true,
+            [first, ..] => first & 0x40 != 0,
+            [] => false,
+        }
+    }
+
+    pub fn packets(&self) -> Result<Vec<QuicPacket<'a>>, FrameError> {
+        let mut reader = ByteReader::new(self.0, "QUIC packet");
+        let mut packets = Vec::new();
+        // Datagrams are padded with zeros after the last packet
+        while !reader.is_empty() && reader.data()[reader.pos()] != 0 {
+            packets.push(QuicPacket::read(&mut reader)?);
+        }
+        Ok(packets)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuicFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        if !Self::is_quic(slice) {
+            let reader = ByteReader::new(slice, "QUIC packet");
+            return Err(reader.malformed("fixed bit not set"));
+        }
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for QuicFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn initial_keys() {
+        // RFC 9001, appendix A.1, and RFC 9369, appendix A.1
+        let dcid = hex("8394c8f03e515708");
+        let cases = [
+            (
+                QuicKeys::client_initial(VERSION_1, &dcid),
+                "1f369613dd76d5467730efcbe3b1a22d",
+                "fa044b2f42a3fd3b46fb255c",
+                "9f50449e04a0e810283a1e9933adedd2",
+            ),
+            (
+                QuicKeys::server_initial(VERSION_1, &dcid),
+                "cf3a5331653c364c88f0f379b6067e37",
+                "0ac1493ca1905853b0bba03e",
+                "c206b8d9b9f0f37644430b490eeaa314",
+            ),
+            (
+                QuicKeys::client_initial(VERSION_2, &dcid),
+                "8b1a0bc121284290a29e0971b5cd045d",
+                "91f73e2351d8fa91660e909f",
+                "45b95e15235d6f45a6b19cbcb0294ba9",
+            ),
+            (
+                QuicKeys::server_initial(VERSION_2, &dcid),
+                "82db637861d55e1d011f19ea71d5d2a7",
+                "dd13c276499c0249d3310652",
+                "edf6d05c83121201b436e16877593c3a",
+            ),
+        ];
+        for (keys, key, iv, hp) in cases.iter() {
+            let keys = keys.as_ref().unwrap();
+            assert_eq!(keys.key.to_vec(), hex(key));
+            assert_eq!(keys.iv.to_vec(), hex(iv));
+            assert_eq!(keys.hp.to_vec(), hex(hp));
+        }
+        assert!(QuicKeys::client_initial(0xff00_001d, &dcid).is_none());
+    }
+
+    #[test]
+    fn decrypt_client_initial() {
+        // RFC 9001, appendix A.2
+        let datagram = hex(concat!(
+            "c000000001088394c8f03e5157080000449e7b9aec34d1b1c98dd7689fb8ec11",
+            "d242b123dc9bd8bab936b47d92ec356c0bab7df5976d27cd449f63300099f399",
+            "1c260ec4c60d17b31f8429157bb35a1282a643a8d2262cad67500cadb8e7378c",
+            "8eb7539ec4d4905fed1bee1fc8aafba17c750e2c7ace01e6005f80fcb7df6212",
+            "30c83711b39343fa028cea7f7fb5ff89eac2308249a02252155e2347b63d58c5",
+            "457afd84d05dfffdb20392844ae812154682e9cf012f9021a6f0be17ddd0c208",
+            "4dce25ff9b06cde535d0f920a2db1bf362c23e596d11a4f5a6cf3948838a3aec",
+            "4e15daf8500a6ef69ec4e3feb6b1d98e610ac8b7ec3faf6ad760b7bad1db4ba3",
+            "485e8a94dc250ae3fdb41ed15fb6a8e5eba0fc3dd60bc8e30c5c4287e53805db",
+            "059ae0648db2f64264ed5e39be2e20d82df566da8dd5998ccabdae053060ae6c",
+            "7b4378e846d29f37ed7b4ea9ec5d82e7961b7f25a9323851f681d582363aa5f8",
+            "9937f5a67258bf63ad6f1a0b1d96dbd4faddfcefc5266ba6611722395c906556",
+            "be52afe3f565636ad1b17d508b73d8743eeb524be22b3dcbc2c7468d54119c74",
+            "68449a13d8e3b95811a198f3491de3e7fe942b330407abf82a4ed7c1b311663a",
+            "c69890f4157015853d91e923037c227a33cdd5ec281ca3f79c44546b9d90ca00",
+            "f064c99e3dd97911d39fe9c5d0b23a229a234cb36186c4819e8b9c5927726632",
+            "291d6a418211cc2962e20fe47feb3edf330f2c603a9d48c0fcb5699dbfe58964",
+            "25c5bac4aee82e57a85aaf4e2513e4f05796b07ba2ee47d80506f8d2c25e50fd",
+            "14de71e6c418559302f939b0e1abd576f279c4b2e0feb85c1f28ff18f58891ff",
+            "ef132eef2fa09346aee33c28eb130ff28f5b766953334113211996d20011a198",
+            "e3fc433f9f2541010ae17c1bf202580f6047472fb36857fe843b19f5984009dd",
+            "c324044e847a4f4a0ab34f719595de37252d6235365e9b84392b061085349d73",
+            "203a4a13e96f5432ec0fd4a1ee65accdd5e3904df54c1da510b0ff20dcc0c77f",
+            "cb2c0e0eb605cb0504db87632cf3d8b4dae6e705769d1de354270123cb11450e",
+            "fc60ac47683d7b8d0f811365565fd98c4c8eb936bcab8d069fc33bd801b03ade",
+            "a2e1fbc5aa463d08ca19896d2bf59a071b851e6c239052172f296bfb5e724047",
+            "90a2181014f3b94a4e97d117b438130368cc39dbb2d198065ae3986547926cd2",
+            "162f40a29f0c3c8745c0f50fba3852e566d44575c29d39a03f0cda721984b6f4",
+            "40591f355e12d439ff150aab7613499dbd49adabc8676eef023b15b65bfc5ca0",
+            "6948109f23f350db82123535eb8a7433bdabcb909271a6ecbcb58b936a88cd4e",
+            "8f2e6ff5800175f113253d8fa9ca8885c2f552e657dc603f252e1a8e308f76f0",
+            "be79e2fb8f5d5fbbe2e30ecadd220723c8c0aea8078cdfcb3868263ff8f09400",
+            "54da48781893a7e49ad5aff4af300cd804a6b6279ab3ff3afb64491c85194aab",
+            "760d58a606654f9f4400e8b38591356fbf6425aca26dc85244259ff2b19c41b9",
+            "f96f3ca9ec1dde434da7d2d392b905ddf3d1f9af93d1af5950bd493f5aa731b4",
+            "056df31bd267b6b90a079831aaf579be0a39013137aac6d404f518cfd4684064",
+            "7e78bfe706ca4cf5e9c5453e9f7cfd2b8b4c8d169a44e55c88d4a9a7f9474241",
+            "e221af44860018ab0856972e194cd934",
+        ));
+        let frame = QuicFrame::try_from(datagram.as_slice()).unwrap();
+        let packets = frame.packets().unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].packet_type(), QuicPacketType::Initial);
+        let plaintext = packets[0].decrypt_initial().unwrap();
+        assert_eq!(plaintext.packet_number(), 2);
+        let crypto = plaintext.crypto().unwrap();
+        assert_eq!(crypto.len(), 0xf1);
+        assert_eq!(crypto[..12], hex("010000ed0303ebf8fa56f129")[..]);
+    }
+}
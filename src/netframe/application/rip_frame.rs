@@ -0,0 +1,227 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::*;
+
+const RIP_HEADER_LEN: usize = 4;
+const RIP_ENTRY_LEN: usize = 20;
+/// Address family of the entries carrying authentication data instead of a route.
+const AUTH_FAMILY: u16 = 0xFFFF;
+/// Metric of unreachable destinations, also used by requests for the whole table.
+const INFINITY: u32 = 16;
+/// Metric of RIPng entries giving the next hop of the routes following them.
+const NEXT_HOP_METRIC: u8 = 0xFF;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RipCommand {
+    Request,  // 1
+    Response, // 2
+    Other(u8),
+}
+
+impl From<u8> for RipCommand {
+    fn from(command: u8) -> Self {
+        match command {
+            1 => RipCommand::Request,
+            2 => RipCommand::Response,
+            other => RipCommand::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for RipCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RipCommand::Request => write!(f, "Request"),
+            RipCommand::Response => write!(f, "Response"),
+            RipCommand::Other(command) => write!(f, "Unknown command ({})", command),
+        }
+    }
+}
+
+pub enum RipEntry<'a> {
+    /// Route of RIPv1 and RIPv2, RIPv1 leaving the tag, mask and next hop zeroed.
+    Route {
+        family: u16,
+        tag: u16,
+        address: Ipv4Addr,
+        mask: Ipv4Addr,
+        next_hop: Ipv4Addr,
+        metric: u32,
+    },
+    /// Authentication of RIPv2 (RFC 2453 and RFC 4822), either heading the entries
+    /// or trailing them with the digest of a keyed authentication.
+    Authentication { auth_type: u16, data: &'a [u8] },
+    /// Route of RIPng.
+    Ipv6Route {
+        prefix: Ipv6Addr,
+        tag: u16,
+        prefix_len: u8,
+        metric: u8,
+    },
+    /// Next hop of the RIPng routes following it, the sender itself if unspecified.
+    NextHop(Ipv6Addr),
+}
+
+impl<'a> fmt::Display for RipEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let metric = |f: &mut fmt::Formatter<'_>, metric: u32| match metric {
+            INFINITY => write!(f, ", metric 16 (unreachable)"),
+            metric => write!(f, ", metric {}", metric),
+        };
+        match self {
+            RipEntry::Route {
+                family: 0,
+                metric: INFINITY,
+                ..
+            } => write!(f, "Whole routing table"),
+            RipEntry::Route {
+                tag,
+                address,
+                mask,
+                next_hop,
+                metric: value,
+                ..
+            } => {
+                write!(f, "{}", address)?;
+                if !mask.is_unspecified() {
+                    write!(f, "/{}", u32::from(*mask).count_ones())?;
+                }
+                if !next_hop.is_unspecified() {
+                    write!(f, " via {}", next_hop)?;
+                }
+                metric(f, *value)?;
+                if *tag != 0 {
+                    write!(f, ", tag {}", tag)?;
+                }
+                Ok(())
+            }
+            RipEntry::Authentication { auth_type, data } => match auth_type {
+                2 => write!(
+                    f,
+                    "Password: {}",
+                    String::from_utf8_lossy(data).trim_end_matches('\0')
+                ),
+                3 => write!(f, "Keyed message digest"),
+                1 => write!(f, "Authentication data: {}", to_hex(data)),
+                other => write!(f, "Authentication type {}", other),
+            },
+            RipEntry::Ipv6Route {
+                prefix: Ipv6Addr::UNSPECIFIED,
+                prefix_len: 0,
+                metric: 16,
+                ..
+            } => write!(f, "Whole routing table"),
+            RipEntry::Ipv6Route {
+                prefix,
+                tag,
+                prefix_len,
+                metric: value,
+            } => {
+                write!(f, "{}/{}", prefix, prefix_len)?;
+                metric(f, u32::from(*value))?;
+                if *tag != 0 {
+                    write!(f, ", tag {}", tag)?;
+                }
+                Ok(())
+            }
+            RipEntry::NextHop(addr) if addr.is_unspecified() => write!(f, "Next hop: sender"),
+            RipEntry::NextHop(addr) => write!(f, "Next hop: {}", addr),
+        }
+    }
+}
+
+impl<'a> RipEntry<'a> {
+    fn read(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let family = reader.u16()?;
+        if family == AUTH_FAMILY {
+            return Ok(RipEntry::Authentication {
+                auth_type: reader.u16()?,
+                data: reader.bytes(RIP_ENTRY_LEN - 4)?,
+            });
+        }
+        Ok(RipEntry::Route {
+            family,
+            tag: reader.u16()?,
+            address: Ipv4Addr::from(reader.u32()?),
+            mask: Ipv4Addr::from(reader.u32()?),
+            next_hop: Ipv4Addr::from(reader.u32()?),
+            metric: reader.u32()?,
+        })
+    }
+
+    fn read_ng(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let prefix = Ipv6Addr::from(clone_into_array::<[u8; 16], u8>(reader.bytes(16)?));
+        let tag = reader.u16()?;
+        let prefix_len = reader.u8()?;
+        match reader.u8()? {
+            NEXT_HOP_METRIC => Ok(RipEntry::NextHop(prefix)),
+            metric => Ok(RipEntry::Ipv6Route {
+                prefix,
+                tag,
+                prefix_len,
+                metric,
+            }),
+        }
+    }
+}
+
+/// Routing Information Protocol message, version 1 or 2 (RFC 2453) on port 520, or
+/// RIPng (RFC 2080) on port 521.
+pub struct RipFrame<'a> {
+    ng: bool,
+    header: &'a [u8],
+    entries: Vec<RipEntry<'a>>,
+}
+
+impl<'a> RipFrame<'a> {
+    pub fn command(&self) -> RipCommand {
+        RipCommand::from(self.header[0])
+    }
+
+    pub fn version(&self) -> u8 {
+        self.header[1]
+    }
+
+    /// Whether the message is a RIPng one, carrying IPv6 routes.
+    pub fn is_ng(&self) -> bool {
+        self.ng
+    }
+
+    pub fn entries(&self) -> &[RipEntry<'a>] {
+        &self.entries
+    }
+
+    fn parse(slice: &'a [u8], ng: bool) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(slice, "RIP header");
+        let header = reader.bytes(RIP_HEADER_LEN)?;
+        match (ng, header[1]) {
+            (false, 1) | (false, 2) | (true, 1) => {}
+            _ => return Err(reader.malformed("unsupported version")),
+        }
+        if reader.remaining() % RIP_ENTRY_LEN != 0 {
+            return Err(reader.malformed("length not a multiple of the entry size"));
+        }
+        let mut reader = ByteReader::new(reader.rest(), "RIP entry");
+        let mut entries = Vec::new();
+        while !reader.is_empty() {
+            entries.push(match ng {
+                false => RipEntry::read(&mut reader)?,
+                true => RipEntry::read_ng(&mut reader)?,
+            });
+        }
+        Ok(Self {
+            ng,
+            header,
+            entries,
+        })
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for RipFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        let ng = frame.src_port() == 521 || frame.dest_port() == 521;
+        Self::parse(frame.payload(), ng)
+    }
+}
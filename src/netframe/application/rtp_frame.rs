@@ -0,0 +1,341 @@
+use chrono::{DateTime, Utc};
+
+use super::*;
+
+const RTP_VERSION: u8 = 2;
+const RTP_HEADER_LEN: usize = 12;
+
+/// RTP data packet (RFC 3550).
+pub struct RtpFrame<'a> {
+    header: &'a [u8],
+    extension: Option<&'a [u8]>,
+    payload: &'a [u8],
+}
+
+impl<'a> RtpFrame<'a> {
+    /// Whether `data` looks like an RTP packet: version 2, a static or dynamic
+    /// payload type, and room for the CSRCs it announces.
+    pub fn is_rtp(data: &[u8]) -> bool {
+        if data.len() < RTP_HEADER_LEN || data[0] >> 6 != RTP_VERSION {
+            return false;
+        }
+        let payload_type = data[1] & 0x7F;
+        let known_type =
+            rtp_static_payload(payload_type).is_some() || (96..=127).contains(&payload_type);
+        known_type && data.len() >= RTP_HEADER_LEN + usize::from(data[0] & 0x0F) * 4
+    }
+
+    pub fn version(&self) -> u8 {
+        self.header[0] >> 6
+    }
+
+    pub fn padding(&self) -> bool {
+        self.header[0] & 0b0010_0000 != 0
+    }
+
+    pub fn has_extension(&self) -> bool {
+        self.header[0] & 0b0001_0000 != 0
+    }
+
+    pub fn csrc_count(&self) -> u8 {
+        self.header[0] & 0x0F
+    }
+
+    pub fn marker(&self) -> bool {
+        self.header[1] & 0b1000_0000 != 0
+    }
+
+    pub fn payload_type(&self) -> u8 {
+        self.header[1] & 0x7F
+    }
+
+    pub fn seq_num(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[4..8]))
+    }
+
+    pub fn ssrc(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[8..12]))
+    }
+
+    pub fn csrcs(&self) -> Vec<u32> {
+        self.header[RTP_HEADER_LEN..]
+            .chunks_exact(4)
+            .map(|csrc| u32::from_be_bytes(clone_into_array(csrc)))
+            .collect()
+    }
+
+    /// Profile specific identifier and data of the header extension.
+    pub fn extension(&self) -> Option<(u16, &'a [u8])> {
+        let extension = self.extension?;
+        Some((
+            u16::from_be_bytes(clone_into_array(&extension[..2])),
+            &extension[4..],
+        ))
+    }
+
+    /// Payload without the padding.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for RtpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "RTP header");
+        let first = reader.u8()?;
+        if first >> 6 != RTP_VERSION {
+            return Err(reader.malformed("unsupported version"));
+        }
+        reader.seek(RTP_HEADER_LEN + usize::from(first & 0x0F) * 4)?;
+        let header = &slice[..reader.pos()];
+        let mut extension = None;
+        if first & 0b0001_0000 != 0 {
+            let start = reader.pos();
+            reader.u16()?;
+            let len = usize::from(reader.u16()?) * 4;
+            reader.bytes(len)?;
+            extension = Some(&slice[start..reader.pos()]);
+        }
+        let mut payload = reader.rest();
+        if first & 0b0010_0000 != 0 {
+            // The last byte counts the padding bytes, itself included
+            let padding = usize::from(payload.last().copied().unwrap_or_default());
+            if padding == 0 || padding > payload.len() {
+                return Err(reader.malformed("invalid padding length"));
+            }
+            payload = &payload[..payload.len() - padding];
+        }
+        Ok(Self {
+            header,
+            extension,
+            payload,
+        })
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for RtpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+/// Reception statistics about one source, sent in sender and receiver reports.
+pub struct RtcpReportBlock {
+    pub ssrc: u32,
+    /// Fraction of the packets lost since the previous report, out of 256.
+    pub fraction_lost: u8,
+    pub cumulative_lost: i32,
+    pub highest_seq: u32,
+    /// Interarrival jitter in timestamp units.
+    pub jitter: u32,
+    /// Middle 32 bits of the NTP timestamp of the last sender report received.
+    pub last_sr: u32,
+    /// Delay since the last sender report, in units of 1/65536 seconds.
+    pub delay_since_last_sr: u32,
+}
+
+fn read_report_blocks(
+    reader: &mut ByteReader,
+    count: u8,
+) -> Result<Vec<RtcpReportBlock>, FrameError> {
+    let mut blocks = Vec::new();
+    for _ in 0..count {
+        let ssrc = reader.u32()?;
+        let fraction_lost = reader.u8()?;
+        // 24 bit signed integer
+        let cumulative_lost = ((reader.u24()? << 8) as i32) >> 8;
+        blocks.push(RtcpReportBlock {
+            ssrc,
+            fraction_lost,
+            cumulative_lost,
+            highest_seq: reader.u32()?,
+            jitter: reader.u32()?,
+            last_sr: reader.u32()?,
+            delay_since_last_sr: reader.u32()?,
+        });
+    }
+    Ok(blocks)
+}
+
+pub fn sdes_item_name(item: u8) -> Option<&'static str> {
+    let name = match item {
+        1 => "CNAME",
+        2 => "NAME",
+        3 => "EMAIL",
+        4 => "PHONE",
+        5 => "LOC",
+        6 => "TOOL",
+        7 => "NOTE",
+        8 => "PRIV",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Source description items of one SSRC or CSRC.
+pub struct RtcpSdesChunk<'a> {
+    pub ssrc: u32,
+    pub items: Vec<(u8, &'a [u8])>,
+}
+
+pub enum RtcpPacket<'a> {
+    SenderReport {
+        ssrc: u32,
+        /// Wallclock time at which the report was sent.
+        ntp_time: Option<DateTime<Utc>>,
+        rtp_timestamp: u32,
+        packet_count: u32,
+        octet_count: u32,
+        reports: Vec<RtcpReportBlock>,
+    }, // 200
+    ReceiverReport {
+        ssrc: u32,
+        reports: Vec<RtcpReportBlock>,
+    }, // 201
+    SourceDescription(Vec<RtcpSdesChunk<'a>>), // 202
+    Bye {
+        ssrcs: Vec<u32>,
+        reason: Option<&'a [u8]>,
+    }, // 203
+    Other(u8, &'a [u8]),
+}
+
+impl<'a> fmt::Display for RtcpPacket<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SenderReport { .. } => write!(f, "Sender Report"),
+            Self::ReceiverReport { .. } => write!(f, "Receiver Report"),
+            Self::SourceDescription(_) => write!(f, "Source Description"),
+            Self::Bye { .. } => write!(f, "Goodbye"),
+            Self::Other(204, _) => write!(f, "Application Defined"),
+            Self::Other(typ, _) => write!(f, "Unknown ({})", typ),
+        }
+    }
+}
+
+impl<'a> RtcpPacket<'a> {
+    fn read(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        let first = reader.u8()?;
+        if first >> 6 != RTP_VERSION {
+            return Err(reader.malformed("unsupported version"));
+        }
+        let count = first & 0x1F;
+        let typ = reader.u8()?;
+        let len = usize::from(reader.u16()?) * 4;
+        let mut body = reader.sub(len)?;
+        if first & 0b0010_0000 != 0 {
+            let data = body.data();
+            let padding = usize::from(data.last().copied().unwrap_or_default());
+            if padding == 0 || padding > data.len() {
+                return Err(body.malformed("invalid padding length"));
+            }
+            body = ByteReader::new(&data[..data.len() - padding], "RTCP packet");
+        }
+        Ok(match typ {
+            200 => Self::SenderReport {
+                ssrc: body.u32()?,
+                ntp_time: ntp_frame::ntp_time(body.u64()?),
+                rtp_timestamp: body.u32()?,
+                packet_count: body.u32()?,
+                octet_count: body.u32()?,
+                reports: read_report_blocks(&mut body, count)?,
+            },
+            201 => Self::ReceiverReport {
+                ssrc: body.u32()?,
+                reports: read_report_blocks(&mut body, count)?,
+            },
+            202 => {
+                let mut chunks = Vec::new();
+                for _ in 0..count {
+                    let ssrc = body.u32()?;
+                    let mut items = Vec::new();
+                    loop {
+                        let item = body.u8()?;
+                        if item == 0 {
+                            break;
+                        }
+                        let len = usize::from(body.u8()?);
+                        items.push((item, body.bytes(len)?));
+                    }
+                    // Chunks end on a 32 bit boundary
+                    let aligned = body.pos().div_ceil(4) * 4;
+                    body.seek(aligned.min(body.data().len()))?;
+                    chunks.push(RtcpSdesChunk { ssrc, items });
+                }
+                Self::SourceDescription(chunks)
+            }
+            203 => {
+                let mut ssrcs = Vec::new();
+                for _ in 0..count {
+                    ssrcs.push(body.u32()?);
+                }
+                let reason = if body.is_empty() {
+                    None
+                } else {
+                    let len = usize::from(body.u8()?);
+                    Some(body.bytes(len)?)
+                };
+                Self::Bye { ssrcs, reason }
+            }
+            other => Self::Other(other, body.rest()),
+        })
+    }
+}
+
+/// Compound RTCP packet, made of several RTCP packets sent together.
+pub struct RtcpFrame<'a>(&'a [u8]);
+
+impl<'a> RtcpFrame<'a> {
+    /// Whether `data` is a compound RTCP packet: it starts with a sender or
+    /// receiver report and the lengths of its packets add up to its size.
+    pub fn is_rtcp(data: &[u8]) -> bool {
+        if data.len() < 8 || !matches!(data[1], 200 | 201) {
+            return false;
+        }
+        let mut pos = 0;
+        while pos + 4 <= data.len() {
+            if data[pos] >> 6 != RTP_VERSION || !(192..=223).contains(&data[pos + 1]) {
+                return false;
+            }
+            pos += (usize::from(u16::from_be_bytes([data[pos + 2], data[pos + 3]])) + 1) * 4;
+        }
+        pos == data.len()
+    }
+
+    pub fn packets(&self) -> Result<Vec<RtcpPacket<'a>>, FrameError> {
+        let mut reader = ByteReader::new(self.0, "RTCP packet");
+        let mut packets = Vec::new();
+        while !reader.is_empty() {
+            packets.push(RtcpPacket::read(&mut reader)?);
+        }
+        Ok(packets)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for RtcpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        if !Self::is_rtcp(slice) {
+            let reader = ByteReader::new(slice, "RTCP packet");
+            return Err(reader.malformed("not a compound RTCP packet"));
+        }
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for RtcpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
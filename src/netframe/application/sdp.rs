@@ -0,0 +1,219 @@
+use std::net::{IpAddr, SocketAddr};
+use std::str;
+
+use super::*;
+
+/// Connection data (`c=` line).
+#[derive(Clone)]
+pub struct SdpConnection<'a> {
+    pub addr_type: &'a str,
+    /// Address, possibly followed by a TTL and an address count for multicast.
+    pub address: &'a str,
+}
+
+impl<'a> SdpConnection<'a> {
+    /// The address without the multicast suffixes, if it is not a host name.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.address.split('/').next()?.parse().ok()
+    }
+}
+
+impl<'a> fmt::Display for SdpConnection<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.addr_type, self.address)
+    }
+}
+
+/// Payload format bound by an `a=rtpmap` attribute.
+pub struct SdpRtpMap<'a> {
+    pub payload_type: u8,
+    pub encoding: &'a str,
+    pub clock_rate: u32,
+    pub channels: Option<u8>,
+}
+
+impl<'a> fmt::Display for SdpRtpMap<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.encoding, self.clock_rate)?;
+        if let Some(channels) = self.channels {
+            write!(f, "/{}", channels)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encoding and clock rate of the static RTP payload types (RFC 3551).
+pub fn rtp_static_payload(payload_type: u8) -> Option<(&'static str, u32)> {
+    let format = match payload_type {
+        0 => ("PCMU", 8000),
+        3 => ("GSM", 8000),
+        4 => ("G723", 8000),
+        5 => ("DVI4", 8000),
+        6 => ("DVI4", 16000),
+        7 => ("LPC", 8000),
+        8 => ("PCMA", 8000),
+        9 => ("G722", 8000),
+        10 => ("L16", 44100),
+        11 => ("L16", 44100),
+        12 => ("QCELP", 8000),
+        13 => ("CN", 8000),
+        14 => ("MPA", 90000),
+        15 => ("G728", 8000),
+        16 => ("DVI4", 11025),
+        17 => ("DVI4", 22050),
+        18 => ("G729", 8000),
+        25 => ("CelB", 90000),
+        26 => ("JPEG", 90000),
+        28 => ("nv", 90000),
+        31 => ("H261", 90000),
+        32 => ("MPV", 90000),
+        33 => ("MP2T", 90000),
+        34 => ("H263", 90000),
+        _ => return None,
+    };
+    Some(format)
+}
+
+/// Media description (`m=` line and the lines up to the next one).
+pub struct SdpMedia<'a> {
+    pub media: &'a str,
+    pub port: u16,
+    pub proto: &'a str,
+    pub formats: Vec<&'a str>,
+    pub connection: Option<SdpConnection<'a>>,
+    pub rtpmaps: Vec<SdpRtpMap<'a>>,
+    /// Attributes other than `rtpmap`, as name and value.
+    pub attributes: Vec<(&'a str, Option<&'a str>)>,
+}
+
+impl<'a> SdpMedia<'a> {
+    /// Name of the payload format `format`, from its `rtpmap` attribute or else
+    /// from the static payload types.
+    pub fn codec(&self, format: &str) -> Option<String> {
+        let payload_type = format.parse().ok()?;
+        match self
+            .rtpmaps
+            .iter()
+            .find(|map| map.payload_type == payload_type)
+        {
+            Some(map) => Some(map.to_string()),
+            None => rtp_static_payload(payload_type)
+                .map(|(encoding, rate)| format!("{}/{}", encoding, rate)),
+        }
+    }
+
+    /// Whether the stream is carried by RTP, as opposed to for instance T.38 over
+    /// UDPTL.
+    pub fn is_rtp(&self) -> bool {
+        self.proto.starts_with("RTP/")
+    }
+
+    /// Port 0 rejects or disables the stream.
+    pub fn is_disabled(&self) -> bool {
+        self.port == 0
+    }
+}
+
+/// Session description of RFC 4566, as carried by SIP bodies.
+pub struct SdpSession<'a> {
+    pub version: &'a str,
+    pub origin: Option<&'a str>,
+    pub session_name: Option<&'a str>,
+    pub connection: Option<SdpConnection<'a>>,
+    pub media: Vec<SdpMedia<'a>>,
+}
+
+impl<'a> SdpSession<'a> {
+    /// Address a media stream is to be sent to, from the connection data of the
+    /// media or else of the session.
+    pub fn endpoint(&self, media: &SdpMedia) -> Option<SocketAddr> {
+        let connection = media.connection.as_ref().or(self.connection.as_ref())?;
+        Some(SocketAddr::new(connection.ip()?, media.port))
+    }
+}
+
+fn read_connection<'a>(
+    value: &'a str,
+    reader: &ByteReader,
+) -> Result<SdpConnection<'a>, FrameError> {
+    match value.split(' ').collect::<Vec<_>>().as_slice() {
+        ["IN", addr_type, address] => Ok(SdpConnection { addr_type, address }),
+        _ => Err(reader.malformed("invalid connection data")),
+    }
+}
+
+fn read_rtpmap<'a>(value: &'a str, reader: &ByteReader) -> Result<SdpRtpMap<'a>, FrameError> {
+    let malformed = || reader.malformed("invalid rtpmap attribute");
+    let (payload_type, format) = value.split_once(' ').ok_or_else(malformed)?;
+    let mut parts = format.trim().split('/');
+    let encoding = parts.next().ok_or_else(malformed)?;
+    let clock_rate = parts.next().and_then(|rate| rate.parse().ok());
+    Ok(SdpRtpMap {
+        payload_type: payload_type.parse().map_err(|_| malformed())?,
+        encoding,
+        clock_rate: clock_rate.ok_or_else(malformed)?,
+        channels: parts.next().and_then(|channels| channels.parse().ok()),
+    })
+}
+
+impl<'a> TryFrom<&'a [u8]> for SdpSession<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let reader = ByteReader::new(slice, "SDP");
+        let text = str::from_utf8(slice).map_err(|_| reader.malformed("non UTF-8 text"))?;
+        let mut session = SdpSession {
+            version: "",
+            origin: None,
+            session_name: None,
+            connection: None,
+            media: Vec::new(),
+        };
+        for line in text.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            let (typ, value) = line
+                .split_once('=')
+                .ok_or_else(|| reader.malformed("line without an equal sign"))?;
+            match (typ, session.media.last_mut()) {
+                ("v", _) => session.version = value,
+                ("o", _) => session.origin = Some(value),
+                ("s", _) => session.session_name = Some(value),
+                ("c", None) => session.connection = Some(read_connection(value, &reader)?),
+                ("c", Some(media)) => media.connection = Some(read_connection(value, &reader)?),
+                ("m", _) => {
+                    let mut fields = value.split(' ');
+                    let media = fields.next().unwrap_or_default();
+                    // The port may be followed by a count of ports
+                    let port = fields
+                        .next()
+                        .and_then(|port| port.split('/').next()?.parse().ok())
+                        .ok_or_else(|| reader.malformed("invalid media port"))?;
+                    let proto = fields
+                        .next()
+                        .ok_or_else(|| reader.malformed("missing media protocol"))?;
+                    session.media.push(SdpMedia {
+                        media,
+                        port,
+                        proto,
+                        formats: fields.collect(),
+                        connection: None,
+                        rtpmaps: Vec::new(),
+                        attributes: Vec::new(),
+                    });
+                }
+                ("a", Some(media)) => match value.split_once(':') {
+                    Some(("rtpmap", map)) => media.rtpmaps.push(read_rtpmap(map, &reader)?),
+                    Some((name, value)) => media.attributes.push((name, Some(value))),
+                    None => media.attributes.push((value, None)),
+                },
+                _ => {}
+            }
+        }
+        if session.version.is_empty() {
+            return Err(reader.malformed("missing version line"));
+        }
+        Ok(session)
+    }
+}
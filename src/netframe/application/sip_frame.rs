@@ -0,0 +1,226 @@
+use super::*;
+
+const METHODS: [&str; 14] = [
+    "INVITE",
+    "ACK",
+    "BYE",
+    "CANCEL",
+    "REGISTER",
+    "OPTIONS",
+    "PRACK",
+    "SUBSCRIBE",
+    "NOTIFY",
+    "PUBLISH",
+    "INFO",
+    "REFER",
+    "MESSAGE",
+    "UPDATE",
+];
+const VERSION: &str = "SIP/2.0";
+
+/// Full name of the headers that have a compact form (RFC 3261, section 7.3.3).
+fn compact_form(name: &str) -> Option<&'static str> {
+    let full = match name {
+        "i" | "I" => "Call-ID",
+        "m" | "M" => "Contact",
+        "e" | "E" => "Content-Encoding",
+        "l" | "L" => "Content-Length",
+        "c" | "C" => "Content-Type",
+        "f" | "F" => "From",
+        "s" | "S" => "Subject",
+        "k" | "K" => "Supported",
+        "t" | "T" => "To",
+        "v" | "V" => "Via",
+        _ => return None,
+    };
+    Some(full)
+}
+
+pub enum SipStartLine<'a> {
+    Request { method: &'a str, uri: &'a str },
+    Response { status: u16, reason: &'a str },
+}
+
+impl<'a> fmt::Display for SipStartLine<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request { method, uri } => write!(f, "{} {} {}", method, uri, VERSION),
+            Self::Response { status, reason } => write!(f, "{} {} {}", VERSION, status, reason),
+        }
+    }
+}
+
+/// SIP message. Over TCP only the first message of a segment is read, and its
+/// body is bounded by its Content-Length.
+pub struct SipFrame<'a> {
+    start: SipStartLine<'a>,
+    headers: Vec<(&'a str, &'a str)>,
+    body: &'a [u8],
+}
+
+impl<'a> SipFrame<'a> {
+    /// Whether `data` begins with a SIP request or status line.
+    pub fn is_sip(data: &[u8]) -> bool {
+        let line = data.split(|&c| c == b'\n').next().unwrap_or_default();
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        line.starts_with(b"SIP/2.0 ")
+            || (line.ends_with(b" SIP/2.0")
+                && METHODS.iter().any(|method| {
+                    line.starts_with(method.as_bytes()) && line.get(method.len()) == Some(&b' ')
+                }))
+    }
+
+    pub fn start_line(&self) -> &SipStartLine<'a> {
+        &self.start
+    }
+
+    pub fn method(&self) -> Option<&'a str> {
+        match self.start {
+            SipStartLine::Request { method, .. } => Some(method),
+            SipStartLine::Response { .. } => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self.start {
+            SipStartLine::Request { .. } => None,
+            SipStartLine::Response { status, .. } => Some(status),
+        }
+    }
+
+    /// Header fields in the order they were sent, with compact names expanded.
+    pub fn headers(&self) -> &[(&'a str, &'a str)] {
+        &self.headers
+    }
+
+    /// Values of every header named `name`, ignoring case. Headers repeated in a
+    /// single comma separated field are not split.
+    pub fn header_values(&self, name: &str) -> Vec<&'a str> {
+        self.headers
+            .iter()
+            .filter(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+            .collect()
+    }
+
+    /// Value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.header_values(name).first().copied()
+    }
+
+    /// Via headers, from the last hop to the first one.
+    pub fn via(&self) -> Vec<&'a str> {
+        self.header_values("Via")
+    }
+
+    pub fn from(&self) -> Option<&'a str> {
+        self.header("From")
+    }
+
+    pub fn to(&self) -> Option<&'a str> {
+        self.header("To")
+    }
+
+    /// Identifier shared by every message of a call.
+    pub fn call_id(&self) -> Option<&'a str> {
+        self.header("Call-ID")
+    }
+
+    /// Sequence number and method of the CSeq header.
+    pub fn cseq(&self) -> Option<(u32, &'a str)> {
+        let (seq, method) = self.header("CSeq")?.split_once(char::is_whitespace)?;
+        Some((seq.parse().ok()?, method.trim()))
+    }
+
+    pub fn contact(&self) -> Option<&'a str> {
+        self.header("Contact")
+    }
+
+    pub fn content_type(&self) -> Option<&'a str> {
+        self.header("Content-Type")
+    }
+
+    pub fn content_length(&self) -> Option<usize> {
+        self.header("Content-Length")?.parse().ok()
+    }
+
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// Session description of the body, if it has one.
+    pub fn sdp(&self) -> Option<Result<SdpSession<'a>, FrameError>> {
+        let content_type = self.content_type()?.split(';').next()?.trim();
+        if !content_type.eq_ignore_ascii_case("application/sdp") || self.body.is_empty() {
+            return None;
+        }
+        Some(SdpSession::try_from(self.body))
+    }
+}
+
+fn read_start_line<'a>(reader: &mut ByteReader<'a>) -> Result<SipStartLine<'a>, FrameError> {
+    let line = read_text(reader)?.ok_or_else(|| reader.malformed("start line not terminated"))?;
+    let mut parts = line.splitn(3, ' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(VERSION), Some(status), reason) => Ok(SipStartLine::Response {
+            status: status
+                .parse()
+                .map_err(|_| reader.malformed("invalid status code"))?,
+            reason: reason.unwrap_or_default(),
+        }),
+        (Some(method), Some(uri), Some(VERSION)) => Ok(SipStartLine::Request { method, uri }),
+        _ => Err(reader.malformed("invalid start line")),
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SipFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "SIP header");
+        let start = read_start_line(&mut reader)?;
+        let mut headers = Vec::new();
+        loop {
+            let line = read_text(&mut reader)?
+                .ok_or_else(|| reader.malformed("header block not terminated"))?;
+            if line.is_empty() {
+                break;
+            }
+            // Folded continuation lines are left out
+            if line.starts_with([' ', '\t']) {
+                continue;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| reader.malformed("header line without a colon"))?;
+            let name = name.trim_end();
+            headers.push((compact_form(name).unwrap_or(name), value.trim()));
+        }
+        let mut frame = Self {
+            start,
+            headers,
+            body: &[],
+        };
+        frame.body = match frame.content_length() {
+            Some(len) => reader.bytes(len)?,
+            None => reader.rest(),
+        };
+        Ok(frame)
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for SipFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for SipFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
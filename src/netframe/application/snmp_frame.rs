@@ -0,0 +1,443 @@
+use std::net::Ipv4Addr;
+
+use super::*;
+
+/// User-based Security Model of SNMPv3 (RFC 3414).
+const USM: i64 = 3;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SnmpVersion {
+    V1,  // 0
+    V2c, // 1
+    V3,  // 3
+    Other(i64),
+}
+
+impl From<i64> for SnmpVersion {
+    fn from(val: i64) -> Self {
+        match val {
+            0 => Self::V1,
+            1 => Self::V2c,
+            3 => Self::V3,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for SnmpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+            Self::V2c => write!(f, "v2c"),
+            Self::V3 => write!(f, "v3"),
+            Self::Other(val) => write!(f, "Unknown ({})", val),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SnmpPduType {
+    GetRequest,     // 0
+    GetNextRequest, // 1
+    Response,       // 2
+    SetRequest,     // 3
+    TrapV1,         // 4
+    GetBulkRequest, // 5
+    InformRequest,  // 6
+    TrapV2,         // 7
+    Report,         // 8
+}
+
+impl SnmpPduType {
+    fn from_tag(tag: BerTag) -> Option<Self> {
+        if tag.class != BerClass::ContextSpecific || !tag.constructed {
+            return None;
+        }
+        Some(match tag.number {
+            0 => Self::GetRequest,
+            1 => Self::GetNextRequest,
+            2 => Self::Response,
+            3 => Self::SetRequest,
+            4 => Self::TrapV1,
+            5 => Self::GetBulkRequest,
+            6 => Self::InformRequest,
+            7 => Self::TrapV2,
+            8 => Self::Report,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SnmpPduType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::GetRequest => "get-request",
+            Self::GetNextRequest => "get-next-request",
+            Self::Response => "get-response",
+            Self::SetRequest => "set-request",
+            Self::TrapV1 => "trap",
+            Self::GetBulkRequest => "getBulkRequest",
+            Self::InformRequest => "informRequest",
+            Self::TrapV2 => "snmpV2-trap",
+            Self::Report => "report",
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+pub fn snmp_error_name(status: i64) -> Option<&'static str> {
+    let name = match status {
+        0 => "noError",
+        1 => "tooBig",
+        2 => "noSuchName",
+        3 => "badValue",
+        4 => "readOnly",
+        5 => "genErr",
+        6 => "noAccess",
+        7 => "wrongType",
+        8 => "wrongLength",
+        9 => "wrongEncoding",
+        10 => "wrongValue",
+        11 => "noCreation",
+        12 => "inconsistentValue",
+        13 => "resourceUnavailable",
+        14 => "commitFailed",
+        15 => "undoFailed",
+        16 => "authorizationError",
+        17 => "notWritable",
+        18 => "inconsistentName",
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub fn snmp_generic_trap_name(trap: i64) -> Option<&'static str> {
+    let name = match trap {
+        0 => "coldStart",
+        1 => "warmStart",
+        2 => "linkDown",
+        3 => "linkUp",
+        4 => "authenticationFailure",
+        5 => "egpNeighborLoss",
+        6 => "enterpriseSpecific",
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub enum SnmpValue<'a> {
+    Integer(i64),          // UNIVERSAL 2
+    OctetString(&'a [u8]), // UNIVERSAL 4
+    Null,                  // UNIVERSAL 5
+    Oid(String),           // UNIVERSAL 6
+    IpAddress(Ipv4Addr),   // APPLICATION 0
+    Counter32(u32),        // APPLICATION 1
+    Gauge32(u32),          // APPLICATION 2
+    TimeTicks(u32),        // APPLICATION 3
+    Opaque(&'a [u8]),      // APPLICATION 4
+    Counter64(u64),        // APPLICATION 6
+    NoSuchObject,          // [0]
+    NoSuchInstance,        // [1]
+    EndOfMibView,          // [2]
+    Other(BerTag, &'a [u8]),
+}
+
+fn unsigned32(element: &BerElement) -> Result<u32, FrameError> {
+    u32::try_from(element.unsigned()?).map_err(|_| element.malformed("value exceeds 32 bits"))
+}
+
+impl<'a> SnmpValue<'a> {
+    fn read(element: BerElement<'a>) -> Result<Self, FrameError> {
+        let application = |number| BerTag::application(number, false);
+        let context = |number| BerTag::context_specific(number, false);
+        Ok(match element.tag() {
+            BerTag::INTEGER => Self::Integer(element.integer()?),
+            BerTag::OCTET_STRING => Self::OctetString(element.contents()),
+            BerTag::NULL => Self::Null,
+            BerTag::OID => Self::Oid(element.oid()?),
+            tag if tag == application(0) => match element.contents() {
+                addr if addr.len() == 4 => {
+                    Self::IpAddress(Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(addr)))
+                }
+                _ => return Err(element.malformed("IpAddress is not 4 bytes")),
+            },
+            tag if tag == application(1) => Self::Counter32(unsigned32(&element)?),
+            tag if tag == application(2) => Self::Gauge32(unsigned32(&element)?),
+            tag if tag == application(3) => Self::TimeTicks(unsigned32(&element)?),
+            tag if tag == application(4) => Self::Opaque(element.contents()),
+            tag if tag == application(6) => Self::Counter64(element.unsigned()?),
+            tag if tag == context(0) => Self::NoSuchObject,
+            tag if tag == context(1) => Self::NoSuchInstance,
+            tag if tag == context(2) => Self::EndOfMibView,
+            tag => Self::Other(tag, element.contents()),
+        })
+    }
+}
+
+impl<'a> fmt::Display for SnmpValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(val) => write!(f, "INTEGER: {}", val),
+            Self::OctetString(val) => match std::str::from_utf8(val) {
+                Ok(text) if !text.chars().any(char::is_control) => {
+                    write!(f, "STRING: \"{}\"", text)
+                }
+                _ => write!(f, "Hex-STRING: {}", to_hex(val)),
+            },
+            Self::Null => write!(f, "NULL"),
+            Self::Oid(oid) => write!(f, "OID: {}", oid),
+            Self::IpAddress(addr) => write!(f, "IpAddress: {}", addr),
+            Self::Counter32(val) => write!(f, "Counter32: {}", val),
+            Self::Gauge32(val) => write!(f, "Gauge32: {}", val),
+            Self::TimeTicks(val) => {
+                let secs = val / 100;
+                write!(
+                    f,
+                    "Timeticks: ({}) {}d {:02}:{:02}:{:02}.{:02}",
+                    val,
+                    secs / 86400,
+                    secs / 3600 % 24,
+                    secs / 60 % 60,
+                    secs % 60,
+                    val % 100
+                )
+            }
+            Self::Opaque(val) => write!(f, "Opaque: {}", to_hex(val)),
+            Self::Counter64(val) => write!(f, "Counter64: {}", val),
+            Self::NoSuchObject => write!(f, "No Such Object"),
+            Self::NoSuchInstance => write!(f, "No Such Instance"),
+            Self::EndOfMibView => write!(f, "End of MIB View"),
+            Self::Other(tag, val) => write!(f, "{}: {}", tag, to_hex(val)),
+        }
+    }
+}
+
+pub struct SnmpVarBind<'a> {
+    pub oid: String,
+    pub value: SnmpValue<'a>,
+}
+
+fn read_varbinds<'a>(reader: &mut BerReader<'a>) -> Result<Vec<SnmpVarBind<'a>>, FrameError> {
+    let mut list = reader.expect(BerTag::SEQUENCE)?.reader();
+    let mut varbinds = Vec::new();
+    while !list.is_empty() {
+        let mut varbind = list.expect(BerTag::SEQUENCE)?.reader();
+        let oid = varbind.expect(BerTag::OID)?.oid()?;
+        let value = SnmpValue::read(varbind.read()?)?;
+        varbinds.push(SnmpVarBind { oid, value });
+    }
+    Ok(varbinds)
+}
+
+pub enum SnmpPdu<'a> {
+    /// Every PDU but the SNMPv1 trap. For GetBulkRequest, the error status and
+    /// index hold the non-repeaters and max-repetitions instead.
+    Request {
+        pdu_type: SnmpPduType,
+        request_id: i64,
+        error_status: i64,
+        error_index: i64,
+        varbinds: Vec<SnmpVarBind<'a>>,
+    },
+    TrapV1 {
+        enterprise: String,
+        agent_addr: Ipv4Addr,
+        generic_trap: i64,
+        specific_trap: i64,
+        timestamp: u32,
+        varbinds: Vec<SnmpVarBind<'a>>,
+    },
+}
+
+impl<'a> SnmpPdu<'a> {
+    fn read(element: BerElement<'a>) -> Result<Self, FrameError> {
+        let mut reader = element.reader();
+        let pdu_type = SnmpPduType::from_tag(element.tag())
+            .ok_or_else(|| reader.malformed("unknown PDU type"))?;
+        if pdu_type == SnmpPduType::TrapV1 {
+            let enterprise = reader.expect(BerTag::OID)?.oid()?;
+            let agent_addr = match SnmpValue::read(reader.read()?)? {
+                SnmpValue::IpAddress(addr) => addr,
+                _ => return Err(reader.malformed("agent address is not an IpAddress")),
+            };
+            return Ok(Self::TrapV1 {
+                enterprise,
+                agent_addr,
+                generic_trap: reader.expect(BerTag::INTEGER)?.integer()?,
+                specific_trap: reader.expect(BerTag::INTEGER)?.integer()?,
+                timestamp: unsigned32(&reader.read()?)?,
+                varbinds: read_varbinds(&mut reader)?,
+            });
+        }
+        Ok(Self::Request {
+            pdu_type,
+            request_id: reader.expect(BerTag::INTEGER)?.integer()?,
+            error_status: reader.expect(BerTag::INTEGER)?.integer()?,
+            error_index: reader.expect(BerTag::INTEGER)?.integer()?,
+            varbinds: read_varbinds(&mut reader)?,
+        })
+    }
+
+    pub fn pdu_type(&self) -> SnmpPduType {
+        match self {
+            Self::Request { pdu_type, .. } => *pdu_type,
+            Self::TrapV1 { .. } => SnmpPduType::TrapV1,
+        }
+    }
+
+    pub fn varbinds(&self) -> &[SnmpVarBind<'a>] {
+        match self {
+            Self::Request { varbinds, .. } | Self::TrapV1 { varbinds, .. } => varbinds,
+        }
+    }
+}
+
+/// Header fields of an SNMPv3 message. The security parameters are only decoded
+/// for the User-based Security Model.
+pub struct SnmpV3Header<'a> {
+    pub msg_id: i64,
+    pub max_size: i64,
+    pub flags: u8,
+    pub security_model: i64,
+    pub engine_id: &'a [u8],
+    pub engine_boots: i64,
+    pub engine_time: i64,
+    pub user_name: &'a [u8],
+    pub auth_params: &'a [u8],
+    pub priv_params: &'a [u8],
+    /// Context of the scoped PDU, unknown when it is encrypted.
+    pub context_engine_id: Option<&'a [u8]>,
+    pub context_name: Option<&'a [u8]>,
+}
+
+impl<'a> SnmpV3Header<'a> {
+    pub fn is_authenticated(&self) -> bool {
+        self.flags & 0b001 != 0
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & 0b010 != 0
+    }
+
+    pub fn is_reportable(&self) -> bool {
+        self.flags & 0b100 != 0
+    }
+}
+
+pub struct SnmpFrame<'a> {
+    version: SnmpVersion,
+    community: Option<&'a [u8]>,
+    header: Option<SnmpV3Header<'a>>,
+    pdu: Option<SnmpPdu<'a>>,
+    encrypted: Option<&'a [u8]>,
+}
+
+impl<'a> SnmpFrame<'a> {
+    pub fn version(&self) -> SnmpVersion {
+        self.version
+    }
+
+    /// Community string of SNMPv1 and SNMPv2c messages.
+    pub fn community(&self) -> Option<&'a [u8]> {
+        self.community
+    }
+
+    pub fn v3_header(&self) -> Option<&SnmpV3Header<'a>> {
+        self.header.as_ref()
+    }
+
+    /// The PDU, unless it is encrypted.
+    pub fn pdu(&self) -> Option<&SnmpPdu<'a>> {
+        self.pdu.as_ref()
+    }
+
+    /// Encrypted scoped PDU of SNMPv3 messages with privacy.
+    pub fn encrypted_pdu(&self) -> Option<&'a [u8]> {
+        self.encrypted
+    }
+
+    fn read_v3(mut reader: BerReader<'a>) -> Result<Self, FrameError> {
+        let mut global = reader.expect(BerTag::SEQUENCE)?.reader();
+        let msg_id = global.expect(BerTag::INTEGER)?.integer()?;
+        let max_size = global.expect(BerTag::INTEGER)?.integer()?;
+        let flags = match global.expect(BerTag::OCTET_STRING)?.contents() {
+            [flags] => *flags,
+            _ => return Err(global.malformed("flags are not a single byte")),
+        };
+        let security_model = global.expect(BerTag::INTEGER)?.integer()?;
+        let security = reader.expect(BerTag::OCTET_STRING)?;
+        let mut header = SnmpV3Header {
+            msg_id,
+            max_size,
+            flags,
+            security_model,
+            engine_id: &[],
+            engine_boots: 0,
+            engine_time: 0,
+            user_name: &[],
+            auth_params: &[],
+            priv_params: &[],
+            context_engine_id: None,
+            context_name: None,
+        };
+        if security_model == USM {
+            let mut usm = BerReader::new(security.contents(), "SNMP USM parameters")
+                .expect(BerTag::SEQUENCE)?
+                .reader();
+            header.engine_id = usm.expect(BerTag::OCTET_STRING)?.contents();
+            header.engine_boots = usm.expect(BerTag::INTEGER)?.integer()?;
+            header.engine_time = usm.expect(BerTag::INTEGER)?.integer()?;
+            header.user_name = usm.expect(BerTag::OCTET_STRING)?.contents();
+            header.auth_params = usm.expect(BerTag::OCTET_STRING)?.contents();
+            header.priv_params = usm.expect(BerTag::OCTET_STRING)?.contents();
+        }
+        let data = reader.read()?;
+        let (pdu, encrypted) = match data.tag() {
+            BerTag::OCTET_STRING => (None, Some(data.contents())),
+            BerTag::SEQUENCE => {
+                let mut scoped = data.reader();
+                header.context_engine_id = Some(scoped.expect(BerTag::OCTET_STRING)?.contents());
+                header.context_name = Some(scoped.expect(BerTag::OCTET_STRING)?.contents());
+                (Some(SnmpPdu::read(scoped.read()?)?), None)
+            }
+            _ => return Err(reader.malformed("unexpected scoped PDU")),
+        };
+        Ok(Self {
+            version: SnmpVersion::V3,
+            community: None,
+            header: Some(header),
+            pdu,
+            encrypted,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SnmpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = BerReader::new(slice, "SNMP message")
+            .expect(BerTag::SEQUENCE)?
+            .reader();
+        let version = SnmpVersion::from(reader.expect(BerTag::INTEGER)?.integer()?);
+        match version {
+            SnmpVersion::V3 => Self::read_v3(reader),
+            SnmpVersion::Other(_) => Err(reader.malformed("unsupported version")),
+            _ => Ok(Self {
+                version,
+                community: Some(reader.expect(BerTag::OCTET_STRING)?.contents()),
+                header: None,
+                pdu: Some(SnmpPdu::read(reader.read()?)?),
+                encrypted: None,
+            }),
+        }
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for SnmpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
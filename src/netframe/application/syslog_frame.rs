@@ -0,0 +1,433 @@
+use std::borrow::Cow;
+use std::str;
+
+use chrono::{DateTime, FixedOffset};
+
+use super::*;
+
+/// Length of an RFC 3164 timestamp, such as `Oct 11 22:14:15`.
+const BSD_TIMESTAMP_LEN: usize = 15;
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+/// Byte order mark allowed at the start of RFC 5424 messages.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SyslogSeverity {
+    Emergency,     // 0
+    Alert,         // 1
+    Critical,      // 2
+    Error,         // 3
+    Warning,       // 4
+    Notice,        // 5
+    Informational, // 6
+    Debug,         // 7
+}
+
+impl From<u8> for SyslogSeverity {
+    fn from(val: u8) -> Self {
+        match val & 0b111 {
+            0 => Self::Emergency,
+            1 => Self::Alert,
+            2 => Self::Critical,
+            3 => Self::Error,
+            4 => Self::Warning,
+            5 => Self::Notice,
+            6 => Self::Informational,
+            _ => Self::Debug,
+        }
+    }
+}
+
+impl fmt::Display for SyslogSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outstr = match self {
+            Self::Emergency => "Emergency",
+            Self::Alert => "Alert",
+            Self::Critical => "Critical",
+            Self::Error => "Error",
+            Self::Warning => "Warning",
+            Self::Notice => "Notice",
+            Self::Informational => "Informational",
+            Self::Debug => "Debug",
+        };
+        write!(f, "{}", outstr)
+    }
+}
+
+pub fn syslog_facility_name(facility: u8) -> Option<&'static str> {
+    let name = match facility {
+        0 => "kern",
+        1 => "user",
+        2 => "mail",
+        3 => "daemon",
+        4 => "auth",
+        5 => "syslog",
+        6 => "lpr",
+        7 => "news",
+        8 => "uucp",
+        9 => "cron",
+        10 => "authpriv",
+        11 => "ftp",
+        12 => "ntp",
+        13 => "security",
+        14 => "console",
+        15 => "solaris-cron",
+        16 => "local0",
+        17 => "local1",
+        18 => "local2",
+        19 => "local3",
+        20 => "local4",
+        21 => "local5",
+        22 => "local6",
+        23 => "local7",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Element of the structured data of an RFC 5424 message, with its parameter
+/// values unescaped.
+pub struct SyslogSdElement<'a> {
+    pub id: &'a str,
+    pub params: Vec<(&'a str, String)>,
+}
+
+impl<'a> fmt::Display for SyslogSdElement<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}", self.id)?;
+        for (name, value) in &self.params {
+            write!(f, " {}=\"{}\"", name, value)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Reads the bytes up to the next space, which is consumed, or up to the end.
+fn read_field<'a>(reader: &mut ByteReader<'a>) -> Result<&'a str, FrameError> {
+    let rest = &reader.data()[reader.pos()..];
+    let end = rest.iter().position(|&c| c == b' ').unwrap_or(rest.len());
+    let field = reader.bytes(end)?;
+    if !reader.is_empty() {
+        reader.u8()?;
+    }
+    str::from_utf8(field).map_err(|_| reader.malformed("non ASCII header field"))
+}
+
+/// Header field that may be the nil value `-`.
+fn read_nil_field<'a>(reader: &mut ByteReader<'a>) -> Result<Option<&'a str>, FrameError> {
+    read_field(reader).map(|field| Some(field).filter(|&field| field != "-"))
+}
+
+/// Reads up to the byte `end`, which is consumed.
+fn read_until<'a>(reader: &mut ByteReader<'a>, end: u8) -> Result<&'a str, FrameError> {
+    let rest = &reader.data()[reader.pos()..];
+    let len = rest
+        .iter()
+        .position(|&c| c == end)
+        .ok_or_else(|| reader.malformed("unterminated field"))?;
+    let field = reader.bytes(len)?;
+    reader.u8()?;
+    str::from_utf8(field).map_err(|_| reader.malformed("non ASCII header field"))
+}
+
+fn read_structured_data<'a>(
+    reader: &mut ByteReader<'a>,
+) -> Result<Vec<SyslogSdElement<'a>>, FrameError> {
+    let mut elements = Vec::new();
+    if reader.data().get(reader.pos()) != Some(&b'[') {
+        read_field(reader)?;
+        return Ok(elements);
+    }
+    while reader.data().get(reader.pos()) == Some(&b'[') {
+        reader.u8()?;
+        let rest = &reader.data()[reader.pos()..];
+        let end = rest
+            .iter()
+            .position(|&c| c == b' ' || c == b']')
+            .ok_or_else(|| reader.malformed("unterminated structured data"))?;
+        let id = str::from_utf8(reader.bytes(end)?)
+            .map_err(|_| reader.malformed("non ASCII structured data"))?;
+        let mut params = Vec::new();
+        while reader.u8()? == b' ' {
+            let name = read_until(reader, b'=')?;
+            if reader.u8()? != b'"' {
+                return Err(reader.malformed("unquoted parameter value"));
+            }
+            // '"', '\' and ']' are escaped with a backslash inside values
+            let mut value = Vec::new();
+            loop {
+                match reader.u8()? {
+                    b'\\' => match reader.u8()? {
+                        c @ (b'"' | b'\\' | b']') => value.push(c),
+                        c => value.extend_from_slice(&[b'\\', c]),
+                    },
+                    b'"' => break,
+                    c => value.push(c),
+                }
+            }
+            params.push((name, String::from_utf8_lossy(&value).into_owned()));
+        }
+        elements.push(SyslogSdElement { id, params });
+    }
+    if !reader.is_empty() {
+        reader.u8()?;
+    }
+    Ok(elements)
+}
+
+/// Whether `data` starts with an RFC 3164 timestamp.
+fn is_bsd_timestamp(data: &[u8]) -> bool {
+    data.len() >= BSD_TIMESTAMP_LEN
+        && MONTHS
+            .iter()
+            .any(|month| data.starts_with(month.as_bytes()))
+        && data[3] == b' '
+        && data[6] == b' '
+        && data[9] == b':'
+        && data[12] == b':'
+}
+
+/// Syslog message in the format of RFC 5424, or in the BSD format described by RFC
+/// 3164 when the PRI is not followed by a version.
+pub struct SyslogMessage<'a> {
+    pri: u8,
+    version: Option<u8>,
+    timestamp: Option<&'a str>,
+    hostname: Option<&'a str>,
+    app_name: Option<&'a str>,
+    procid: Option<&'a str>,
+    msgid: Option<&'a str>,
+    structured_data: Vec<SyslogSdElement<'a>>,
+    msg: &'a [u8],
+}
+
+impl<'a> SyslogMessage<'a> {
+    pub fn pri(&self) -> u8 {
+        self.pri
+    }
+
+    pub fn facility(&self) -> u8 {
+        self.pri >> 3
+    }
+
+    pub fn severity(&self) -> SyslogSeverity {
+        SyslogSeverity::from(self.pri)
+    }
+
+    /// Version of RFC 5424 messages; `None` for BSD messages.
+    pub fn version(&self) -> Option<u8> {
+        self.version
+    }
+
+    /// Timestamp as sent. BSD timestamps lack the year and the time zone.
+    pub fn timestamp(&self) -> Option<&'a str> {
+        self.timestamp
+    }
+
+    /// Timestamp of RFC 5424 messages, which follows RFC 3339.
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.version?;
+        DateTime::parse_from_rfc3339(self.timestamp?).ok()
+    }
+
+    pub fn hostname(&self) -> Option<&'a str> {
+        self.hostname
+    }
+
+    /// Application name, or the tag of BSD messages.
+    pub fn app_name(&self) -> Option<&'a str> {
+        self.app_name
+    }
+
+    pub fn procid(&self) -> Option<&'a str> {
+        self.procid
+    }
+
+    pub fn msgid(&self) -> Option<&'a str> {
+        self.msgid
+    }
+
+    pub fn structured_data(&self) -> &[SyslogSdElement<'a>] {
+        &self.structured_data
+    }
+
+    /// Free form message, without the byte order mark of RFC 5424 messages.
+    pub fn message(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.msg)
+    }
+
+    fn read_rfc5424(&mut self, reader: &mut ByteReader<'a>) -> Result<(), FrameError> {
+        self.timestamp = read_nil_field(reader)?;
+        self.hostname = read_nil_field(reader)?;
+        self.app_name = read_nil_field(reader)?;
+        self.procid = read_nil_field(reader)?;
+        self.msgid = read_nil_field(reader)?;
+        self.structured_data = read_structured_data(reader)?;
+        let msg = reader.rest();
+        self.msg = msg.strip_prefix(BOM).unwrap_or(msg);
+        Ok(())
+    }
+
+    /// Splits a BSD message into its timestamp, host name and tag, which are all
+    /// optional; the message is whatever cannot be read as those.
+    fn read_rfc3164(&mut self, reader: &mut ByteReader<'a>) -> Result<(), FrameError> {
+        if is_bsd_timestamp(&reader.data()[reader.pos()..]) {
+            let timestamp = reader.bytes(BSD_TIMESTAMP_LEN)?;
+            self.timestamp = str::from_utf8(timestamp).ok();
+            if reader.data().get(reader.pos()) == Some(&b' ') {
+                reader.u8()?;
+                self.hostname = Some(read_field(reader)?);
+            }
+        }
+        let rest = &reader.data()[reader.pos()..];
+        // The tag is alphanumeric, then an optional PID in brackets and a colon
+        let tag_len = rest
+            .iter()
+            .position(|&c| !(c.is_ascii_alphanumeric() || b"-_./".contains(&c)))
+            .unwrap_or(rest.len());
+        let (tag, after) = rest.split_at(tag_len);
+        let (procid, after) = match after.strip_prefix(b"[") {
+            Some(after) => match after.iter().position(|&c| c == b']') {
+                Some(end) => (Some(&after[..end]), &after[end + 1..]),
+                None => (None, &b""[..]),
+            },
+            None => (None, after),
+        };
+        match after.strip_prefix(b":") {
+            Some(msg) if tag_len > 0 => {
+                self.app_name = str::from_utf8(tag).ok();
+                self.procid = procid.and_then(|procid| str::from_utf8(procid).ok());
+                self.msg = msg.strip_prefix(b" ").unwrap_or(msg);
+            }
+            _ => self.msg = rest,
+        }
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SyslogMessage<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "Syslog message");
+        if reader.u8()? != b'<' {
+            return Err(reader.malformed("missing PRI"));
+        }
+        let digits = read_until(&mut reader, b'>')?;
+        let pri = match digits.parse() {
+            Ok(pri) if pri <= 191 && (digits == "0" || !digits.starts_with('0')) => pri,
+            _ => return Err(reader.malformed("invalid PRI")),
+        };
+        let mut message = Self {
+            pri,
+            version: None,
+            timestamp: None,
+            hostname: None,
+            app_name: None,
+            procid: None,
+            msgid: None,
+            structured_data: Vec::new(),
+            msg: &[],
+        };
+        match &slice[reader.pos()..] {
+            [version @ b'1'..=b'9', b' ', ..] => {
+                message.version = Some(version - b'0');
+                reader.bytes(2)?;
+                message.read_rfc5424(&mut reader)?;
+            }
+            _ => message.read_rfc3164(&mut reader)?,
+        }
+        Ok(message)
+    }
+}
+
+/// Syslog messages of a UDP datagram, or of a TCP segment framed as in RFC 6587
+/// along with the start of a message continued in the next segment.
+pub struct SyslogFrame<'a>(Vec<SyslogMessage<'a>>, &'a [u8]);
+
+impl<'a> SyslogFrame<'a> {
+    /// Whether `data` begins with a message over TCP, prefixed by its length or not,
+    /// which tells the start of a message apart from its continuation.
+    pub fn is_syslog(data: &[u8]) -> bool {
+        let digits = data.iter().take_while(|c| c.is_ascii_digit()).count();
+        let pri = match digits {
+            0 => data,
+            _ if data.get(digits) == Some(&b' ') => &data[digits + 1..],
+            _ => return false,
+        };
+        // The PRI has one to three digits
+        match pri.iter().position(|&c| c == b'>') {
+            Some(end @ 2..=4) => pri[0] == b'<' && pri[1..end].iter().all(u8::is_ascii_digit),
+            _ => false,
+        }
+    }
+
+    pub fn messages(&self) -> &[SyslogMessage<'a>] {
+        &self.0
+    }
+
+    /// Start of the last message of a TCP segment, left undissected as it is cut by
+    /// the end of the segment.
+    pub fn partial(&self) -> &'a [u8] {
+        self.1
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SyslogFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        // Some senders end datagrams with a line feed
+        let slice = slice.strip_suffix(b"\n").unwrap_or(slice);
+        Ok(Self(vec![SyslogMessage::try_from(slice)?], &[]))
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for SyslogFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for SyslogFrame<'a> {
+    type Error = FrameError;
+
+    /// Messages are either prefixed by their length in decimal and a space, or ended
+    /// by a line feed. Only segments starting with a message are supported.
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(frame.payload(), "Syslog over TCP");
+        let mut messages = Vec::new();
+        while !reader.is_empty() {
+            let rest = &reader.data()[reader.pos()..];
+            let msg = if rest[0].is_ascii_digit() {
+                // Even the length may be cut by the end of the segment
+                if !rest.contains(&b' ') {
+                    return Ok(Self(messages, rest));
+                }
+                let len = read_until(&mut reader, b' ')?
+                    .parse()
+                    .map_err(|_| reader.malformed("invalid message length"))?;
+                match reader.bytes(len) {
+                    Ok(msg) => msg,
+                    Err(_) => return Ok(Self(messages, rest)),
+                }
+            } else {
+                match rest.iter().position(|&c| c == b'\n') {
+                    Some(end) => {
+                        let msg = reader.bytes(end)?;
+                        reader.u8()?;
+                        msg
+                    }
+                    // A message without its trailer is cut by the end of the segment
+                    None => return Ok(Self(messages, rest)),
+                }
+            };
+            messages.push(SyslogMessage::try_from(msg)?);
+        }
+        Ok(Self(messages, &[]))
+    }
+}
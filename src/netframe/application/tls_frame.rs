@@ -0,0 +1,750 @@
+use md5::{Digest, Md5};
+
+use super::*;
+
+const RECORD_HEADER_LEN: usize = 5;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TlsContentType {
+    ChangeCipherSpec, // 20
+    Alert,            // 21
+    Handshake,        // 22
+    ApplicationData,  // 23
+    Heartbeat,        // 24
+    Other(u8),
+}
+
+impl From<u8> for TlsContentType {
+    fn from(val: u8) -> Self {
+        match val {
+            20 => Self::ChangeCipherSpec,
+            21 => Self::Alert,
+            22 => Self::Handshake,
+            23 => Self::ApplicationData,
+            24 => Self::Heartbeat,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for TlsContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChangeCipherSpec => write!(f, "Change Cipher Spec (20)"),
+            Self::Alert => write!(f, "Alert (21)"),
+            Self::Handshake => write!(f, "Handshake (22)"),
+            Self::ApplicationData => write!(f, "Application Data (23)"),
+            Self::Heartbeat => write!(f, "Heartbeat (24)"),
+            Self::Other(val) => write!(f, "Unknown ({})", val),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TlsVersion(pub u16);
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0x0300 => write!(f, "SSL 3.0"),
+            0x0301 => write!(f, "TLS 1.0"),
+            0x0302 => write!(f, "TLS 1.1"),
+            0x0303 => write!(f, "TLS 1.2"),
+            0x0304 => write!(f, "TLS 1.3"),
+            val if is_grease(val) => write!(f, "Reserved (GREASE) (0x{:04x})", val),
+            val => write!(f, "Unknown (0x{:04x})", val),
+        }
+    }
+}
+
+/// Whether `val` is one of the values reserved by RFC 8701 to keep peers from
+/// rejecting unknown values. They are left out of the fingerprints.
+pub fn is_grease(val: u16) -> bool {
+    val & 0x0F0F == 0x0A0A && val >> 8 == val & 0xFF
+}
+
+pub fn cipher_suite_name(suite: u16) -> Option<&'static str> {
+    match suite {
+        0x002F => Some("TLS_RSA_WITH_AES_128_CBC_SHA"),
+        0x0035 => Some("TLS_RSA_WITH_AES_256_CBC_SHA"),
+        0x009C => Some("TLS_RSA_WITH_AES_128_GCM_SHA256"),
+        0x009D => Some("TLS_RSA_WITH_AES_256_GCM_SHA384"),
+        0x00FF => Some("TLS_EMPTY_RENEGOTIATION_INFO_SCSV"),
+        0x1301 => Some("TLS_AES_128_GCM_SHA256"),
+        0x1302 => Some("TLS_AES_256_GCM_SHA384"),
+        0x1303 => Some("TLS_CHACHA20_POLY1305_SHA256"),
+        0xC009 => Some("TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA"),
+        0xC00A => Some("TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA"),
+        0xC013 => Some("TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA"),
+        0xC014 => Some("TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA"),
+        0xC02B => Some("TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256"),
+        0xC02C => Some("TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384"),
+        0xC02F => Some("TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256"),
+        0xC030 => Some("TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384"),
+        0xCCA8 => Some("TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256"),
+        0xCCA9 => Some("TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256"),
+        _ => None,
+    }
+}
+
+pub fn group_name(group: u16) -> Option<&'static str> {
+    match group {
+        23 => Some("secp256r1"),
+        24 => Some("secp384r1"),
+        25 => Some("secp521r1"),
+        29 => Some("x25519"),
+        30 => Some("x448"),
+        256 => Some("ffdhe2048"),
+        257 => Some("ffdhe3072"),
+        0x11EC => Some("X25519MLKEM768"),
+        _ => None,
+    }
+}
+
+pub fn extension_name(code: u16) -> Option<&'static str> {
+    match code {
+        0 => Some("server_name"),
+        5 => Some("status_request"),
+        10 => Some("supported_groups"),
+        11 => Some("ec_point_formats"),
+        13 => Some("signature_algorithms"),
+        16 => Some("application_layer_protocol_negotiation"),
+        18 => Some("signed_certificate_timestamp"),
+        21 => Some("padding"),
+        22 => Some("encrypt_then_mac"),
+        23 => Some("extended_master_secret"),
+        27 => Some("compress_certificate"),
+        35 => Some("session_ticket"),
+        41 => Some("pre_shared_key"),
+        43 => Some("supported_versions"),
+        45 => Some("psk_key_exchange_modes"),
+        51 => Some("key_share"),
+        57 => Some("quic_transport_parameters"),
+        65281 => Some("renegotiation_info"),
+        _ => None,
+    }
+}
+
+/// Formats a list of code points as Wireshark does, with their names when known.
+fn named_list(codes: &[u16], name: fn(u16) -> Option<&'static str>) -> String {
+    let codes: Vec<_> = codes
+        .iter()
+        .map(|&code| match name(code) {
+            Some(name) => format!("{} (0x{:04x})", name, code),
+            None if is_grease(code) => format!("GREASE (0x{:04x})", code),
+            None => format!("0x{:04x}", code),
+        })
+        .collect();
+    codes.join(", ")
+}
+
+pub enum TlsExtension<'a> {
+    ServerName(String),          // 0
+    SupportedGroups(Vec<u16>),   // 10
+    EcPointFormats(Vec<u8>),     // 11
+    Alpn(Vec<String>),           // 16
+    SupportedVersions(Vec<u16>), // 43
+    Other(u16, &'a [u8]),
+}
+
+impl<'a> TlsExtension<'a> {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::ServerName(_) => 0,
+            Self::SupportedGroups(_) => 10,
+            Self::EcPointFormats(_) => 11,
+            Self::Alpn(_) => 16,
+            Self::SupportedVersions(_) => 43,
+            Self::Other(code, _) => *code,
+        }
+    }
+
+    /// Reads the extension `code`; the supported versions extension holds a list in
+    /// a ClientHello and the selected version in a ServerHello.
+    fn read(code: u16, data: &'a [u8], from_client: bool) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(data, "TLS extension");
+        Ok(match code {
+            0 if !data.is_empty() => {
+                let len = usize::from(reader.u16()?);
+                let mut names = reader.sub(len)?;
+                let mut host_name = None;
+                while !names.is_empty() {
+                    let typ = names.u8()?;
+                    let len = usize::from(names.u16()?);
+                    let name = names.bytes(len)?;
+                    if typ == 0 && host_name.is_none() {
+                        host_name = Some(String::from_utf8_lossy(name).into_owned());
+                    }
+                }
+                match host_name {
+                    Some(name) => Self::ServerName(name),
+                    None => Self::Other(code, data),
+                }
+            }
+            10 => {
+                let len = usize::from(reader.u16()?);
+                let mut groups = reader.sub(len)?;
+                let mut list = Vec::new();
+                while !groups.is_empty() {
+                    list.push(groups.u16()?);
+                }
+                Self::SupportedGroups(list)
+            }
+            11 => {
+                let len = usize::from(reader.u8()?);
+                Self::EcPointFormats(reader.bytes(len)?.to_vec())
+            }
+            16 if !data.is_empty() => {
+                let len = usize::from(reader.u16()?);
+                let mut protocols = reader.sub(len)?;
+                let mut list = Vec::new();
+                while !protocols.is_empty() {
+                    let len = usize::from(protocols.u8()?);
+                    list.push(String::from_utf8_lossy(protocols.bytes(len)?).into_owned());
+                }
+                Self::Alpn(list)
+            }
+            43 if from_client => {
+                let len = usize::from(reader.u8()?);
+                let mut versions = reader.sub(len)?;
+                let mut list = Vec::new();
+                while !versions.is_empty() {
+                    list.push(versions.u16()?);
+                }
+                Self::SupportedVersions(list)
+            }
+            43 => Self::SupportedVersions(vec![reader.u16()?]),
+            other => Self::Other(other, data),
+        })
+    }
+}
+
+impl<'a> fmt::Display for TlsExtension<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ServerName(name) => write!(f, "server_name: {}", name),
+            Self::SupportedGroups(groups) => {
+                write!(f, "supported_groups: {}", named_list(groups, group_name))
+            }
+            Self::EcPointFormats(formats) => write!(f, "ec_point_formats: {:?}", formats),
+            Self::Alpn(protocols) => write!(
+                f,
+                "application_layer_protocol_negotiation: {}",
+                protocols.join(", ")
+            ),
+            Self::SupportedVersions(versions) => {
+                let versions: Vec<_> = versions
+                    .iter()
+                    .map(|&version| TlsVersion(version).to_string())
+                    .collect();
+                write!(f, "supported_versions: {}", versions.join(", "))
+            }
+            Self::Other(code, data) => match extension_name(*code) {
+                Some(name) => write!(f, "{} ({} bytes)", name, data.len()),
+                None if is_grease(*code) => write!(f, "GREASE (0x{:04x})", code),
+                None => write!(f, "Unknown 0x{:04x} ({} bytes)", code, data.len()),
+            },
+        }
+    }
+}
+
+fn read_extensions<'a>(
+    reader: &mut ByteReader<'a>,
+    from_client: bool,
+) -> Result<Vec<TlsExtension<'a>>, FrameError> {
+    let mut extensions = Vec::new();
+    // The extension block is optional
+    if reader.is_empty() {
+        return Ok(extensions);
+    }
+    let len = usize::from(reader.u16()?);
+    let mut block = reader.sub(len)?;
+    while !block.is_empty() {
+        let code = block.u16()?;
+        let len = usize::from(block.u16()?);
+        extensions.push(TlsExtension::read(code, block.bytes(len)?, from_client)?);
+    }
+    Ok(extensions)
+}
+
+fn dash_list<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    let values: Vec<_> = values.map(|value| value.to_string()).collect();
+    values.join("-")
+}
+
+fn md5_hex(text: &str) -> String {
+    Md5::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub struct ClientHello<'a> {
+    version: TlsVersion,
+    random: &'a [u8],
+    session_id: &'a [u8],
+    cipher_suites: Vec<u16>,
+    compression_methods: &'a [u8],
+    extensions: Vec<TlsExtension<'a>>,
+}
+
+impl<'a> ClientHello<'a> {
+    fn read(data: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(data, "TLS ClientHello");
+        let version = TlsVersion(reader.u16()?);
+        let random = reader.bytes(32)?;
+        let len = usize::from(reader.u8()?);
+        let session_id = reader.bytes(len)?;
+        let len = usize::from(reader.u16()?);
+        let mut suites = reader.sub(len)?;
+        let mut cipher_suites = Vec::new();
+        while !suites.is_empty() {
+            cipher_suites.push(suites.u16()?);
+        }
+        let len = usize::from(reader.u8()?);
+        let compression_methods = reader.bytes(len)?;
+        Ok(Self {
+            version,
+            random,
+            session_id,
+            cipher_suites,
+            compression_methods,
+            extensions: read_extensions(&mut reader, true)?,
+        })
+    }
+
+    /// Legacy version field; TLS 1.3 is offered in the supported versions extension.
+    pub fn version(&self) -> TlsVersion {
+        self.version
+    }
+
+    pub fn random(&self) -> &'a [u8] {
+        self.random
+    }
+
+    pub fn session_id(&self) -> &'a [u8] {
+        self.session_id
+    }
+
+    pub fn cipher_suites(&self) -> &[u16] {
+        &self.cipher_suites
+    }
+
+    pub fn compression_methods(&self) -> &'a [u8] {
+        self.compression_methods
+    }
+
+    pub fn extensions(&self) -> &[TlsExtension<'a>] {
+        &self.extensions
+    }
+
+    /// Host name sent in the Server Name Indication extension.
+    pub fn server_name(&self) -> Option<&str> {
+        self.extensions.iter().find_map(|ext| match ext {
+            TlsExtension::ServerName(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Protocols offered with ALPN, most preferred first.
+    pub fn alpn(&self) -> &[String] {
+        self.extensions
+            .iter()
+            .find_map(|ext| match ext {
+                TlsExtension::Alpn(protocols) => Some(protocols.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn supported_groups(&self) -> &[u16] {
+        self.extensions
+            .iter()
+            .find_map(|ext| match ext {
+                TlsExtension::SupportedGroups(groups) => Some(groups.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// JA3 fingerprint string: version, cipher suites, extensions, groups and point
+    /// formats, with GREASE values removed.
+    pub fn ja3_string(&self) -> String {
+        let point_formats = self
+            .extensions
+            .iter()
+            .find_map(|ext| match ext {
+                TlsExtension::EcPointFormats(formats) => Some(formats.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        format!(
+            "{},{},{},{},{}",
+            self.version.0,
+            dash_list(
+                self.cipher_suites
+                    .iter()
+                    .filter(|&&suite| !is_grease(suite))
+            ),
+            dash_list(
+                self.extensions
+                    .iter()
+                    .map(TlsExtension::code)
+                    .filter(|&code| !is_grease(code))
+            ),
+            dash_list(
+                self.supported_groups()
+                    .iter()
+                    .filter(|&&group| !is_grease(group))
+            ),
+            dash_list(point_formats.iter()),
+        )
+    }
+
+    /// MD5 hash of the JA3 string, in hex.
+    pub fn ja3(&self) -> String {
+        md5_hex(&self.ja3_string())
+    }
+}
+
+pub struct ServerHello<'a> {
+    version: TlsVersion,
+    random: &'a [u8],
+    session_id: &'a [u8],
+    cipher_suite: u16,
+    compression_method: u8,
+    extensions: Vec<TlsExtension<'a>>,
+}
+
+impl<'a> ServerHello<'a> {
+    fn read(data: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(data, "TLS ServerHello");
+        let version = TlsVersion(reader.u16()?);
+        let random = reader.bytes(32)?;
+        let len = usize::from(reader.u8()?);
+        Ok(Self {
+            version,
+            random,
+            session_id: reader.bytes(len)?,
+            cipher_suite: reader.u16()?,
+            compression_method: reader.u8()?,
+            extensions: read_extensions(&mut reader, false)?,
+        })
+    }
+
+    pub fn version(&self) -> TlsVersion {
+        self.version
+    }
+
+    /// Version selected with the supported versions extension, or the legacy
+    /// version field.
+    pub fn negotiated_version(&self) -> TlsVersion {
+        self.extensions
+            .iter()
+            .find_map(|ext| match ext {
+                TlsExtension::SupportedVersions(versions) => versions.first().copied(),
+                _ => None,
+            })
+            .map_or(self.version, TlsVersion)
+    }
+
+    pub fn random(&self) -> &'a [u8] {
+        self.random
+    }
+
+    pub fn session_id(&self) -> &'a [u8] {
+        self.session_id
+    }
+
+    pub fn cipher_suite(&self) -> u16 {
+        self.cipher_suite
+    }
+
+    pub fn compression_method(&self) -> u8 {
+        self.compression_method
+    }
+
+    pub fn extensions(&self) -> &[TlsExtension<'a>] {
+        &self.extensions
+    }
+
+    /// Protocol selected with ALPN.
+    pub fn alpn(&self) -> Option<&str> {
+        self.extensions.iter().find_map(|ext| match ext {
+            TlsExtension::Alpn(protocols) => protocols.first().map(String::as_str),
+            _ => None,
+        })
+    }
+
+    /// JA3S fingerprint string: version, cipher suite and extensions.
+    pub fn ja3s_string(&self) -> String {
+        format!(
+            "{},{},{}",
+            self.version.0,
+            self.cipher_suite,
+            dash_list(self.extensions.iter().map(TlsExtension::code)),
+        )
+    }
+
+    /// MD5 hash of the JA3S string, in hex.
+    pub fn ja3s(&self) -> String {
+        md5_hex(&self.ja3s_string())
+    }
+}
+
+pub fn handshake_type_name(typ: u8) -> &'static str {
+    match typ {
+        0 => "Hello Request",
+        1 => "Client Hello",
+        2 => "Server Hello",
+        4 => "New Session Ticket",
+        8 => "Encrypted Extensions",
+        11 => "Certificate",
+        12 => "Server Key Exchange",
+        13 => "Certificate Request",
+        14 => "Server Hello Done",
+        15 => "Certificate Verify",
+        16 => "Client Key Exchange",
+        20 => "Finished",
+        _ => "Unknown",
+    }
+}
+
+fn is_handshake_type(typ: u8) -> bool {
+    matches!(typ, 0 | 1 | 2 | 4 | 8 | 11..=16 | 20)
+}
+
+pub enum TlsHandshake<'a> {
+    ClientHello(ClientHello<'a>), // 1
+    ServerHello(ServerHello<'a>), // 2
+    Certificate(Vec<&'a [u8]>),   // 11
+    Other(u8, &'a [u8]),
+    /// Message whose end is in a later record or segment.
+    Partial(u8),
+    /// Message sent after Change Cipher Spec, like Finished in TLS 1.2.
+    Encrypted,
+}
+
+impl<'a> TlsHandshake<'a> {
+    /// Reads the handshake messages of a stream, such as the fragment of a record or
+    /// the CRYPTO frames of a QUIC packet. The last one may be partial.
+    pub fn read_all(data: &'a [u8]) -> Result<Vec<Self>, FrameError> {
+        let mut reader = ByteReader::new(data, "TLS handshake");
+        let mut handshakes = Vec::new();
+        while !reader.is_empty() {
+            let header = reader.bytes(4.min(reader.remaining()))?;
+            let typ = header[0];
+            let len = match header {
+                [_, a, b, c] => u32::from_be_bytes([0, *a, *b, *c]) as usize,
+                _ => usize::MAX,
+            };
+            if len > reader.remaining() {
+                handshakes.push(Self::Partial(typ));
+                break;
+            }
+            handshakes.push(Self::read(typ, reader.bytes(len)?)?);
+        }
+        Ok(handshakes)
+    }
+
+    fn read(typ: u8, data: &'a [u8]) -> Result<Self, FrameError> {
+        Ok(match typ {
+            1 => Self::ClientHello(ClientHello::read(data)?),
+            2 => Self::ServerHello(ServerHello::read(data)?),
+            11 => {
+                let mut reader = ByteReader::new(data, "TLS Certificate");
+                let len = reader.u24()? as usize;
+                let mut list = reader.sub(len)?;
+                let mut certs = Vec::new();
+                while !list.is_empty() {
+                    let len = list.u24()? as usize;
+                    certs.push(list.bytes(len)?);
+                }
+                Self::Certificate(certs)
+            }
+            other => Self::Other(other, data),
+        })
+    }
+}
+
+impl<'a> fmt::Display for TlsHandshake<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClientHello(_) => write!(f, "Client Hello"),
+            Self::ServerHello(_) => write!(f, "Server Hello"),
+            Self::Certificate(_) => write!(f, "Certificate"),
+            Self::Other(typ, _) => write!(f, "{} ({})", handshake_type_name(*typ), typ),
+            Self::Partial(typ) => write!(
+                f,
+                "{} ({}), continued in the next segment",
+                handshake_type_name(*typ),
+                typ
+            ),
+            Self::Encrypted => write!(f, "Encrypted Handshake Message"),
+        }
+    }
+}
+
+pub fn alert_description(code: u8) -> &'static str {
+    match code {
+        0 => "Close Notify",
+        10 => "Unexpected Message",
+        20 => "Bad Record MAC",
+        22 => "Record Overflow",
+        40 => "Handshake Failure",
+        42 => "Bad Certificate",
+        43 => "Unsupported Certificate",
+        44 => "Certificate Revoked",
+        45 => "Certificate Expired",
+        46 => "Certificate Unknown",
+        47 => "Illegal Parameter",
+        48 => "Unknown CA",
+        49 => "Access Denied",
+        50 => "Decode Error",
+        51 => "Decrypt Error",
+        70 => "Protocol Version",
+        71 => "Insufficient Security",
+        80 => "Internal Error",
+        86 => "Inappropriate Fallback",
+        90 => "User Canceled",
+        109 => "Missing Extension",
+        110 => "Unsupported Extension",
+        112 => "Unrecognized Name",
+        116 => "Certificate Required",
+        120 => "No Application Protocol",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TlsAlert {
+    pub level: u8,
+    pub description: u8,
+}
+
+impl TlsAlert {
+    pub fn is_fatal(&self) -> bool {
+        self.level == 2
+    }
+}
+
+impl fmt::Display for TlsAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({})",
+            if self.is_fatal() { "Fatal" } else { "Warning" },
+            alert_description(self.description),
+            self.description
+        )
+    }
+}
+
+pub struct TlsRecord<'a> {
+    header: &'a [u8],
+    fragment: &'a [u8],
+}
+
+impl<'a> TlsRecord<'a> {
+    pub fn content_type(&self) -> TlsContentType {
+        TlsContentType::from(self.header[0])
+    }
+
+    pub fn version(&self) -> TlsVersion {
+        TlsVersion(u16::from_be_bytes([self.header[1], self.header[2]]))
+    }
+
+    pub fn len(&self) -> u16 {
+        u16::from_be_bytes([self.header[3], self.header[4]])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Part of the fragment found in this segment.
+    pub fn fragment(&self) -> &'a [u8] {
+        self.fragment
+    }
+
+    /// Whether the whole fragment was found in this segment.
+    pub fn is_complete(&self) -> bool {
+        self.fragment.len() == usize::from(self.len())
+    }
+
+    /// Handshake messages of a Handshake record.
+    pub fn handshakes(&self) -> Result<Vec<TlsHandshake<'a>>, FrameError> {
+        if let [typ, a, b, c, ..] = *self.fragment {
+            let len = u32::from_be_bytes([0, a, b, c]) as usize;
+            // A length running past a complete record is the tell of ciphertext
+            if self.is_complete() && !is_handshake_type(typ) && len > self.fragment.len() - 4 {
+                return Ok(vec![TlsHandshake::Encrypted]);
+            }
+        }
+        TlsHandshake::read_all(self.fragment)
+    }
+
+    /// Alert of an Alert record, unless it was encrypted.
+    pub fn alert(&self) -> Option<TlsAlert> {
+        match self.fragment {
+            [level, description] if self.is_complete() && (1..=2).contains(level) => {
+                Some(TlsAlert {
+                    level: *level,
+                    description: *description,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// TLS records starting at the beginning of a TCP payload.
+pub struct TlsFrame<'a>(&'a [u8]);
+
+impl<'a> TlsFrame<'a> {
+    /// Whether `data` begins with a record header, which tells the start of a record
+    /// apart from the continuation of a fragment.
+    pub fn is_tls(data: &[u8]) -> bool {
+        match data {
+            [typ, 3, minor, ..] => (20..=24).contains(typ) && *minor <= 4,
+            _ => false,
+        }
+    }
+
+    /// Records in the payload; the fragment of the last one may continue in the next
+    /// segment.
+    pub fn records(&self) -> Vec<TlsRecord<'a>> {
+        let mut reader = ByteReader::new(self.0, "TLS record");
+        let mut records = Vec::new();
+        while reader.remaining() >= RECORD_HEADER_LEN && Self::is_tls(&self.0[reader.pos()..]) {
+            let header = reader.bytes(RECORD_HEADER_LEN).unwrap_or_default();
+            let len = usize::from(u16::from_be_bytes([header[3], header[4]]));
+            let fragment = reader.bytes(len).unwrap_or_else(|_| reader.rest());
+            records.push(TlsRecord { header, fragment });
+        }
+        records
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for TlsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let reader = ByteReader::new(slice, "TLS record");
+        if slice.len() < RECORD_HEADER_LEN {
+            return Err(reader.malformed("record header cut short"));
+        }
+        if !Self::is_tls(slice) {
+            return Err(reader.malformed("unknown content type or version"));
+        }
+        Ok(Self(slice))
+    }
+}
+
+impl<'a> TryFrom<TcpFrame<'a>> for TlsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: TcpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
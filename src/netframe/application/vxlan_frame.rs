@@ -0,0 +1,55 @@
+use super::*;
+
+const VXLAN_LEN: usize = 8;
+/// Flag telling the VNI is valid, which RFC 7348 requires to be set.
+const VNI_PRESENT: u8 = 0x08;
+
+/// Virtual eXtensible Local Area Network header (RFC 7348), carrying Ethernet
+/// frames over UDP.
+pub struct VxlanFrame<'a> {
+    header: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> VxlanFrame<'a> {
+    pub fn flags(&self) -> u8 {
+        self.header[0]
+    }
+
+    /// VXLAN network identifier, naming the overlay segment of the inner frame.
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.header[4], self.header[5], self.header[6]])
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> Encapsulated<'a> {
+        Encapsulated::ethernet(self.payload)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for VxlanFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "VXLAN header");
+        let header = reader.bytes(VXLAN_LEN)?;
+        if header[0] & VNI_PRESENT == 0 {
+            return Err(reader.malformed("VNI flag not set"));
+        }
+        Ok(Self {
+            header,
+            payload: reader.rest(),
+        })
+    }
+}
+
+impl<'a> TryFrom<UdpFrame<'a>> for VxlanFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: UdpFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+use super::*;
+
+/// Explicit tag of the version field of a TBS certificate.
+const VERSION: BerTag = BerTag::context_specific(0, true);
+
+fn attribute_name(oid: &str) -> Option<&'static str> {
+    match oid {
+        "2.5.4.3" => Some("CN"),
+        "2.5.4.5" => Some("serialNumber"),
+        "2.5.4.6" => Some("C"),
+        "2.5.4.7" => Some("L"),
+        "2.5.4.8" => Some("ST"),
+        "2.5.4.10" => Some("O"),
+        "2.5.4.11" => Some("OU"),
+        "1.2.840.113549.1.9.1" => Some("emailAddress"),
+        "0.9.2342.19200300.100.1.25" => Some("DC"),
+        _ => None,
+    }
+}
+
+/// Formats a distinguished name as its attributes separated by commas, in the
+/// order they were encoded.
+fn read_name(name: BerElement) -> Result<String, FrameError> {
+    let mut rdns = name.reader();
+    let mut attributes = Vec::new();
+    while !rdns.is_empty() {
+        let mut rdn = rdns.expect(BerTag::SET)?.reader();
+        while !rdn.is_empty() {
+            let mut attribute = rdn.expect(BerTag::SEQUENCE)?.reader();
+            let oid = attribute.expect(BerTag::OID)?.oid()?;
+            let value = attribute.read()?;
+            let name = attribute_name(&oid).map_or(oid, String::from);
+            attributes.push(format!("{}={}", name, value.string()));
+        }
+    }
+    Ok(attributes.join(", "))
+}
+
+fn read_time(reader: &mut BerReader) -> Result<DateTime<Utc>, FrameError> {
+    let element = reader.read()?;
+    let time = element.string();
+    let time = match element.tag() {
+        // Two digit years from 50 onwards are in the 20th century (RFC 5280)
+        BerTag::UTC_TIME if time.as_bytes().first() >= Some(&b'5') => format!("19{}", time),
+        BerTag::UTC_TIME => format!("20{}", time),
+        BerTag::GENERALIZED_TIME => time,
+        _ => return Err(reader.malformed("unexpected time type")),
+    };
+    NaiveDateTime::parse_from_str(&time, "%Y%m%d%H%M%SZ")
+        .map(|time| Utc.from_utc_datetime(&time))
+        .map_err(|_| reader.malformed("invalid time"))
+}
+
+/// Identity fields of a DER encoded X.509 certificate. Extensions and the
+/// signature are not decoded.
+pub struct X509Certificate<'a> {
+    serial: &'a [u8],
+    issuer: String,
+    subject: String,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+}
+
+impl<'a> X509Certificate<'a> {
+    pub fn serial(&self) -> &'a [u8] {
+        self.serial
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn not_before(&self) -> DateTime<Utc> {
+        self.not_before
+    }
+
+    pub fn not_after(&self) -> DateTime<Utc> {
+        self.not_after
+    }
+
+    pub fn is_self_signed(&self) -> bool {
+        self.issuer == self.subject
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for X509Certificate<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = BerReader::new(slice, "X.509 certificate");
+        let mut cert = reader.expect(BerTag::SEQUENCE)?.reader();
+        let mut tbs = cert.expect(BerTag::SEQUENCE)?.reader();
+        // Version 1 certificates omit the version
+        if tbs.peek() == Some(VERSION) {
+            tbs.read()?;
+        }
+        let serial = tbs.expect(BerTag::INTEGER)?.contents();
+        tbs.expect(BerTag::SEQUENCE)?;
+        let issuer = read_name(tbs.expect(BerTag::SEQUENCE)?)?;
+        let mut validity = tbs.expect(BerTag::SEQUENCE)?.reader();
+        let not_before = read_time(&mut validity)?;
+        let not_after = read_time(&mut validity)?;
+        let subject = read_name(tbs.expect(BerTag::SEQUENCE)?)?;
+        Ok(Self {
+            serial,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+        })
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+
+use super::{ByteReader, FrameError};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BerClass {
+    Universal,       // 0b00
+    Application,     // 0b01
+    ContextSpecific, // 0b10
+    Private,         // 0b11
+}
+
+/// Identifier of a BER element.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BerTag {
+    pub class: BerClass,
+    pub constructed: bool,
+    pub number: u32,
+}
+
+impl BerTag {
+    pub const BOOLEAN: Self = Self::universal(1, false);
+    pub const INTEGER: Self = Self::universal(2, false);
+    pub const BIT_STRING: Self = Self::universal(3, false);
+    pub const OCTET_STRING: Self = Self::universal(4, false);
+    pub const NULL: Self = Self::universal(5, false);
+    pub const OID: Self = Self::universal(6, false);
+    pub const UTF8_STRING: Self = Self::universal(12, false);
+    pub const SEQUENCE: Self = Self::universal(16, true);
+    pub const SET: Self = Self::universal(17, true);
+    pub const PRINTABLE_STRING: Self = Self::universal(19, false);
+    pub const IA5_STRING: Self = Self::universal(22, false);
+    pub const UTC_TIME: Self = Self::universal(23, false);
+    pub const GENERALIZED_TIME: Self = Self::universal(24, false);
+    pub const BMP_STRING: Self = Self::universal(30, false);
+
+    pub const fn universal(number: u32, constructed: bool) -> Self {
+        Self {
+            class: BerClass::Universal,
+            constructed,
+            number,
+        }
+    }
+
+    pub const fn application(number: u32, constructed: bool) -> Self {
+        Self {
+            class: BerClass::Application,
+            constructed,
+            number,
+        }
+    }
+
+    pub const fn context_specific(number: u32, constructed: bool) -> Self {
+        Self {
+            class: BerClass::ContextSpecific,
+            constructed,
+            number,
+        }
+    }
+}
+
+impl fmt::Display for BerTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.class {
+            BerClass::Universal => write!(f, "[UNIVERSAL {}]", self.number),
+            BerClass::Application => write!(f, "[APPLICATION {}]", self.number),
+            BerClass::ContextSpecific => write!(f, "[{}]", self.number),
+            BerClass::Private => write!(f, "[PRIVATE {}]", self.number),
+        }
+    }
+}
+
+/// Element of a BER encoding, with its contents left undecoded until they are
+/// read as a given type.
+pub struct BerElement<'a> {
+    tag: BerTag,
+    contents: &'a [u8],
+    what: &'static str,
+}
+
+impl<'a> BerElement<'a> {
+    pub fn tag(&self) -> BerTag {
+        self.tag
+    }
+
+    pub fn contents(&self) -> &'a [u8] {
+        self.contents
+    }
+
+    pub fn malformed(&self, reason: &'static str) -> FrameError {
+        FrameError::Malformed {
+            what: self.what,
+            reason,
+        }
+    }
+
+    /// Reader over the elements nested in a constructed element.
+    pub fn reader(&self) -> BerReader<'a> {
+        BerReader::new(self.contents, self.what)
+    }
+
+    pub fn boolean(&self) -> Result<bool, FrameError> {
+        match self.contents {
+            [val] => Ok(*val != 0),
+            _ => Err(self.malformed("boolean is not a single byte")),
+        }
+    }
+
+    /// Two's complement integer of up to 8 bytes.
+    pub fn integer(&self) -> Result<i64, FrameError> {
+        if self.contents.is_empty() || self.contents.len() > 8 {
+            return Err(self.malformed("integer does not fit in 64 bits"));
+        }
+        let sign = if self.contents[0] & 0x80 != 0 { -1 } else { 0 };
+        Ok(self
+            .contents
+            .iter()
+            .fold(sign, |acc, &byte| acc << 8 | i64::from(byte)))
+    }
+
+    /// Non-negative integer of up to 64 bits, which may take 9 bytes as the
+    /// encoding is signed.
+    pub fn unsigned(&self) -> Result<u64, FrameError> {
+        let contents = match self.contents {
+            [0, rest @ ..] if !rest.is_empty() => rest,
+            contents => contents,
+        };
+        if contents.is_empty() || contents.len() > 8 {
+            return Err(self.malformed("integer does not fit in 64 bits"));
+        }
+        if self.contents[0] & 0x80 != 0 {
+            return Err(self.malformed("negative unsigned integer"));
+        }
+        Ok(contents
+            .iter()
+            .fold(0, |acc, &byte| acc << 8 | u64::from(byte)))
+    }
+
+    /// Object identifier in dotted decimal notation.
+    pub fn oid(&self) -> Result<String, FrameError> {
+        if self.contents.last().is_none_or(|byte| byte & 0x80 != 0) {
+            return Err(self.malformed("object identifier not terminated"));
+        }
+        let mut arcs = Vec::new();
+        let mut arc = 0u64;
+        for &byte in self.contents {
+            arc = arc << 7 | u64::from(byte & 0x7F);
+            if byte & 0x80 != 0 {
+                continue;
+            }
+            // The first subidentifier packs the first two arcs
+            if arcs.is_empty() {
+                let first = (arc / 40).min(2);
+                arcs.push(first);
+                arcs.push(arc - first * 40);
+            } else {
+                arcs.push(arc);
+            }
+            arc = 0;
+        }
+        let arcs: Vec<_> = arcs.iter().map(u64::to_string).collect();
+        Ok(arcs.join("."))
+    }
+
+    /// Contents of a character string. BMP strings are decoded as UTF-16 and any
+    /// other type as UTF-8, which covers the ASCII based types.
+    pub fn string(&self) -> String {
+        if self.tag == BerTag::BMP_STRING {
+            let units: Vec<_> = self
+                .contents
+                .chunks_exact(2)
+                .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            String::from_utf8_lossy(self.contents).into_owned()
+        }
+    }
+}
+
+/// Reader over a sequence of BER elements, such as the contents of a constructed
+/// element.
+#[derive(Clone)]
+pub struct BerReader<'a>(ByteReader<'a>);
+
+impl<'a> BerReader<'a> {
+    pub fn new(data: &'a [u8], what: &'static str) -> Self {
+        Self(ByteReader::new(data, what))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn malformed(&self, reason: &'static str) -> FrameError {
+        self.0.malformed(reason)
+    }
+
+    fn read_tag(&mut self) -> Result<BerTag, FrameError> {
+        let first = self.0.u8()?;
+        let class = match first >> 6 {
+            0b00 => BerClass::Universal,
+            0b01 => BerClass::Application,
+            0b10 => BerClass::ContextSpecific,
+            _ => BerClass::Private,
+        };
+        let mut number = u32::from(first & 0x1F);
+        // High tag numbers follow in base 128
+        if number == 0x1F {
+            number = 0;
+            loop {
+                let byte = self.0.u8()?;
+                if number > u32::MAX >> 7 {
+                    return Err(self.malformed("tag number too large"));
+                }
+                number = number << 7 | u32::from(byte & 0x7F);
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(BerTag {
+            class,
+            constructed: first & 0x20 != 0,
+            number,
+        })
+    }
+
+    /// Reads the length following `tag`, none standing for an indefinite length.
+    fn read_len(&mut self, tag: BerTag) -> Result<Option<usize>, FrameError> {
+        match self.0.u8()? {
+            len if len < 0x80 => Ok(Some(usize::from(len))),
+            0x80 if !tag.constructed => {
+                Err(self.malformed("indefinite length of a primitive element"))
+            }
+            0x80 => Ok(None),
+            len if len & 0x7F > 4 => Err(self.malformed("length too long")),
+            len => Ok(Some(
+                self.0
+                    .bytes(usize::from(len & 0x7F))?
+                    .iter()
+                    .fold(0, |acc, &byte| acc << 8 | usize::from(byte)),
+            )),
+        }
+    }
+
+    /// Reads the next element. Constructed elements of indefinite length are
+    /// delimited by their end-of-contents marker, which is left out of the contents.
+    pub fn read(&mut self) -> Result<BerElement<'a>, FrameError> {
+        let tag = self.read_tag()?;
+        let contents = match self.read_len(tag)? {
+            Some(len) => self.0.bytes(len)?,
+            None => {
+                let start = self.0.pos();
+                // Elements of indefinite length may nest arbitrarily deep, so the
+                // levels are counted instead of recursing into each of them
+                let mut depth = 1;
+                loop {
+                    if self.0.data()[self.0.pos()..].starts_with(&[0, 0]) {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                        self.0.bytes(2)?;
+                        continue;
+                    }
+                    let tag = self.read_tag()?;
+                    match self.read_len(tag)? {
+                        Some(len) => {
+                            self.0.bytes(len)?;
+                        }
+                        None => depth += 1,
+                    }
+                }
+                let end = self.0.pos();
+                self.0.bytes(2)?;
+                &self.0.data()[start..end]
+            }
+        };
+        Ok(BerElement {
+            tag,
+            contents,
+            what: self.0.what(),
+        })
+    }
+
+    /// Reads the next element, failing unless it has the tag `tag`.
+    pub fn expect(&mut self, tag: BerTag) -> Result<BerElement<'a>, FrameError> {
+        let element = self.read()?;
+        if element.tag != tag {
+            return Err(self.malformed("unexpected tag"));
+        }
+        Ok(element)
+    }
+
+    /// Tag of the next element, without consuming it.
+    pub fn peek(&self) -> Option<BerTag> {
+        self.clone().read_tag().ok()
+    }
+}
@@ -1,5 +1,19 @@
+use crate::netframe::{ByteReader, FrameError};
+
 use super::*;
 
+/// Length of an ARP message for Ethernet and IPv4 addresses.
+const ARP_LEN: usize = 28;
+
+/// Checks that `payload` holds a whole ARP message, addresses included, before it
+/// is parsed by the infallible `ArpFrame::from`.
+pub(crate) fn check_arp(payload: &[u8]) -> Result<(), FrameError> {
+    let mut reader = ByteReader::new(payload, "ARP message");
+    let header = reader.bytes(ARP_LEN)?;
+    let (hlen, plen) = (usize::from(header[4]), usize::from(header[5]));
+    reader.seek(8 + 2 * (hlen + plen))
+}
+
 pub enum Operation {
     Request,
     Reply,
@@ -42,25 +56,25 @@ impl<'a> ArpFrame<'a> {
     }
 
     pub fn sha(&self) -> &'a [u8] {
-        let size = self.hlen() as usize;
+        let size = usize::from(self.hlen());
         &self.0[8..8 + size]
     }
 
     pub fn spa(&self) -> &'a [u8] {
-        let begin = (8 + self.hlen()) as usize;
-        let end = begin + self.plen() as usize;
+        let begin = 8 + usize::from(self.hlen());
+        let end = begin + usize::from(self.plen());
         &self.0[begin..end]
     }
 
     pub fn tha(&self) -> &'a [u8] {
-        let begin = (8 + self.hlen() + self.plen()) as usize;
-        let end = begin + self.hlen() as usize;
+        let begin = 8 + usize::from(self.hlen()) + usize::from(self.plen());
+        let end = begin + usize::from(self.hlen());
         &self.0[begin..end]
     }
 
     pub fn tpa(&self) -> &'a [u8] {
-        let begin = (8 + self.hlen() * 2 + self.plen()) as usize;
-        let end = begin + self.plen() as usize;
+        let begin = 8 + usize::from(self.hlen()) * 2 + usize::from(self.plen());
+        let end = begin + usize::from(self.plen());
         &self.0[begin..end]
     }
 }
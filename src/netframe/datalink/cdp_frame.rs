@@ -0,0 +1,196 @@
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use crate::netframe::application::to_hex;
+use crate::netframe::{ByteReader, FrameError};
+
+use super::*;
+
+/// 802.2 protocol identifier of IPv6 in address TLVs.
+const CDP_IPV6: [u8; 8] = [0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x86, 0xDD];
+
+/// Names of the capabilities set in a capability bitmap of CDP.
+pub fn cdp_capability_names(bits: u32) -> Vec<&'static str> {
+    const NAMES: [&str; 11] = [
+        "Router",
+        "Transparent bridge",
+        "Source route bridge",
+        "Switch",
+        "Host",
+        "IGMP capable",
+        "Repeater",
+        "VoIP phone",
+        "Remotely managed",
+        "CVTA/STP dispute resolution",
+        "Two-port MAC relay",
+    ];
+    NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Reads the address list of the address and management address TLVs, skipping
+/// the protocols other than IPv4 and IPv6.
+fn read_addresses(reader: &mut ByteReader) -> Result<Vec<IpAddr>, FrameError> {
+    let mut addresses = Vec::new();
+    for _ in 0..reader.u32()? {
+        let protocol_type = reader.u8()?;
+        let len = usize::from(reader.u8()?);
+        let protocol = reader.bytes(len)?;
+        let len = usize::from(reader.u16()?);
+        let address = reader.bytes(len)?;
+        let address = match (protocol_type, protocol) {
+            // NLPID of IP
+            (1, [0xCC]) => <[u8; 4]>::try_from(address).ok().map(IpAddr::from),
+            (2, protocol) if protocol == CDP_IPV6 => {
+                <[u8; 16]>::try_from(address).ok().map(IpAddr::from)
+            }
+            _ => None,
+        };
+        addresses.extend(address);
+    }
+    Ok(addresses)
+}
+
+pub enum CdpTlv<'a> {
+    DeviceId(&'a [u8]),               // 0x0001
+    Addresses(Vec<IpAddr>),           // 0x0002
+    PortId(&'a [u8]),                 // 0x0003
+    Capabilities(u32),                // 0x0004
+    SoftwareVersion(&'a [u8]),        // 0x0005
+    Platform(&'a [u8]),               // 0x0006
+    NativeVlan(u16),                  // 0x000A
+    FullDuplex(bool),                 // 0x000B
+    ManagementAddresses(Vec<IpAddr>), // 0x0016
+    Other(u16, &'a [u8]),
+}
+
+impl<'a> fmt::Display for CdpTlv<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |addresses: &[IpAddr]| {
+            addresses
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            CdpTlv::DeviceId(value) => {
+                write!(f, "Device ID: {}", String::from_utf8_lossy(value))
+            }
+            CdpTlv::Addresses(addresses) => write!(f, "Addresses: {}", join(addresses)),
+            CdpTlv::PortId(value) => write!(f, "Port ID: {}", String::from_utf8_lossy(value)),
+            CdpTlv::Capabilities(bits) => {
+                write!(
+                    f,
+                    "Capabilities: {}",
+                    cdp_capability_names(*bits).join(", ")
+                )
+            }
+            CdpTlv::SoftwareVersion(value) => {
+                // Usually several lines long, the first one naming the release
+                let version = String::from_utf8_lossy(value);
+                write!(
+                    f,
+                    "Software version: {}",
+                    version.lines().next().unwrap_or("")
+                )
+            }
+            CdpTlv::Platform(value) => write!(f, "Platform: {}", String::from_utf8_lossy(value)),
+            CdpTlv::NativeVlan(vlan_id) => write!(f, "Native VLAN: {}", vlan_id),
+            CdpTlv::FullDuplex(true) => write!(f, "Duplex: full"),
+            CdpTlv::FullDuplex(false) => write!(f, "Duplex: half"),
+            CdpTlv::ManagementAddresses(addresses) => {
+                write!(f, "Management addresses: {}", join(addresses))
+            }
+            CdpTlv::Other(typ, value) => write!(f, "0x{:04X}: {}", typ, to_hex(value)),
+        }
+    }
+}
+
+impl<'a> CdpTlv<'a> {
+    fn read(typ: u16, value: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(value, "CDP TLV");
+        let tlv = match typ {
+            0x0001 => CdpTlv::DeviceId(value),
+            0x0002 => CdpTlv::Addresses(read_addresses(&mut reader)?),
+            0x0003 => CdpTlv::PortId(value),
+            0x0004 => CdpTlv::Capabilities(reader.u32()?),
+            0x0005 => CdpTlv::SoftwareVersion(value),
+            0x0006 => CdpTlv::Platform(value),
+            0x000A => CdpTlv::NativeVlan(reader.u16()?),
+            0x000B => CdpTlv::FullDuplex(reader.u8()? != 0),
+            0x0016 => CdpTlv::ManagementAddresses(read_addresses(&mut reader)?),
+            other => CdpTlv::Other(other, value),
+        };
+        Ok(tlv)
+    }
+}
+
+/// Cisco Discovery Protocol packet, sent over SNAP.
+pub struct CdpFrame<'a> {
+    header: &'a [u8],
+    tlvs: Vec<CdpTlv<'a>>,
+}
+
+impl<'a> CdpFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0]
+    }
+
+    /// Seconds the information stays valid.
+    pub fn ttl(&self) -> u8 {
+        self.header[1]
+    }
+
+    /// Checksum as sent, which Cisco devices compute differently from the Internet
+    /// checksum for packets of odd length.
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    pub fn tlvs(&self) -> &[CdpTlv<'a>] {
+        &self.tlvs
+    }
+
+    pub fn device_id(&self) -> Option<&'a [u8]> {
+        self.tlvs.iter().find_map(|tlv| match tlv {
+            CdpTlv::DeviceId(value) => Some(*value),
+            _ => None,
+        })
+    }
+
+    pub fn port_id(&self) -> Option<&'a [u8]> {
+        self.tlvs.iter().find_map(|tlv| match tlv {
+            CdpTlv::PortId(value) => Some(*value),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for CdpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "CDP header");
+        let header = reader.bytes(4)?;
+        if !matches!(header[0], 1 | 2) {
+            return Err(reader.malformed("unsupported version"));
+        }
+        let mut reader = ByteReader::new(reader.rest(), "CDP TLV");
+        let mut tlvs = Vec::new();
+        while !reader.is_empty() {
+            let typ = reader.u16()?;
+            // The length includes the type and length fields
+            let len = usize::from(reader.u16()?);
+            if len < 4 {
+                return Err(reader.malformed("TLV shorter than its header"));
+            }
+            tlvs.push(CdpTlv::read(typ, reader.bytes(len - 4)?)?);
+        }
+        Ok(Self { header, tlvs })
+    }
+}
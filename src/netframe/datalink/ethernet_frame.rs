@@ -1,6 +1,8 @@
 use pcap::Packet;
 
-use crate::netframe::UnknownFrame;
+use std::convert::TryFrom;
+
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
 
 use super::HwAddr;
 use super::*;
@@ -9,9 +11,14 @@ pub enum EtherType<'a> {
     Ipv4(Ipv4Frame<'a>),
     Ipv6(Ipv6Frame<'a>),
     Arp(ArpFrame<'a>),
+    Mpls(MplsFrame<'a>),
+    Pppoe(PppoeFrame<'a>),
+    Lldp(LldpFrame<'a>),
     IeeeLlc(IeeeLlcFrame<'a>),
-    IeeeSnap,
+    IeeeSnap(SnapFrame<'a>),
     NovellIeee,
+    /// The EtherType matched a known protocol, but its header could not be parsed.
+    Malformed(&'static str, FrameError),
     Other(UnknownFrame<'a>),
 }
 
@@ -21,8 +28,14 @@ impl<'a> fmt::Display for EtherType<'a> {
             EtherType::Ipv4(_) => write!(f, "IPv4 (0x0800)"),
             EtherType::Ipv6(_) => write!(f, "IPv6 (0x86DD)"),
             EtherType::Arp(_) => write!(f, "ARP (0x0806)"),
+            EtherType::Mpls(_) => write!(f, "MPLS"),
+            EtherType::Pppoe(frame) if frame.is_discovery() => {
+                write!(f, "PPPoE Discovery (0x8863)")
+            }
+            EtherType::Pppoe(_) => write!(f, "PPPoE Session (0x8864)"),
+            EtherType::Lldp(_) => write!(f, "LLDP (0x88CC)"),
             EtherType::IeeeLlc(_) => write!(f, "IEEE 802.2 LLC (< 1500)"),
-            EtherType::IeeeSnap => write!(
+            EtherType::IeeeSnap(_) => write!(
                 f, 
                 "IEEE 802.2 SNAP (< 1500, payload begins with 0xAAAA)"
             ),
@@ -30,6 +43,7 @@ impl<'a> fmt::Display for EtherType<'a> {
                 f,
                 "Novell raw IEEE 802.3 (< 1500, payload begins with 0xFFFF)"
             ),
+            EtherType::Malformed(name, _) => write!(f, "{} (malformed)", name),
             EtherType::Other(typ) => write!(f, "Other (0x{:04X})", typ.type_id()),
         }
     }
@@ -53,19 +67,53 @@ impl<'a> EthernetFrame<'a> {
         u16::from_be_bytes(clone_into_array(&self.header[12..14]))
     }
 
+    /// Next header, checked before being parsed: frames carried by tunnels are not
+    /// padded to the minimum Ethernet length.
     pub fn try_next_header(self) -> EtherType<'a> {
         match self.ether_type() {
-            0x0800 => EtherType::Ipv4(Ipv4Frame::from(self)),
-            0x86DD => EtherType::Ipv6(Ipv6Frame::from(self)),
-            0x0806 => EtherType::Arp(ArpFrame::from(self)),
+            0x0800 => match check_ipv4(self.payload) {
+                Ok(()) => EtherType::Ipv4(Ipv4Frame::from(self)),
+                Err(err) => EtherType::Malformed("IPv4", err),
+            },
+            0x86DD => match check_ipv6(self.payload) {
+                Ok(()) => EtherType::Ipv6(Ipv6Frame::from(self)),
+                Err(err) => EtherType::Malformed("IPv6", err),
+            },
+            0x0806 => match check_arp(self.payload) {
+                Ok(()) => EtherType::Arp(ArpFrame::from(self)),
+                Err(err) => EtherType::Malformed("ARP", err),
+            },
+            // Unicast and multicast label stacks
+            0x8847 | 0x8848 => match MplsFrame::try_from(self) {
+                Ok(frame) => EtherType::Mpls(frame),
+                Err(err) => EtherType::Malformed("MPLS", err),
+            },
+            0x8863 | 0x8864 => match PppoeFrame::try_from(self) {
+                Ok(frame) => EtherType::Pppoe(frame),
+                Err(err) => EtherType::Malformed("PPPoE", err),
+            },
+            0x88CC => match LldpFrame::try_from(self) {
+                Ok(frame) => EtherType::Lldp(frame),
+                Err(err) => EtherType::Malformed("LLDP", err),
+            },
             size if size <= 1500 => {
-                let id = u16::from_be_bytes(clone_into_array(&self.payload[0..2]));
+                let mut reader = ByteReader::new(self.payload, "IEEE 802.2 LLC header");
+                let id = match reader.u16() {
+                    Ok(id) => id,
+                    Err(err) => return EtherType::Malformed("IEEE 802.2 LLC", err),
+                };
                 if id == 0xFFFF {
                     EtherType::NovellIeee
                 } else if id == 0xAAAA {
-                    EtherType::IeeeSnap
+                    match SnapFrame::try_from(self) {
+                        Ok(frame) => EtherType::IeeeSnap(frame),
+                        Err(err) => EtherType::Malformed("SNAP", err),
+                    }
                 } else {
-                    EtherType::IeeeLlc(IeeeLlcFrame::from(self))
+                    match reader.seek(if size > 3 { 4 } else { 3 }) {
+                        Ok(()) => EtherType::IeeeLlc(IeeeLlcFrame::from(self)),
+                        Err(err) => EtherType::Malformed("IEEE 802.2 LLC", err),
+                    }
                 }
             }
             other => EtherType::Other(UnknownFrame::from_u8_slice(self.payload, other)),
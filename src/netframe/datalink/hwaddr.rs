@@ -4,7 +4,9 @@ use std::str::FromStr;
 
 use crate::clone_into_array;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+use super::OuiDatabase;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct HwAddr([u8; 6]);
 
 impl HwAddr {
@@ -12,9 +14,40 @@ impl HwAddr {
         self.0
     }
 
+    pub fn oui(&self) -> [u8; 3] {
+        clone_into_array(&self.0[0..3])
+    }
+
     pub fn is_broadcast(&self) -> bool {
         self.0 == [0xffu8; 6]
     }
+
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0b0000_0001 != 0
+    }
+
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0b0000_0010 != 0
+    }
+
+    /// Name of the address using the vendor short name found in `db`, e.g. `Cisco_9c:df:1b`.
+    /// Falls back to the plain address if the OUI is unknown.
+    pub fn resolve(&self, db: &OuiDatabase) -> String {
+        match db.lookup(self) {
+            Some(vendor) => format!(
+                "{}_{:02x}:{:02x}:{:02x}",
+                vendor.short_name(),
+                self.0[3],
+                self.0[4],
+                self.0[5]
+            ),
+            None => self.to_string(),
+        }
+    }
 }
 
 impl FromStr for HwAddr {
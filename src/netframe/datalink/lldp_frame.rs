@@ -0,0 +1,318 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use crate::netframe::application::to_hex;
+use crate::netframe::{ByteReader, FrameError};
+
+use super::*;
+
+const IEEE_802_1: [u8; 3] = [0x00, 0x80, 0xC2];
+const IEEE_802_3: [u8; 3] = [0x00, 0x12, 0x0F];
+
+/// Names of the capabilities set in a capability bitmap of LLDP (IEEE 802.1AB).
+pub fn lldp_capability_names(bits: u16) -> Vec<&'static str> {
+    const NAMES: [&str; 11] = [
+        "Other",
+        "Repeater",
+        "Bridge",
+        "WLAN access point",
+        "Router",
+        "Telephone",
+        "DOCSIS cable device",
+        "Station",
+        "C-VLAN component",
+        "S-VLAN component",
+        "Two-port MAC relay",
+    ];
+    NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Reads an address prefixed by its IANA address family, as used by the network
+/// address subtypes and the management address TLV.
+fn read_address(family: u8, addr: &[u8]) -> Option<IpAddr> {
+    match family {
+        1 => <[u8; 4]>::try_from(addr).ok().map(IpAddr::from),
+        2 => <[u8; 16]>::try_from(addr).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+/// Chassis or port identifier, decoded according to its subtype.
+pub enum LldpId<'a> {
+    Mac(HwAddr),
+    Address(IpAddr),
+    /// Interface names, aliases and locally assigned identifiers.
+    Name(&'a [u8]),
+    Other(u8, &'a [u8]),
+}
+
+impl<'a> LldpId<'a> {
+    /// Identifier of the subtype `subtype`, where the MAC and network address
+    /// subtypes are `mac` and `mac + 1`, the only ones differing between chassis
+    /// and port identifiers.
+    fn read(subtype: u8, id: &'a [u8], mac: u8) -> Self {
+        match (subtype, id) {
+            (s, [_, _, _, _, _, _]) if s == mac => LldpId::Mac(HwAddr::from(id)),
+            (s, [family, addr @ ..]) if s == mac + 1 => match read_address(*family, addr) {
+                Some(addr) => LldpId::Address(addr),
+                None => LldpId::Other(subtype, id),
+            },
+            (1..=7, _) => LldpId::Name(id),
+            _ => LldpId::Other(subtype, id),
+        }
+    }
+}
+
+impl<'a> fmt::Display for LldpId<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LldpId::Mac(addr) => write!(f, "{}", addr),
+            LldpId::Address(addr) => write!(f, "{}", addr),
+            LldpId::Name(name) => write!(f, "{}", String::from_utf8_lossy(name)),
+            LldpId::Other(subtype, id) => write!(f, "{} (subtype {})", to_hex(id), subtype),
+        }
+    }
+}
+
+pub enum LldpTlv<'a> {
+    ChassisId(LldpId<'a>),       // 1
+    PortId(LldpId<'a>),          // 2
+    Ttl(u16),                    // 3
+    PortDescription(&'a [u8]),   // 4
+    SystemName(&'a [u8]),        // 5
+    SystemDescription(&'a [u8]), // 6
+    Capabilities {
+        supported: u16,
+        enabled: u16,
+    }, // 7
+    ManagementAddress {
+        /// `None` for address families other than IPv4 and IPv6.
+        address: Option<IpAddr>,
+        interface: u32,
+    }, // 8
+    PortVlanId(u16),             // 127, IEEE 802.1 subtype 1
+    VlanName {
+        vlan_id: u16,
+        name: &'a [u8],
+    }, // 127, IEEE 802.1 subtype 3
+    MacPhyConfig {
+        autoneg_supported: bool,
+        autoneg_enabled: bool,
+        mau_type: u16,
+    }, // 127, IEEE 802.3 subtype 1
+    MaxFrameSize(u16),           // 127, IEEE 802.3 subtype 4
+    Organization {
+        oui: [u8; 3],
+        subtype: u8,
+        info: &'a [u8],
+    }, // 127
+    Other(u8, &'a [u8]),
+}
+
+fn text(value: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(value)
+}
+
+impl<'a> fmt::Display for LldpTlv<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LldpTlv::ChassisId(id) => write!(f, "Chassis ID: {}", id),
+            LldpTlv::PortId(id) => write!(f, "Port ID: {}", id),
+            LldpTlv::Ttl(ttl) => write!(f, "Time to live: {} s", ttl),
+            LldpTlv::PortDescription(value) => write!(f, "Port description: {}", text(value)),
+            LldpTlv::SystemName(value) => write!(f, "System name: {}", text(value)),
+            LldpTlv::SystemDescription(value) => {
+                // Often several lines long, the first one naming the system
+                let description = text(value);
+                write!(
+                    f,
+                    "System description: {}",
+                    description.lines().next().unwrap_or("")
+                )
+            }
+            LldpTlv::Capabilities { supported, enabled } => write!(
+                f,
+                "Capabilities: {} (enabled: {})",
+                lldp_capability_names(*supported).join(", "),
+                lldp_capability_names(*enabled).join(", ")
+            ),
+            LldpTlv::ManagementAddress { address, interface } => match address {
+                Some(address) => {
+                    write!(
+                        f,
+                        "Management address: {} (interface {})",
+                        address, interface
+                    )
+                }
+                None => write!(
+                    f,
+                    "Management address: unknown family (interface {})",
+                    interface
+                ),
+            },
+            LldpTlv::PortVlanId(vlan_id) => write!(f, "Port VLAN ID: {}", vlan_id),
+            LldpTlv::VlanName { vlan_id, name } => {
+                write!(f, "VLAN name: {} ({})", text(name), vlan_id)
+            }
+            LldpTlv::MacPhyConfig {
+                autoneg_supported,
+                autoneg_enabled,
+                mau_type,
+            } => write!(
+                f,
+                "MAC/PHY configuration: auto-negotiation {}, MAU type {}",
+                match (autoneg_supported, autoneg_enabled) {
+                    (true, true) => "enabled",
+                    (true, false) => "disabled",
+                    _ => "not supported",
+                },
+                mau_type
+            ),
+            LldpTlv::MaxFrameSize(size) => write!(f, "Maximum frame size: {}", size),
+            LldpTlv::Organization { oui, subtype, info } => write!(
+                f,
+                "Organization {} subtype {}: {}",
+                to_hex(oui),
+                subtype,
+                to_hex(info)
+            ),
+            LldpTlv::Other(typ, value) => write!(f, "Unknown ({}): {}", typ, to_hex(value)),
+        }
+    }
+}
+
+impl<'a> LldpTlv<'a> {
+    fn read(typ: u8, value: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(value, "LLDP TLV");
+        let tlv = match typ {
+            1 => LldpTlv::ChassisId(LldpId::read(reader.u8()?, reader.rest(), 4)),
+            2 => LldpTlv::PortId(LldpId::read(reader.u8()?, reader.rest(), 3)),
+            3 => LldpTlv::Ttl(reader.u16()?),
+            4 => LldpTlv::PortDescription(value),
+            5 => LldpTlv::SystemName(value),
+            6 => LldpTlv::SystemDescription(value),
+            7 => LldpTlv::Capabilities {
+                supported: reader.u16()?,
+                enabled: reader.u16()?,
+            },
+            8 => {
+                // The address length includes the address family
+                let len = usize::from(reader.u8()?);
+                if len < 1 {
+                    return Err(reader.malformed("empty management address"));
+                }
+                let family = reader.u8()?;
+                let address = read_address(family, reader.bytes(len - 1)?);
+                reader.u8()?;
+                LldpTlv::ManagementAddress {
+                    address,
+                    interface: reader.u32()?,
+                }
+            }
+            127 => {
+                let oui = clone_into_array(reader.bytes(3)?);
+                let subtype = reader.u8()?;
+                match (oui, subtype) {
+                    (IEEE_802_1, 1) => LldpTlv::PortVlanId(reader.u16()?),
+                    (IEEE_802_1, 3) => {
+                        let vlan_id = reader.u16()?;
+                        let len = usize::from(reader.u8()?);
+                        LldpTlv::VlanName {
+                            vlan_id,
+                            name: reader.bytes(len)?,
+                        }
+                    }
+                    (IEEE_802_3, 1) => {
+                        let autoneg = reader.u8()?;
+                        reader.u16()?;
+                        LldpTlv::MacPhyConfig {
+                            autoneg_supported: autoneg & 0x01 != 0,
+                            autoneg_enabled: autoneg & 0x02 != 0,
+                            mau_type: reader.u16()?,
+                        }
+                    }
+                    (IEEE_802_3, 4) => LldpTlv::MaxFrameSize(reader.u16()?),
+                    _ => LldpTlv::Organization {
+                        oui,
+                        subtype,
+                        info: reader.rest(),
+                    },
+                }
+            }
+            other => LldpTlv::Other(other, value),
+        };
+        Ok(tlv)
+    }
+}
+
+/// Link Layer Discovery Protocol data unit (IEEE 802.1AB).
+pub struct LldpFrame<'a> {
+    tlvs: Vec<LldpTlv<'a>>,
+}
+
+impl<'a> LldpFrame<'a> {
+    /// TLVs in the order they were sent, the first three being the chassis ID, the
+    /// port ID and the time to live.
+    pub fn tlvs(&self) -> &[LldpTlv<'a>] {
+        &self.tlvs
+    }
+
+    pub fn chassis_id(&self) -> &LldpId<'a> {
+        match &self.tlvs[0] {
+            LldpTlv::ChassisId(id) => id,
+            _ => unreachable!("the first TLV is checked when parsing"),
+        }
+    }
+
+    pub fn port_id(&self) -> &LldpId<'a> {
+        match &self.tlvs[1] {
+            LldpTlv::PortId(id) => id,
+            _ => unreachable!("the second TLV is checked when parsing"),
+        }
+    }
+
+    /// Seconds the information stays valid, zero telling to forget it right away.
+    pub fn ttl(&self) -> u16 {
+        match self.tlvs[2] {
+            LldpTlv::Ttl(ttl) => ttl,
+            _ => unreachable!("the third TLV is checked when parsing"),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for LldpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "LLDP TLV");
+        let mut tlvs = Vec::new();
+        while !reader.is_empty() {
+            let header = reader.u16()?;
+            let typ = (header >> 9) as u8;
+            let value = reader.bytes(usize::from(header & 0x01FF))?;
+            // End of LLDPDU
+            if typ == 0 {
+                break;
+            }
+            tlvs.push(LldpTlv::read(typ, value)?);
+        }
+        match tlvs.as_slice() {
+            [LldpTlv::ChassisId(_), LldpTlv::PortId(_), LldpTlv::Ttl(_), ..] => Ok(Self { tlvs }),
+            _ => Err(reader.malformed("missing chassis ID, port ID or TTL TLV")),
+        }
+    }
+}
+
+impl<'a> TryFrom<EthernetFrame<'a>> for LldpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: EthernetFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
@@ -1,9 +1,16 @@
 use std::fmt;
 
 pub use arp_frame::*;
+pub use cdp_frame::*;
 pub use ethernet_frame::*;
 pub use hwaddr::*;
 pub use ieee_llc_frame::*;
+pub use lldp_frame::*;
+pub use mpls_frame::*;
+pub use ppp_frame::*;
+pub use pppoe_frame::*;
+pub use snap_frame::*;
+pub use oui::*;
 
 use crate::clone_into_array;
 
@@ -12,4 +19,11 @@ use super::internet::*;
 mod ethernet_frame;
 mod ieee_llc_frame;
 mod arp_frame;
+mod cdp_frame;
 mod hwaddr;
+mod lldp_frame;
+mod mpls_frame;
+mod ppp_frame;
+mod pppoe_frame;
+mod snap_frame;
+mod oui;
@@ -0,0 +1,144 @@
+use std::convert::TryFrom;
+
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
+
+use super::*;
+
+/// Name of the labels reserved by RFC 3032 and later RFCs.
+pub fn mpls_reserved_label_name(label: u32) -> Option<&'static str> {
+    let name = match label {
+        0 => "IPv4 Explicit NULL",
+        1 => "Router Alert",
+        2 => "IPv6 Explicit NULL",
+        3 => "Implicit NULL",
+        7 => "Entropy Label Indicator",
+        13 => "Generic Associated Channel",
+        14 => "OAM Alert",
+        15 => "Extension",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Label stack entry.
+#[derive(Copy, Clone)]
+pub struct MplsLabel {
+    pub label: u32,
+    /// Traffic class, formerly the experimental bits.
+    pub tc: u8,
+    pub bottom_of_stack: bool,
+    pub ttl: u8,
+}
+
+impl fmt::Display for MplsLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Label {}", self.label)?;
+        if let Some(name) = mpls_reserved_label_name(self.label) {
+            write!(f, " ({})", name)?;
+        }
+        write!(f, ", TC {}, TTL {}", self.tc, self.ttl)?;
+        if self.bottom_of_stack {
+            write!(f, ", bottom of stack")?;
+        }
+        Ok(())
+    }
+}
+
+/// MPLS label stack (RFC 3032). The payload carries no type, so it is guessed from
+/// the bottom label and from the first nibble following the stack.
+pub struct MplsFrame<'a> {
+    labels: Vec<MplsLabel>,
+    payload: &'a [u8],
+}
+
+impl<'a> MplsFrame<'a> {
+    /// Label stack entries, from the top one to the bottom one.
+    pub fn labels(&self) -> &[MplsLabel] {
+        &self.labels
+    }
+
+    /// Pseudowire control word (RFC 4385), whose first nibble is zero unlike the
+    /// version of an IP header.
+    pub fn control_word(&self) -> Option<u32> {
+        match self.payload {
+            [first, second, third, fourth, ..] if first >> 4 == 0 => {
+                Some(u32::from_be_bytes([*first, *second, *third, *fourth]))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> Encapsulated<'a> {
+        let bottom = self.labels.last().map(|entry| entry.label);
+        match (bottom, self.payload.first().map(|first| first >> 4)) {
+            (Some(0), _) | (_, Some(4)) => Encapsulated::ipv4(self.payload),
+            (Some(2), _) | (_, Some(6)) => Encapsulated::ipv6(self.payload),
+            (_, Some(0)) if self.payload.len() >= 4 => Encapsulated::ethernet(&self.payload[4..]),
+            _ => Encapsulated::Other(UnknownFrame::from_u8_slice(self.payload, 0)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for MplsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "MPLS label stack");
+        let mut labels = Vec::new();
+        loop {
+            let entry = reader.u32()?;
+            let label = MplsLabel {
+                label: entry >> 12,
+                tc: ((entry >> 9) & 0x07) as u8,
+                bottom_of_stack: entry & 0x100 != 0,
+                ttl: entry as u8,
+            };
+            labels.push(label);
+            if label.bottom_of_stack {
+                break;
+            }
+        }
+        Ok(Self {
+            labels,
+            payload: reader.rest(),
+        })
+    }
+}
+
+impl<'a> TryFrom<EthernetFrame<'a>> for MplsFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: EthernetFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Label 17 at the bottom of the stack, then a zero control word and an Ethernet
+    /// header announcing IPv4, cut after the first byte of the IPv4 header.
+    const SHORT_PSEUDOWIRE: [u8; 23] = [
+        0x00, 0x01, 0x11, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x45,
+    ];
+
+    #[test]
+    fn short_pseudowire_payload() {
+        let frame = MplsFrame::try_from(&SHORT_PSEUDOWIRE[..]).unwrap();
+        assert_eq!(frame.control_word(), Some(0));
+        let inner = match frame.try_next_header() {
+            Encapsulated::Ethernet(inner) => inner,
+            other => panic!("unexpected {}", other),
+        };
+        assert!(matches!(
+            inner.try_next_header(),
+            EtherType::Malformed("IPv4", _)
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::HwAddr;
+
+const EMBEDDED_MANUF: &str = include_str!("manuf.txt");
+
+pub struct Vendor {
+    short_name: String,
+    name: String,
+}
+
+impl Vendor {
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Manufacturer database indexed by the 24 bit OUI of a hardware address.
+pub struct OuiDatabase {
+    vendors: HashMap<[u8; 3], Vendor>,
+}
+
+impl OuiDatabase {
+    /// Database compiled into the binary. Only covers a small set of common vendors.
+    pub fn embedded() -> Self {
+        Self::parse(EMBEDDED_MANUF)
+    }
+
+    /// Loads a database in the Wireshark `manuf` format.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Parses `manuf` formatted text. Comments, malformed lines and entries with
+    /// a mask longer than 24 bits are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut vendors = HashMap::new();
+        for line in text.lines() {
+            let line = match line.find('#') {
+                Some(idx) => &line[..idx],
+                None => line,
+            };
+            let mut fields = line.split('\t').map(str::trim).filter(|f| !f.is_empty());
+            let (prefix, short_name) = match (fields.next(), fields.next()) {
+                (Some(prefix), Some(short_name)) => (prefix, short_name),
+                _ => continue,
+            };
+            if let Some(oui) = parse_oui(prefix) {
+                vendors.insert(
+                    oui,
+                    Vendor {
+                        short_name: short_name.to_string(),
+                        name: fields.next().unwrap_or(short_name).to_string(),
+                    },
+                );
+            }
+        }
+        Self { vendors }
+    }
+
+    pub fn lookup(&self, addr: &HwAddr) -> Option<&Vendor> {
+        self.vendors.get(&addr.oui())
+    }
+
+    pub fn len(&self) -> usize {
+        self.vendors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vendors.is_empty()
+    }
+}
+
+fn parse_oui(prefix: &str) -> Option<[u8; 3]> {
+    let (prefix, mask) = match prefix.find('/') {
+        Some(idx) => (&prefix[..idx], prefix[idx + 1..].parse::<u8>().ok()?),
+        None => (prefix, 24),
+    };
+    if mask != 24 {
+        return None;
+    }
+
+    let mut oui = [0u8; 3];
+    let mut octets = prefix.split(&[':', '-', '.'][..]);
+    for byte in oui.iter_mut() {
+        *byte = u8::from_str_radix(octets.next()?, 16).ok()?;
+    }
+    Some(oui)
+}
@@ -0,0 +1,258 @@
+use std::convert::TryFrom;
+
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
+
+use super::*;
+
+const LCP: u16 = 0xC021;
+const IPCP: u16 = 0x8021;
+const IPV6CP: u16 = 0x8057;
+const PAP: u16 = 0xC023;
+const CHAP: u16 = 0xC223;
+
+/// Option of a Configure packet of LCP, IPCP or IPV6CP.
+pub struct PppOption<'a> {
+    pub typ: u8,
+    pub data: &'a [u8],
+}
+
+/// Packet of a PPP control or authentication protocol, all of which share a code,
+/// an identifier and a length (RFC 1661, section 5).
+pub struct PppPacket<'a> {
+    protocol: u16,
+    code: u8,
+    identifier: u8,
+    data: &'a [u8],
+}
+
+impl<'a> PppPacket<'a> {
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    pub fn identifier(&self) -> u8 {
+        self.identifier
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn code_name(&self) -> Option<&'static str> {
+        let name = match (self.protocol, self.code) {
+            (PAP, 1) => "Authenticate-Request",
+            (PAP, 2) => "Authenticate-Ack",
+            (PAP, 3) => "Authenticate-Nak",
+            (PAP, _) => return None,
+            (CHAP, 1) => "Challenge",
+            (CHAP, 2) => "Response",
+            (CHAP, 3) => "Success",
+            (CHAP, 4) => "Failure",
+            (CHAP, _) => return None,
+            (_, 1) => "Configure-Request",
+            (_, 2) => "Configure-Ack",
+            (_, 3) => "Configure-Nak",
+            (_, 4) => "Configure-Reject",
+            (_, 5) => "Terminate-Request",
+            (_, 6) => "Terminate-Ack",
+            (_, 7) => "Code-Reject",
+            (LCP, 8) => "Protocol-Reject",
+            (LCP, 9) => "Echo-Request",
+            (LCP, 10) => "Echo-Reply",
+            (LCP, 11) => "Discard-Request",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    pub fn option_name(&self, typ: u8) -> Option<&'static str> {
+        let name = match (self.protocol, typ) {
+            (LCP, 1) => "Maximum-Receive-Unit",
+            (LCP, 2) => "Async-Control-Character-Map",
+            (LCP, 3) => "Authentication-Protocol",
+            (LCP, 4) => "Quality-Protocol",
+            (LCP, 5) => "Magic-Number",
+            (LCP, 7) => "Protocol-Field-Compression",
+            (LCP, 8) => "Address-and-Control-Field-Compression",
+            (IPCP, 2) => "IP-Compression-Protocol",
+            (IPCP, 3) => "IP-Address",
+            (IPCP, 129) => "Primary-DNS-Server",
+            (IPCP, 131) => "Secondary-DNS-Server",
+            (IPV6CP, 1) => "Interface-Identifier",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Options of Configure packets of the network and link control protocols,
+    /// empty for other packets.
+    pub fn options(&self) -> Result<Vec<PppOption<'a>>, FrameError> {
+        let mut options = Vec::new();
+        if matches!(self.protocol, PAP | CHAP) || !(1..=4).contains(&self.code) {
+            return Ok(options);
+        }
+        let mut reader = ByteReader::new(self.data, "PPP option");
+        while !reader.is_empty() {
+            let typ = reader.u8()?;
+            let len = usize::from(reader.u8()?);
+            if len < 2 {
+                return Err(reader.malformed("option length below 2 bytes"));
+            }
+            options.push(PppOption {
+                typ,
+                data: reader.bytes(len - 2)?,
+            });
+        }
+        Ok(options)
+    }
+
+    /// Peer ID of a PAP Authenticate-Request, or name of a CHAP Challenge or Response.
+    pub fn name(&self) -> Result<Option<&'a [u8]>, FrameError> {
+        let mut reader = ByteReader::new(self.data, "PPP authentication packet");
+        match (self.protocol, self.code) {
+            (PAP, 1) => {
+                let len = usize::from(reader.u8()?);
+                Ok(Some(reader.bytes(len)?))
+            }
+            (CHAP, 1) | (CHAP, 2) => {
+                let len = usize::from(reader.u8()?);
+                reader.bytes(len)?;
+                Ok(Some(reader.rest()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Message of a PAP Authenticate-Ack or Nak, or of a CHAP Success or Failure.
+    pub fn message(&self) -> Result<Option<&'a [u8]>, FrameError> {
+        let mut reader = ByteReader::new(self.data, "PPP authentication packet");
+        match (self.protocol, self.code) {
+            (PAP, 2) | (PAP, 3) => {
+                let len = usize::from(reader.u8()?);
+                Ok(Some(reader.bytes(len)?))
+            }
+            (CHAP, 3) | (CHAP, 4) => Ok(Some(reader.rest())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether the packet is a PAP Authenticate-Request, whose password follows the
+    /// peer ID in clear.
+    pub fn has_cleartext_password(&self) -> bool {
+        (self.protocol, self.code) == (PAP, 1)
+    }
+
+    /// Whether the packet tells the peer failed to authenticate.
+    pub fn is_auth_failure(&self) -> bool {
+        matches!((self.protocol, self.code), (PAP, 3) | (CHAP, 4))
+    }
+
+    fn read(protocol: u16, slice: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(slice, "PPP packet");
+        let code = reader.u8()?;
+        let identifier = reader.u8()?;
+        let len = usize::from(reader.u16()?);
+        if len < 4 {
+            return Err(reader.malformed("length below 4 bytes"));
+        }
+        Ok(Self {
+            protocol,
+            code,
+            identifier,
+            data: reader.bytes(len - 4)?,
+        })
+    }
+}
+
+pub enum PppPayload<'a> {
+    Ipv4(Ipv4Frame<'a>),   // 0x0021
+    Ipv6(Ipv6Frame<'a>),   // 0x0057
+    Lcp(PppPacket<'a>),    // 0xC021
+    Ipcp(PppPacket<'a>),   // 0x8021
+    Ipv6cp(PppPacket<'a>), // 0x8057
+    Pap(PppPacket<'a>),    // 0xC023
+    Chap(PppPacket<'a>),   // 0xC223
+    /// The protocol field matched a known protocol, but its packet could not be parsed.
+    Malformed(&'static str, FrameError),
+    Other(UnknownFrame<'a>),
+}
+
+impl<'a> fmt::Display for PppPayload<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PppPayload::Ipv4(_) => write!(f, "IPv4 (0x0021)"),
+            PppPayload::Ipv6(_) => write!(f, "IPv6 (0x0057)"),
+            PppPayload::Lcp(_) => write!(f, "LCP (0xC021)"),
+            PppPayload::Ipcp(_) => write!(f, "IPCP (0x8021)"),
+            PppPayload::Ipv6cp(_) => write!(f, "IPV6CP (0x8057)"),
+            PppPayload::Pap(_) => write!(f, "PAP (0xC023)"),
+            PppPayload::Chap(_) => write!(f, "CHAP (0xC223)"),
+            PppPayload::Malformed(name, _) => write!(f, "{} (malformed)", name),
+            PppPayload::Other(typ) => write!(f, "Other (0x{:04X})", typ.type_id()),
+        }
+    }
+}
+
+/// Point-to-Point Protocol frame (RFC 1661), as carried by PPPoE sessions without
+/// the HDLC framing.
+pub struct PppFrame<'a> {
+    protocol: u16,
+    payload: &'a [u8],
+}
+
+impl<'a> PppFrame<'a> {
+    pub fn protocol(&self) -> u16 {
+        self.protocol
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> PppPayload<'a> {
+        let packet = |name, variant: fn(PppPacket<'a>) -> PppPayload<'a>| {
+            PppPacket::read(self.protocol, self.payload)
+                .map_or_else(|err| PppPayload::Malformed(name, err), variant)
+        };
+        match self.protocol {
+            0x0021 => match check_ipv4(self.payload) {
+                Ok(()) => PppPayload::Ipv4(Ipv4Frame::from(self.payload)),
+                Err(err) => PppPayload::Malformed("IPv4", err),
+            },
+            0x0057 => match check_ipv6(self.payload) {
+                Ok(()) => PppPayload::Ipv6(Ipv6Frame::from(self.payload)),
+                Err(err) => PppPayload::Malformed("IPv6", err),
+            },
+            LCP => packet("LCP", PppPayload::Lcp),
+            IPCP => packet("IPCP", PppPayload::Ipcp),
+            IPV6CP => packet("IPV6CP", PppPayload::Ipv6cp),
+            PAP => packet("PAP", PppPayload::Pap),
+            CHAP => packet("CHAP", PppPayload::Chap),
+            other => PppPayload::Other(UnknownFrame::from_u8_slice(self.payload, other)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PppFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "PPP header");
+        // Address and control fields, unless compressed away
+        if slice.starts_with(&[0xFF, 0x03]) {
+            reader.u16()?;
+        }
+        // Protocol numbers have an odd low byte, so an odd first byte tells the field
+        // was compressed to a single byte
+        let first = reader.u8()?;
+        let protocol = if first & 1 == 1 {
+            u16::from(first)
+        } else {
+            u16::from_be_bytes([first, reader.u8()?])
+        };
+        Ok(Self {
+            protocol,
+            payload: reader.rest(),
+        })
+    }
+}
@@ -0,0 +1,185 @@
+use std::convert::TryFrom;
+use std::str;
+
+use crate::netframe::application::to_hex;
+use crate::netframe::{ByteReader, FrameError};
+
+use super::*;
+
+const PPPOE_LEN: usize = 6;
+
+pub enum PppoeCode {
+    Session, // 0x00
+    Padi,    // 0x09
+    Pado,    // 0x07
+    Padr,    // 0x19
+    Pads,    // 0x65
+    Padt,    // 0xA7
+    Other(u8),
+}
+
+impl From<u8> for PppoeCode {
+    fn from(code: u8) -> Self {
+        match code {
+            0x00 => Self::Session,
+            0x09 => Self::Padi,
+            0x07 => Self::Pado,
+            0x19 => Self::Padr,
+            0x65 => Self::Pads,
+            0xA7 => Self::Padt,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for PppoeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Session => write!(f, "Session Data"),
+            Self::Padi => write!(f, "Active Discovery Initiation (PADI)"),
+            Self::Pado => write!(f, "Active Discovery Offer (PADO)"),
+            Self::Padr => write!(f, "Active Discovery Request (PADR)"),
+            Self::Pads => write!(f, "Active Discovery Session-confirmation (PADS)"),
+            Self::Padt => write!(f, "Active Discovery Terminate (PADT)"),
+            Self::Other(code) => write!(f, "Unknown (0x{:02X})", code),
+        }
+    }
+}
+
+pub fn pppoe_tag_name(typ: u16) -> Option<&'static str> {
+    let name = match typ {
+        0x0000 => "End-Of-List",
+        0x0101 => "Service-Name",
+        0x0102 => "AC-Name",
+        0x0103 => "Host-Uniq",
+        0x0104 => "AC-Cookie",
+        0x0105 => "Vendor-Specific",
+        0x0110 => "Relay-Session-Id",
+        0x0120 => "PPP-Max-Payload",
+        0x0201 => "Service-Name-Error",
+        0x0202 => "AC-System-Error",
+        0x0203 => "Generic-Error",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Tag of a discovery packet.
+pub struct PppoeTag<'a> {
+    pub typ: u16,
+    pub value: &'a [u8],
+}
+
+impl<'a> PppoeTag<'a> {
+    /// Whether the tag reports an error, which ends the discovery.
+    pub fn is_error(&self) -> bool {
+        matches!(self.typ, 0x0201..=0x0203)
+    }
+}
+
+impl<'a> fmt::Display for PppoeTag<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match pppoe_tag_name(self.typ) {
+            Some(name) => write!(f, "{}: ", name)?,
+            None => write!(f, "0x{:04X}: ", self.typ)?,
+        }
+        // Names and error messages are UTF-8 strings, other tags are opaque
+        match (self.typ, str::from_utf8(self.value)) {
+            (0x0101 | 0x0102 | 0x0201..=0x0203, Ok(text)) => write!(f, "{}", text),
+            (0x0120, _) if self.value.len() == 2 => {
+                write!(f, "{}", u16::from_be_bytes([self.value[0], self.value[1]]))
+            }
+            _ => write!(f, "{}", to_hex(self.value)),
+        }
+    }
+}
+
+/// PPP over Ethernet packet (RFC 2516), either a discovery packet (EtherType 0x8863)
+/// or session data (EtherType 0x8864).
+pub struct PppoeFrame<'a> {
+    header: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> PppoeFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0] >> 4
+    }
+
+    pub fn typ(&self) -> u8 {
+        self.header[0] & 0x0F
+    }
+
+    pub fn code(&self) -> PppoeCode {
+        PppoeCode::from(self.header[1])
+    }
+
+    pub fn is_discovery(&self) -> bool {
+        self.header[1] != 0
+    }
+
+    pub fn session_id(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    pub fn len(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[4..6]))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Tags of a discovery packet.
+    pub fn tags(&self) -> Result<Vec<PppoeTag<'a>>, FrameError> {
+        let mut reader = ByteReader::new(self.payload, "PPPoE tag");
+        let mut tags = Vec::new();
+        while !reader.is_empty() {
+            let typ = reader.u16()?;
+            let len = usize::from(reader.u16()?);
+            tags.push(PppoeTag {
+                typ,
+                value: reader.bytes(len)?,
+            });
+            if typ == 0x0000 {
+                break;
+            }
+        }
+        Ok(tags)
+    }
+
+    /// PPP frame of session data.
+    pub fn ppp(&self) -> Result<PppFrame<'a>, FrameError> {
+        PppFrame::try_from(self.payload)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PppoeFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "PPPoE header");
+        let header = reader.bytes(PPPOE_LEN)?;
+        if header[0] != 0x11 {
+            return Err(reader.malformed("unsupported version or type"));
+        }
+        let len = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        Ok(Self {
+            header,
+            // Drop the Ethernet padding of short packets
+            payload: reader.bytes(len)?,
+        })
+    }
+}
+
+impl<'a> TryFrom<EthernetFrame<'a>> for PppoeFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: EthernetFrame<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(frame.payload())
+    }
+}
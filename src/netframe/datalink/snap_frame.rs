@@ -0,0 +1,76 @@
+use std::convert::TryFrom;
+
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
+
+use super::*;
+
+const SNAP_LEN: usize = 8;
+const CISCO_OUI: [u8; 3] = [0x00, 0x00, 0x0C];
+
+pub enum SnapProtocol<'a> {
+    Cdp(CdpFrame<'a>), // OUI 0x00000C, PID 0x2000
+    /// The protocol identifier matched a known protocol, but its header could not be parsed.
+    Malformed(&'static str, FrameError),
+    Other(UnknownFrame<'a>),
+}
+
+impl<'a> fmt::Display for SnapProtocol<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapProtocol::Cdp(_) => write!(f, "CDP (0x2000)"),
+            SnapProtocol::Malformed(name, _) => write!(f, "{} (malformed)", name),
+            SnapProtocol::Other(frame) => write!(f, "Other (0x{:04X})", frame.type_id()),
+        }
+    }
+}
+
+/// IEEE 802.2 LLC header with a Subnetwork Access Protocol extension, naming the
+/// payload by an organization and a protocol identifier.
+pub struct SnapFrame<'a> {
+    header: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> SnapFrame<'a> {
+    pub fn oui(&self) -> [u8; 3] {
+        clone_into_array(&self.header[3..6])
+    }
+
+    /// Protocol identifier, an EtherType when the OUI is zero.
+    pub fn pid(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[6..8]))
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> SnapProtocol<'a> {
+        match (self.oui(), self.pid()) {
+            (CISCO_OUI, 0x2000) => match CdpFrame::try_from(self.payload) {
+                Ok(frame) => SnapProtocol::Cdp(frame),
+                Err(err) => SnapProtocol::Malformed("CDP", err),
+            },
+            (_, pid) => SnapProtocol::Other(UnknownFrame::from_u8_slice(self.payload, pid)),
+        }
+    }
+}
+
+impl<'a> TryFrom<EthernetFrame<'a>> for SnapFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: EthernetFrame<'a>) -> Result<Self, Self::Error> {
+        // The length field excludes the Ethernet padding of short frames
+        let len = usize::from(frame.ether_type());
+        let payload = frame.payload();
+        let mut reader = ByteReader::new(&payload[..len.min(payload.len())], "SNAP header");
+        let header = reader.bytes(SNAP_LEN)?;
+        if header[2] != 0x03 {
+            return Err(reader.malformed("not an unnumbered information frame"));
+        }
+        Ok(Self {
+            header,
+            payload: reader.rest(),
+        })
+    }
+}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+/// Error returned by the parsers that validate their input instead of trusting the
+/// layer below, like the ones dispatched by port number.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FrameError {
+    #[error("truncated {what}: {needed} bytes needed at offset {offset}, {available} available")]
+    Truncated {
+        what: &'static str,
+        offset: usize,
+        needed: usize,
+        available: usize,
+    },
+    #[error("malformed {what}: {reason}")]
+    Malformed {
+        what: &'static str,
+        reason: &'static str,
+    },
+}
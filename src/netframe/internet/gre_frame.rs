@@ -0,0 +1,122 @@
+use super::*;
+
+const CHECKSUM_PRESENT: u16 = 0x8000;
+const KEY_PRESENT: u16 = 0x2000;
+const SEQ_PRESENT: u16 = 0x1000;
+/// Only defined by the enhanced GRE of PPTP (RFC 2637).
+const ACK_PRESENT: u16 = 0x0080;
+/// EtherType of Ethernet frames bridged over a tunnel, as used by NVGRE.
+pub const TRANSPARENT_ETHERNET_BRIDGING: u16 = 0x6558;
+
+/// Generic Routing Encapsulation header (RFC 2784 and RFC 2890), as well as the
+/// enhanced version 1 used by PPTP.
+pub struct GreFrame<'a> {
+    raw: &'a [u8],
+    checksum: Option<u16>,
+    key: Option<u32>,
+    seq_num: Option<u32>,
+    ack_num: Option<u32>,
+    payload: &'a [u8],
+}
+
+impl<'a> GreFrame<'a> {
+    fn flags(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.raw[0..2]))
+    }
+
+    pub fn version(&self) -> u8 {
+        (self.flags() & 0x0007) as u8
+    }
+
+    /// EtherType of the payload.
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.raw[2..4]))
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        self.checksum
+    }
+
+    /// Whether the checksum, when present, covers the header and payload correctly.
+    pub fn has_integrity(&self) -> bool {
+        self.checksum.is_none() || u8_checksum16(self.raw) == 0
+    }
+
+    pub fn key(&self) -> Option<u32> {
+        self.key
+    }
+
+    /// Virtual subnet identifier of NVGRE (RFC 7637), from the upper 24 bits of the key.
+    pub fn nvgre_vsid(&self) -> Option<u32> {
+        match self.protocol_type() {
+            TRANSPARENT_ETHERNET_BRIDGING if self.version() == 0 => Some(self.key? >> 8),
+            _ => None,
+        }
+    }
+
+    /// Flow identifier of NVGRE, from the lower 8 bits of the key.
+    pub fn nvgre_flow_id(&self) -> Option<u8> {
+        self.nvgre_vsid().and(self.key.map(|key| key as u8))
+    }
+
+    pub fn seq_num(&self) -> Option<u32> {
+        self.seq_num
+    }
+
+    /// Acknowledgment number of the enhanced GRE of PPTP.
+    pub fn ack_num(&self) -> Option<u32> {
+        self.ack_num
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> Encapsulated<'a> {
+        Encapsulated::from_ether_type(self.protocol_type(), self.payload)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for GreFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "GRE header");
+        let flags = reader.u16()?;
+        reader.u16()?;
+        let version = flags & 0x0007;
+        if version > 1 {
+            return Err(reader.malformed("unsupported version"));
+        }
+        let checksum = if flags & CHECKSUM_PRESENT != 0 {
+            let checksum = reader.u16()?;
+            reader.u16()?;
+            Some(checksum)
+        } else {
+            None
+        };
+        let key = if flags & KEY_PRESENT != 0 {
+            Some(reader.u32()?)
+        } else {
+            None
+        };
+        let seq_num = if flags & SEQ_PRESENT != 0 {
+            Some(reader.u32()?)
+        } else {
+            None
+        };
+        let ack_num = if version == 1 && flags & ACK_PRESENT != 0 {
+            Some(reader.u32()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            raw: slice,
+            checksum,
+            key,
+            seq_num,
+            ack_num,
+            payload: reader.rest(),
+        })
+    }
+}
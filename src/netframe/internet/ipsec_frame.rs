@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use aes::cipher::{generic_array::GenericArray, Block, BlockDecrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, Nonce};
+
+use super::*;
+
+const AES_BLOCK_LEN: usize = 16;
+/// Explicit IV sent before the ciphertext of AES-GCM (RFC 4106).
+const GCM_IV_LEN: usize = 8;
+const GCM_ICV_LEN: usize = 16;
+/// Salt appended to the AES-GCM key, completing the nonce along with the IV.
+const GCM_SALT_LEN: usize = 4;
+
+/// Authentication Header (RFC 4302), protecting the integrity of the packet.
+pub struct AhFrame<'a> {
+    ip: IpHeader<'a>,
+    header: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> AhFrame<'a> {
+    /// Protocol of the payload.
+    pub fn next_header(&self) -> u8 {
+        self.header[0]
+    }
+
+    /// Length of the header in bytes.
+    pub fn header_len(&self) -> usize {
+        self.header.len()
+    }
+
+    pub fn spi(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[4..8]))
+    }
+
+    pub fn seq_num(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[8..12]))
+    }
+
+    /// Integrity check value, which cannot be verified without the key of the SA.
+    pub fn icv(&self) -> &'a [u8] {
+        &self.header[12..]
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn try_next_header(self) -> IpProtocol<'a> {
+        self.ip.try_header(self.next_header(), self.payload)
+    }
+}
+
+impl<'a> TryFrom<IpHeader<'a>> for AhFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(ip: IpHeader<'a>) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(ip.payload(), "AH header");
+        reader.u8()?;
+        // In 32 bit words, minus 2
+        let len = (usize::from(reader.u8()?) + 2) * 4;
+        if len < 12 {
+            return Err(reader.malformed("header length below 12 bytes"));
+        }
+        reader.seek(0)?;
+        Ok(Self {
+            ip,
+            header: reader.bytes(len)?,
+            payload: reader.rest(),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EspAlgorithm {
+    /// AES-CBC (RFC 3602), followed by an integrity check value of `icv_len` bytes
+    /// which is stripped without being verified.
+    AesCbc { icv_len: usize },
+    /// AES-GCM with a 16 byte ICV (RFC 4106), the last 4 bytes of the key being the salt.
+    AesGcm,
+}
+
+impl EspAlgorithm {
+    /// Algorithm named `name` in a security association file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let algorithm = match name {
+            "aes-cbc" => EspAlgorithm::AesCbc { icv_len: 0 },
+            "aes-cbc-hmac-sha1-96" => EspAlgorithm::AesCbc { icv_len: 12 },
+            "aes-cbc-hmac-sha256-128" => EspAlgorithm::AesCbc { icv_len: 16 },
+            "aes-gcm" => EspAlgorithm::AesGcm,
+            _ => return None,
+        };
+        Some(algorithm)
+    }
+
+    /// Lengths of the keys of the algorithm, the salt included for AES-GCM.
+    pub fn key_lens(&self) -> &'static [usize] {
+        match self {
+            EspAlgorithm::AesCbc { .. } => &[16, 24, 32],
+            EspAlgorithm::AesGcm => &[16 + GCM_SALT_LEN, 32 + GCM_SALT_LEN],
+        }
+    }
+}
+
+impl fmt::Display for EspAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EspAlgorithm::AesCbc { icv_len: 0 } => write!(f, "AES-CBC"),
+            EspAlgorithm::AesCbc { icv_len } => write!(f, "AES-CBC with {} byte ICV", icv_len),
+            EspAlgorithm::AesGcm => write!(f, "AES-GCM with 16 byte ICV"),
+        }
+    }
+}
+
+/// Encryption parameters of an ESP security association.
+pub struct EspSa {
+    pub algorithm: EspAlgorithm,
+    pub key: Vec<u8>,
+}
+
+/// ESP security associations indexed by SPI, used to decrypt captured traffic.
+#[derive(Default)]
+pub struct SecurityAssociations {
+    sas: HashMap<u32, EspSa>,
+}
+
+impl SecurityAssociations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a file with a security association per line: the SPI, the algorithm name
+    /// and the key, the SPI and key in hexadecimal. Blank lines and text after a `#`
+    /// are ignored.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Parses the text of a security association file, failing on the first
+    /// malformed line since a mistyped key would only show up as decryption errors.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut sas = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let error = |reason: &str| format!("line {}: {}", number + 1, reason);
+            let line = match line.find('#') {
+                Some(idx) => &line[..idx],
+                None => line,
+            };
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let (spi, algorithm, key) = match fields.as_slice() {
+                [] => continue,
+                [spi, algorithm, key] => (spi, algorithm, key),
+                _ => return Err(error("expected SPI, algorithm and key")),
+            };
+            let spi = u32::from_str_radix(spi.trim_start_matches("0x"), 16)
+                .map_err(|_| error("invalid SPI"))?;
+            let algorithm =
+                EspAlgorithm::from_name(algorithm).ok_or_else(|| error("unknown algorithm"))?;
+            let key =
+                parse_hex(key.trim_start_matches("0x")).ok_or_else(|| error("invalid key"))?;
+            if !algorithm.key_lens().contains(&key.len()) {
+                return Err(error(&format!(
+                    "{} byte key does not fit the algorithm",
+                    key.len()
+                )));
+            }
+            sas.insert(spi, EspSa { algorithm, key });
+        }
+        Ok(sas)
+    }
+
+    pub fn insert(&mut self, spi: u32, sa: EspSa) {
+        self.sas.insert(spi, sa);
+    }
+
+    pub fn get(&self, spi: u32) -> Option<&EspSa> {
+        self.sas.get(&spi)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sas.is_empty()
+    }
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn cbc_decrypt<C: BlockDecrypt + KeyInit>(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let cipher = C::new(GenericArray::from_slice(key));
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut previous = iv;
+    for block in ciphertext.chunks_exact(AES_BLOCK_LEN) {
+        let mut buf = Block::<C>::clone_from_slice(block);
+        cipher.decrypt_block(&mut buf);
+        plaintext.extend(buf.iter().zip(previous).map(|(byte, prev)| byte ^ prev));
+        previous = block;
+    }
+    plaintext
+}
+
+/// Encapsulating Security Payload (RFC 4303), whose payload is encrypted.
+pub struct EspFrame<'a> {
+    ip: IpHeader<'a>,
+    header: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> EspFrame<'a> {
+    pub fn spi(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[0..4]))
+    }
+
+    pub fn seq_num(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.header[4..8]))
+    }
+
+    /// Encrypted payload, along with the IV and ICV of the algorithm.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Decrypts the payload with the security association `sa`.
+    pub fn decrypt(&self, sa: &EspSa) -> Result<EspPlaintext<'a>, FrameError> {
+        let reader = ByteReader::new(self.data, "ESP payload");
+        let mut plaintext = match sa.algorithm {
+            EspAlgorithm::AesCbc { icv_len } => {
+                let len = self
+                    .data
+                    .len()
+                    .checked_sub(AES_BLOCK_LEN + icv_len)
+                    .filter(|len| *len > 0 && len % AES_BLOCK_LEN == 0)
+                    .ok_or_else(|| reader.malformed("length not a multiple of the block size"))?;
+                let (iv, ciphertext) = self.data[..AES_BLOCK_LEN + len].split_at(AES_BLOCK_LEN);
+                match sa.key.len() {
+                    16 => cbc_decrypt::<Aes128>(&sa.key, iv, ciphertext),
+                    24 => cbc_decrypt::<Aes192>(&sa.key, iv, ciphertext),
+                    32 => cbc_decrypt::<Aes256>(&sa.key, iv, ciphertext),
+                    _ => return Err(reader.malformed("unsupported key length")),
+                }
+            }
+            EspAlgorithm::AesGcm => {
+                if self.data.len() < GCM_IV_LEN + GCM_ICV_LEN {
+                    return Err(reader.malformed("payload shorter than the IV and ICV"));
+                }
+                let (key, salt) = sa.key.split_at(sa.key.len().saturating_sub(GCM_SALT_LEN));
+                let mut nonce = salt.to_vec();
+                nonce.extend(&self.data[..GCM_IV_LEN]);
+                let payload = Payload {
+                    msg: &self.data[GCM_IV_LEN..],
+                    aad: self.header,
+                };
+                match key.len() {
+                    16 => Aes128Gcm::new(GenericArray::from_slice(key))
+                        .decrypt(Nonce::from_slice(&nonce), payload),
+                    32 => Aes256Gcm::new(GenericArray::from_slice(key))
+                        .decrypt(Nonce::from_slice(&nonce), payload),
+                    _ => return Err(reader.malformed("unsupported key length")),
+                }
+                .map_err(|_| reader.malformed("decryption failed"))?
+            }
+        };
+
+        // The trailer ends with the padding length and the next header
+        let (next_header, pad_len) = match (plaintext.pop(), plaintext.pop()) {
+            (Some(next_header), Some(pad_len)) => (next_header, pad_len),
+            _ => return Err(reader.malformed("missing ESP trailer")),
+        };
+        let payload_len = plaintext
+            .len()
+            .checked_sub(usize::from(pad_len))
+            .ok_or_else(|| reader.malformed("padding longer than the payload"))?;
+        // The default padding counts from 1, which a wrong key is unlikely to produce
+        if !plaintext[payload_len..]
+            .iter()
+            .zip(1..)
+            .all(|(&pad, count)| pad == count)
+        {
+            return Err(reader.malformed("invalid padding, wrong key?"));
+        }
+        plaintext.truncate(payload_len);
+        Ok(EspPlaintext {
+            ip: self.ip,
+            next_header,
+            pad_len,
+            payload: plaintext,
+        })
+    }
+}
+
+impl<'a> TryFrom<IpHeader<'a>> for EspFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(ip: IpHeader<'a>) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(ip.payload(), "ESP header");
+        Ok(Self {
+            ip,
+            header: reader.bytes(8)?,
+            data: reader.rest(),
+        })
+    }
+}
+
+/// Decrypted payload of an ESP packet.
+pub struct EspPlaintext<'a> {
+    ip: IpHeader<'a>,
+    next_header: u8,
+    pad_len: u8,
+    payload: Vec<u8>,
+}
+
+impl<'a> EspPlaintext<'a> {
+    /// Protocol of the payload, 59 for dummy packets without payload.
+    pub fn next_header(&self) -> u8 {
+        self.next_header
+    }
+
+    pub fn pad_len(&self) -> u8 {
+        self.pad_len
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn try_next_header(&self) -> IpProtocol<'_> {
+        self.ip.try_header(self.next_header, &self.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UDP datagram from port 1234 to 5678 carrying "hello".
+    const UDP: &str = "04d2162e000d000068656c6c6f";
+
+    /// IPv4 packet carrying the ESP header and `data`.
+    fn esp_packet(header: &str, data: &str) -> Vec<u8> {
+        let mut packet = parse_hex("450000000000000040320000c0a80001c0a80002").unwrap();
+        packet.extend(parse_hex(header).unwrap());
+        packet.extend(parse_hex(data).unwrap());
+        let len = packet.len() as u16;
+        packet[2..4].copy_from_slice(&len.to_be_bytes());
+        packet
+    }
+
+    fn decrypt<'a>(packet: &'a [u8], sas: &str) -> EspPlaintext<'a> {
+        let frame = EspFrame::try_from(IpHeader::V4(Ipv4Frame::from(packet))).unwrap();
+        let sas = SecurityAssociations::parse(sas).unwrap();
+        frame.decrypt(sas.get(frame.spi()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn decrypt_aes_cbc() {
+        // Key and IV of RFC 3602, case #1
+        let packet = esp_packet(
+            "0000100000000001",
+            "3dafba429d9eb430b422da802c9fac41ddca368652b642726a2e8a58cadcbeb8",
+        );
+        let plaintext = decrypt(&packet, "0x1000 aes-cbc 0x06a9214036b8a15b512e03d534120006");
+        assert_eq!(plaintext.next_header(), 17);
+        assert_eq!(plaintext.pad_len(), 1);
+        assert_eq!(plaintext.payload(), parse_hex(UDP).unwrap().as_slice());
+    }
+
+    #[test]
+    fn decrypt_aes_gcm() {
+        // Key, salt and IV of the GCM specification, test case #3
+        let packet = esp_packet(
+            "0000a5f800000001",
+            "facedbaddecaf8889f603ac9d9fe72c1864e441e4424f3171b6745ee7e765034972b40ab6c0679fa",
+        );
+        let plaintext = decrypt(
+            &packet,
+            "a5f8 aes-gcm feffe9928665731c6d6a8f9467308308cafebabe",
+        );
+        assert_eq!(plaintext.next_header(), 17);
+        assert_eq!(plaintext.pad_len(), 1);
+        assert_eq!(plaintext.payload(), parse_hex(UDP).unwrap().as_slice());
+    }
+
+    #[test]
+    fn reject_key_length() {
+        assert!(SecurityAssociations::parse("1 aes-cbc 00112233445566778899aabbccddeeff").is_ok());
+        assert!(SecurityAssociations::parse("1 aes-cbc 00112233").is_err());
+        assert!(SecurityAssociations::parse("1 aes-gcm 00112233445566778899aabbccddeeff").is_err());
+    }
+}
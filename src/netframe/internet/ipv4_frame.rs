@@ -2,10 +2,12 @@ use std::net::Ipv4Addr;
 
 use super::*;
 
+#[derive(Clone, Copy)]
 pub struct Ipv4Frame<'a> {
     header: &'a [u8],
     opts: Option<&'a [u8]>,
     payload: &'a [u8],
+    captured_len: usize,
 }
 
 impl<'a> Ipv4Frame<'a> {
@@ -65,12 +67,28 @@ impl<'a> Ipv4Frame<'a> {
     }
 
     pub fn try_next_header(self) -> IpProtocol<'a> {
-        match self.protocol() {
-            0x06 => IpProtocol::Tcp(TcpFrame::from(self)),
-            0x11 => IpProtocol::Udp(UdpFrame::from(self)),
-            0x01 => IpProtocol::Icmp(IcmpFrame::from(self)),
-            0x02 => IpProtocol::Igmp(IgmpFrame::from(self)),
-            other => IpProtocol::Other(UnknownFrame::from_u8_slice(self.payload, other as u16))
+        self.try_header(self.protocol(), self.payload)
+    }
+
+    /// Parses `payload` as the header of `protocol` carried by this datagram, which
+    /// differ from its own protocol and payload behind IPsec headers.
+    pub(crate) fn try_header(self, protocol: u8, payload: &'a [u8]) -> IpProtocol<'a> {
+        let frame = Self { payload, ..self };
+        match protocol {
+            0x06 => IpProtocol::tcp(frame, payload),
+            0x11 => IpProtocol::udp(frame, payload),
+            0x01 => IpProtocol::icmp(frame),
+            0x02 => IpProtocol::igmp(frame),
+            0x04 => IpProtocol::ipv4(payload),
+            0x29 => IpProtocol::ipv6(payload),
+            0x2F => IpProtocol::gre(payload),
+            0x32 => IpProtocol::esp(IpHeader::V4(frame)),
+            0x33 => IpProtocol::ah(IpHeader::V4(frame)),
+            0x59 => IpProtocol::ospf(OspfFrame::try_from(frame)),
+            0x67 => IpProtocol::pim(IpHeader::V4(frame)),
+            0x70 => IpProtocol::vrrp(IpHeader::V4(frame)),
+            0x84 => IpProtocol::sctp(payload),
+            other => IpProtocol::Other(UnknownFrame::from_u8_slice(payload, other as u16))
         }
     }
 
@@ -98,6 +116,12 @@ impl<'a> Ipv4Frame<'a> {
         self.opts
     }
 
+    /// Bytes available for this datagram in the capture, including any link layer
+    /// padding after it. Differs from `total_len` on padded or truncated packets.
+    pub fn captured_len(&self) -> usize {
+        self.captured_len
+    }
+
     pub fn has_integrity(&self) -> bool {
         match self.opts {
             None => u8_checksum16(&self.header) == 0,
@@ -112,11 +136,16 @@ impl<'a> From<&'a [u8]> for Ipv4Frame<'a> {
     fn from(slice: &'a [u8]) -> Self {
         let header_len = usize::from(slice[0] & 0x0F);
 
+        let total_len = usize::from(u16::from_be_bytes([slice[2], slice[3]]));
+
         let (header, payload) = slice.split_at(header_len * 4);
         let (header, opts) = header.split_at(20);
+        // Drop the link layer padding trailing short datagrams
+        let payload = &payload[..payload.len().min(total_len.saturating_sub(header_len * 4))];
         Self {
             header,
             payload,
+            captured_len: slice.len(),
             opts: match opts {
                 [] => None,
                 _ => Some(opts),
@@ -2,6 +2,7 @@ use std::net::Ipv6Addr;
 
 use super::*;
 
+#[derive(Clone, Copy)]
 pub struct Ipv6Frame<'a> {
     header: &'a [u8],
     payload: &'a [u8],
@@ -36,6 +37,32 @@ impl<'a> Ipv6Frame<'a> {
         self.header[6]
     }
 
+    /// Extension headers are not walked, so only upper layer protocols directly
+    /// following the fixed header are found.
+    pub fn try_next_header(self) -> IpProtocol<'a> {
+        self.try_header(self.next_header(), self.payload)
+    }
+
+    /// Parses `payload` as the header of `next_header` carried by this packet, which
+    /// differ from its own next header and payload behind IPsec headers.
+    pub(crate) fn try_header(self, next_header: u8, payload: &'a [u8]) -> IpProtocol<'a> {
+        let frame = Self { payload, ..self };
+        match next_header {
+            0x06 => IpProtocol::tcp(frame, payload),
+            0x11 => IpProtocol::udp(frame, payload),
+            0x04 => IpProtocol::ipv4(payload),
+            0x29 => IpProtocol::ipv6(payload),
+            0x2F => IpProtocol::gre(payload),
+            0x32 => IpProtocol::esp(IpHeader::V6(frame)),
+            0x33 => IpProtocol::ah(IpHeader::V6(frame)),
+            0x59 => IpProtocol::ospf(OspfFrame::try_from(frame)),
+            0x67 => IpProtocol::pim(IpHeader::V6(frame)),
+            0x70 => IpProtocol::vrrp(IpHeader::V6(frame)),
+            0x84 => IpProtocol::sctp(payload),
+            other => IpProtocol::Other(UnknownFrame::from_u8_slice(payload, other as u16)),
+        }
+    }
+
     pub fn hop_limit(&self) -> u8 {
         self.header[7]
     }
@@ -76,6 +103,11 @@ impl<'a> From<&'a [u8]> for Ipv6Frame<'a> {
         assert_eq!(slice[0] & 0xF0, 0x60);
 
         let (header, payload) = slice.split_at(40);
+        // A zero payload length is used by jumbograms, which carry the real length in an extension header
+        let payload = match usize::from(u16::from_be_bytes([header[4], header[5]])) {
+            0 => payload,
+            len => &payload[..payload.len().min(len)],
+        };
         Self {
             header,
             payload,
@@ -1,29 +1,57 @@
+use std::convert::TryFrom;
 use std::fmt;
 
 use ux::*;
 
 pub use ipv4_frame::*;
 pub use ipv6_frame::*;
+pub use gre_frame::*;
 pub use icmp_frame::*;
 pub use igmp_frame::*;
+pub use ipsec_frame::*;
+pub use ospf_frame::*;
+pub use pim_frame::*;
+pub use vrrp_frame::*;
 
 use crate::clone_into_array;
 use crate::error_check::*;
-use crate::netframe::UnknownFrame;
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
 
 use super::datalink::EthernetFrame;
 use super::transport::*;
 
 mod ipv4_frame;
 mod ipv6_frame;
+mod gre_frame;
 mod icmp_frame;
 mod igmp_frame;
+mod ipsec_frame;
+mod ospf_frame;
+mod pim_frame;
+mod vrrp_frame;
+
+/// Tunnels decapsulated within a single packet before giving up, so that a crafted
+/// packet nesting headers in each other cannot exhaust the stack of a dissector.
+pub const MAX_ENCAPSULATION_DEPTH: usize = 8;
 
 pub enum IpProtocol<'a> {
     Tcp(TcpFrame<'a>),
     Udp(UdpFrame<'a>),
+    Sctp(SctpFrame<'a>),
     Icmp(IcmpFrame<'a>),
     Igmp(IgmpFrame<'a>),
+    Gre(GreFrame<'a>),
+    Esp(EspFrame<'a>),
+    Ah(AhFrame<'a>),
+    Ospf(OspfFrame<'a>),
+    Pim(PimFrame<'a>),
+    Vrrp(VrrpFrame<'a>),
+    /// IPv4 packet tunneled in IP (IP-in-IP or 4in6).
+    Ipv4(Ipv4Frame<'a>),
+    /// IPv6 packet tunneled in IP (6in4 or IPv6-in-IPv6).
+    Ipv6(Ipv6Frame<'a>),
+    /// The protocol number matched a known protocol, but its header could not be parsed.
+    Malformed(&'static str, FrameError),
     Other(UnknownFrame<'a>),
 }
 
@@ -32,9 +60,244 @@ impl<'a> fmt::Display for IpProtocol<'a> {
         match self {
             IpProtocol::Tcp(_) => write!(f, "TCP (0x06)"),
             IpProtocol::Udp(_) => write!(f, "UDP (0x11)"),
+            IpProtocol::Sctp(_) => write!(f, "SCTP (0x84)"),
             IpProtocol::Icmp(_) => write!(f, "ICMP (0x01)"),
             IpProtocol::Igmp(_) => write!(f, "IGMP (0x02)"),
+            IpProtocol::Gre(_) => write!(f, "GRE (0x2F)"),
+            IpProtocol::Esp(_) => write!(f, "ESP (0x32)"),
+            IpProtocol::Ah(_) => write!(f, "AH (0x33)"),
+            IpProtocol::Ospf(_) => write!(f, "OSPF (0x59)"),
+            IpProtocol::Pim(_) => write!(f, "PIM (0x67)"),
+            IpProtocol::Vrrp(_) => write!(f, "VRRP (0x70)"),
+            IpProtocol::Ipv4(_) => write!(f, "IPv4 (0x04)"),
+            IpProtocol::Ipv6(_) => write!(f, "IPv6 (0x29)"),
+            IpProtocol::Malformed(name, _) => write!(f, "{} (malformed)", name),
             IpProtocol::Other(typ) => write!(f, "Other (0x{:02X})", typ.type_id())
         }
     }
-}
\ No newline at end of file
+}
+
+impl<'a> IpProtocol<'a> {
+    /// Wraps the TCP header of `payload`, carried by the IP packet `frame`.
+    fn tcp<F>(frame: F, payload: &'a [u8]) -> Self
+    where
+        TcpFrame<'a>: From<F>,
+    {
+        match check_tcp(payload) {
+            Ok(()) => IpProtocol::Tcp(TcpFrame::from(frame)),
+            Err(err) => IpProtocol::Malformed("TCP", err),
+        }
+    }
+
+    fn udp<F>(frame: F, payload: &'a [u8]) -> Self
+    where
+        UdpFrame<'a>: From<F>,
+    {
+        match check_udp(payload) {
+            Ok(()) => IpProtocol::Udp(UdpFrame::from(frame)),
+            Err(err) => IpProtocol::Malformed("UDP", err),
+        }
+    }
+
+    fn icmp(frame: Ipv4Frame<'a>) -> Self {
+        let mut reader = ByteReader::new(frame.payload(), "ICMP header");
+        // Timestamp and address mask messages carry fixed fields past the header
+        let len = match frame.payload().first() {
+            Some(13) | Some(14) => 20,
+            Some(18) => 12,
+            _ => 8,
+        };
+        match reader.bytes(len) {
+            Ok(_) => IpProtocol::Icmp(IcmpFrame::from(frame)),
+            Err(err) => IpProtocol::Malformed("ICMP", err),
+        }
+    }
+
+    fn igmp(frame: Ipv4Frame<'a>) -> Self {
+        let mut reader = ByteReader::new(frame.payload(), "IGMP header");
+        match reader.bytes(8) {
+            Ok(_) => IpProtocol::Igmp(IgmpFrame::from(frame)),
+            Err(err) => IpProtocol::Malformed("IGMP", err),
+        }
+    }
+
+    /// Wraps the GRE header of `payload`, or the error met while parsing it.
+    fn gre(payload: &'a [u8]) -> Self {
+        match GreFrame::try_from(payload) {
+            Ok(frame) => IpProtocol::Gre(frame),
+            Err(err) => IpProtocol::Malformed("GRE", err),
+        }
+    }
+
+    fn sctp(payload: &'a [u8]) -> Self {
+        match SctpFrame::try_from(payload) {
+            Ok(frame) => IpProtocol::Sctp(frame),
+            Err(err) => IpProtocol::Malformed("SCTP", err),
+        }
+    }
+
+    fn ospf(frame: Result<OspfFrame<'a>, FrameError>) -> Self {
+        match frame {
+            Ok(frame) => IpProtocol::Ospf(frame),
+            Err(err) => IpProtocol::Malformed("OSPF", err),
+        }
+    }
+
+    fn pim(ip: IpHeader<'a>) -> Self {
+        match PimFrame::try_from(ip) {
+            Ok(frame) => IpProtocol::Pim(frame),
+            Err(err) => IpProtocol::Malformed("PIM", err),
+        }
+    }
+
+    fn vrrp(ip: IpHeader<'a>) -> Self {
+        match VrrpFrame::try_from(ip) {
+            Ok(frame) => IpProtocol::Vrrp(frame),
+            Err(err) => IpProtocol::Malformed("VRRP", err),
+        }
+    }
+
+    fn esp(ip: IpHeader<'a>) -> Self {
+        match EspFrame::try_from(ip) {
+            Ok(frame) => IpProtocol::Esp(frame),
+            Err(err) => IpProtocol::Malformed("ESP", err),
+        }
+    }
+
+    fn ah(ip: IpHeader<'a>) -> Self {
+        match AhFrame::try_from(ip) {
+            Ok(frame) => IpProtocol::Ah(frame),
+            Err(err) => IpProtocol::Malformed("AH", err),
+        }
+    }
+
+    /// Wraps the inner IPv4 packet of an IP-in-IP tunnel.
+    fn ipv4(payload: &'a [u8]) -> Self {
+        match check_ipv4(payload) {
+            Ok(()) => IpProtocol::Ipv4(Ipv4Frame::from(payload)),
+            Err(err) => IpProtocol::Malformed("IPv4", err),
+        }
+    }
+
+    /// Wraps the inner IPv6 packet of a 6in4 or IPv6-in-IPv6 tunnel.
+    fn ipv6(payload: &'a [u8]) -> Self {
+        match check_ipv6(payload) {
+            Ok(()) => IpProtocol::Ipv6(Ipv6Frame::from(payload)),
+            Err(err) => IpProtocol::Malformed("IPv6", err),
+        }
+    }
+}
+
+/// IP header carrying an IPsec header, whose addresses are still needed by the
+/// checksums of the transport protocols protected by it.
+#[derive(Clone, Copy)]
+pub enum IpHeader<'a> {
+    V4(Ipv4Frame<'a>),
+    V6(Ipv6Frame<'a>),
+}
+
+impl<'a> IpHeader<'a> {
+    /// Payload of the IP header, starting with the IPsec header.
+    pub fn payload(&self) -> &'a [u8] {
+        match self {
+            IpHeader::V4(frame) => frame.payload(),
+            IpHeader::V6(frame) => frame.payload(),
+        }
+    }
+
+    /// Sum of the pseudo-header of an upper layer message of `protocol`, `len`
+    /// bytes long, carried by this packet.
+    pub(crate) fn pseudo_header_sum(&self, protocol: u8, len: usize) -> u16 {
+        let addr_sum = match self {
+            IpHeader::V4(frame) => u8_slice_to_sum16(&frame.raw_header()[12..20]),
+            IpHeader::V6(frame) => u8_slice_to_sum16(&frame.raw_header()[8..40]),
+        };
+        u16_slice_to_sum16(&[addr_sum, u16::from(protocol), len as u16])
+    }
+
+    fn try_header(self, protocol: u8, payload: &'a [u8]) -> IpProtocol<'a> {
+        match self {
+            IpHeader::V4(frame) => frame.try_header(protocol, payload),
+            IpHeader::V6(frame) => frame.try_header(protocol, payload),
+        }
+    }
+}
+
+/// Checks that `payload` holds a whole IPv4 header, before it is parsed by the
+/// infallible `Ipv4Frame::from`.
+pub(crate) fn check_ipv4(payload: &[u8]) -> Result<(), FrameError> {
+    let mut reader = ByteReader::new(payload, "IPv4 header");
+    let first = reader.u8()?;
+    if first >> 4 != 4 {
+        return Err(reader.malformed("unsupported version"));
+    }
+    if first & 0x0F < 5 {
+        return Err(reader.malformed("header length below 20 bytes"));
+    }
+    reader.seek(usize::from(first & 0x0F) * 4)
+}
+
+pub(crate) fn check_ipv6(payload: &[u8]) -> Result<(), FrameError> {
+    let mut reader = ByteReader::new(payload, "IPv6 header");
+    match reader.bytes(40)?[0] >> 4 {
+        6 => Ok(()),
+        _ => Err(reader.malformed("unsupported version")),
+    }
+}
+
+/// Frame carried by a tunnel. Inner headers are as untrusted as any payload, so
+/// their lengths, down to the transport header, are checked before they are handed
+/// to the infallible parsers.
+pub enum Encapsulated<'a> {
+    Ethernet(EthernetFrame<'a>),
+    Ipv4(Ipv4Frame<'a>),
+    Ipv6(Ipv6Frame<'a>),
+    Malformed(&'static str, FrameError),
+    Other(UnknownFrame<'a>),
+}
+
+impl<'a> fmt::Display for Encapsulated<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encapsulated::Ethernet(_) => write!(f, "Ethernet (0x6558)"),
+            Encapsulated::Ipv4(_) => write!(f, "IPv4 (0x0800)"),
+            Encapsulated::Ipv6(_) => write!(f, "IPv6 (0x86DD)"),
+            Encapsulated::Malformed(name, _) => write!(f, "{} (malformed)", name),
+            Encapsulated::Other(typ) => write!(f, "Other (0x{:04X})", typ.type_id()),
+        }
+    }
+}
+
+impl<'a> Encapsulated<'a> {
+    /// Inner frame of the type `ether_type`, as named by GRE and Geneve headers.
+    pub fn from_ether_type(ether_type: u16, payload: &'a [u8]) -> Self {
+        match ether_type {
+            TRANSPARENT_ETHERNET_BRIDGING => Self::ethernet(payload),
+            0x0800 => Self::ipv4(payload),
+            0x86DD => Self::ipv6(payload),
+            other => Encapsulated::Other(UnknownFrame::from_u8_slice(payload, other)),
+        }
+    }
+
+    pub fn ethernet(payload: &'a [u8]) -> Self {
+        let mut reader = ByteReader::new(payload, "Ethernet header");
+        match reader.bytes(14) {
+            Ok(_) => Encapsulated::Ethernet(EthernetFrame::from(payload)),
+            Err(err) => Encapsulated::Malformed("Ethernet", err),
+        }
+    }
+
+    pub fn ipv4(payload: &'a [u8]) -> Self {
+        match check_ipv4(payload) {
+            Ok(()) => Encapsulated::Ipv4(Ipv4Frame::from(payload)),
+            Err(err) => Encapsulated::Malformed("IPv4", err),
+        }
+    }
+
+    pub fn ipv6(payload: &'a [u8]) -> Self {
+        match check_ipv6(payload) {
+            Ok(()) => Encapsulated::Ipv6(Ipv6Frame::from(payload)),
+            Err(err) => Encapsulated::Malformed("IPv6", err),
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::net::Ipv4Addr;
+
+use super::*;
+
+const OSPFV2_HEADER_LEN: usize = 24;
+const OSPFV3_HEADER_LEN: usize = 16;
+const LSA_HEADER_LEN: usize = 20;
+/// Authentication type of OSPFv2 whose digest replaces the checksum (RFC 2328, D.4.3).
+const CRYPTOGRAPHIC_AUTH: u16 = 2;
+
+/// Name of the LSA type `ls_type` of OSPF version `version`.
+pub fn ospf_lsa_type_name(version: u8, ls_type: u16) -> Option<&'static str> {
+    let name = match (version, ls_type) {
+        (2, 1) => "Router-LSA",
+        (2, 2) => "Network-LSA",
+        (2, 3) => "Summary-LSA (network)",
+        (2, 4) => "Summary-LSA (ASBR)",
+        (2, 5) => "AS-External-LSA",
+        (2, 7) => "NSSA-LSA",
+        (2, 9) => "Opaque-LSA (link-local)",
+        (2, 10) => "Opaque-LSA (area-local)",
+        (2, 11) => "Opaque-LSA (AS)",
+        // The upper bits of OSPFv3 types hold the flooding scope
+        (3, ls_type) => match ls_type & 0x1FFF {
+            1 => "Router-LSA",
+            2 => "Network-LSA",
+            3 => "Inter-Area-Prefix-LSA",
+            4 => "Inter-Area-Router-LSA",
+            5 => "AS-External-LSA",
+            7 => "NSSA-LSA",
+            8 => "Link-LSA",
+            9 => "Intra-Area-Prefix-LSA",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Header of a link state advertisement, identifying an instance of it.
+pub struct LsaHeader {
+    version: u8,
+    pub age: u16,
+    pub ls_type: u16,
+    pub link_state_id: Ipv4Addr,
+    pub advertising_router: Ipv4Addr,
+    pub seq_num: u32,
+    pub checksum: u16,
+    /// Length of the whole LSA, header included.
+    pub len: u16,
+}
+
+impl LsaHeader {
+    fn read(version: u8, reader: &mut ByteReader) -> Result<Self, FrameError> {
+        let age = reader.u16()?;
+        // The OSPFv2 type follows the options
+        let ls_type = match version {
+            2 => u16::from(reader.u16()? as u8),
+            _ => reader.u16()?,
+        };
+        Ok(Self {
+            version,
+            age,
+            ls_type,
+            link_state_id: Ipv4Addr::from(reader.u32()?),
+            advertising_router: Ipv4Addr::from(reader.u32()?),
+            seq_num: reader.u32()?,
+            checksum: reader.u16()?,
+            len: reader.u16()?,
+        })
+    }
+}
+
+impl fmt::Display for LsaHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ospf_lsa_type_name(self.version, self.ls_type) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "LSA type 0x{:04X}", self.ls_type)?,
+        }
+        write!(
+            f,
+            ", ID {}, router {}, seq 0x{:08X}, age {}",
+            self.link_state_id, self.advertising_router, self.seq_num, self.age
+        )
+    }
+}
+
+/// LSA requested by a link state request.
+pub struct LsaRequest {
+    version: u8,
+    pub ls_type: u16,
+    pub link_state_id: Ipv4Addr,
+    pub advertising_router: Ipv4Addr,
+}
+
+impl fmt::Display for LsaRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ospf_lsa_type_name(self.version, self.ls_type) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "LSA type 0x{:04X}", self.ls_type)?,
+        }
+        write!(
+            f,
+            ", ID {}, router {}",
+            self.link_state_id, self.advertising_router
+        )
+    }
+}
+
+pub struct OspfHello {
+    /// Mask of the network of the interface, OSPFv2 only.
+    pub network_mask: Option<Ipv4Addr>,
+    /// Identifier of the interface, OSPFv3 only.
+    pub interface_id: Option<u32>,
+    pub hello_interval: u16,
+    pub options: u32,
+    pub priority: u8,
+    pub dead_interval: u32,
+    pub designated_router: Ipv4Addr,
+    pub backup_router: Ipv4Addr,
+    /// Routers whose hellos were recently seen on the network.
+    pub neighbors: Vec<Ipv4Addr>,
+}
+
+pub enum OspfMessage {
+    Hello(OspfHello), // 1
+    DatabaseDescription {
+        mtu: u16,
+        options: u32,
+        /// Init, more and master bits.
+        flags: u8,
+        seq_num: u32,
+        lsas: Vec<LsaHeader>,
+    }, // 2
+    LinkStateRequest(Vec<LsaRequest>), // 3
+    /// Headers of the updated LSAs, whose bodies are skipped.
+    LinkStateUpdate(Vec<LsaHeader>), // 4
+    LinkStateAck(Vec<LsaHeader>), // 5
+    Other(u8),
+}
+
+impl fmt::Display for OspfMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OspfMessage::Hello(_) => write!(f, "Hello"),
+            OspfMessage::DatabaseDescription { .. } => write!(f, "Database description"),
+            OspfMessage::LinkStateRequest(_) => write!(f, "Link state request"),
+            OspfMessage::LinkStateUpdate(_) => write!(f, "Link state update"),
+            OspfMessage::LinkStateAck(_) => write!(f, "Link state acknowledgment"),
+            OspfMessage::Other(typ) => write!(f, "Unknown message ({})", typ),
+        }
+    }
+}
+
+/// Open Shortest Path First packet, version 2 (RFC 2328) over IPv4 or version 3
+/// (RFC 5340) over IPv6.
+pub struct OspfFrame<'a> {
+    /// Sum of the IPv6 pseudo-header, covered by the checksum of OSPFv3 only.
+    pseudo_header_sum: Option<u16>,
+    header: &'a [u8],
+    body: &'a [u8],
+}
+
+impl<'a> OspfFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0]
+    }
+
+    pub fn message_type(&self) -> u8 {
+        self.header[1]
+    }
+
+    /// Length of the packet, excluding the authentication trailer of OSPFv2.
+    pub fn packet_len(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    pub fn router_id(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.header[4..8]))
+    }
+
+    pub fn area_id(&self) -> Ipv4Addr {
+        Ipv4Addr::from(clone_into_array::<[u8; 4], u8>(&self.header[8..12]))
+    }
+
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[12..14]))
+    }
+
+    /// Authentication type of OSPFv2, 0 for none, 1 for a password and 2 for a
+    /// cryptographic digest.
+    pub fn auth_type(&self) -> Option<u16> {
+        match self.version() {
+            2 => Some(u16::from_be_bytes(clone_into_array(&self.header[14..16]))),
+            _ => None,
+        }
+    }
+
+    /// Instance of the protocol running on the link, OSPFv3 only.
+    pub fn instance_id(&self) -> Option<u8> {
+        match self.version() {
+            3 => Some(self.header[14]),
+            _ => None,
+        }
+    }
+
+    /// Whether the checksum matches, which is always the case for OSPFv2 packets
+    /// authenticated by a digest since they leave it zeroed.
+    pub fn has_integrity(&self) -> bool {
+        match self.pseudo_header_sum {
+            Some(pseudo_header_sum) => {
+                u16_checksum16(&[
+                    pseudo_header_sum,
+                    u8_slice_to_sum16(self.header),
+                    u8_slice_to_sum16(self.body),
+                ]) == 0
+            }
+            None if self.auth_type() == Some(CRYPTOGRAPHIC_AUTH) => true,
+            // The authentication field is left out of the checksum
+            None => {
+                u16_checksum16(&[
+                    u8_slice_to_sum16(&self.header[..16]),
+                    u8_slice_to_sum16(self.body),
+                ]) == 0
+            }
+        }
+    }
+
+    pub fn message(&self) -> Result<OspfMessage, FrameError> {
+        let version = self.version();
+        let mut reader = ByteReader::new(self.body, "OSPF message");
+        let message = match self.message_type() {
+            1 => {
+                let mut hello = match version {
+                    2 => OspfHello {
+                        network_mask: Some(Ipv4Addr::from(reader.u32()?)),
+                        interface_id: None,
+                        hello_interval: reader.u16()?,
+                        options: u32::from(reader.u8()?),
+                        priority: reader.u8()?,
+                        dead_interval: reader.u32()?,
+                        designated_router: Ipv4Addr::from(reader.u32()?),
+                        backup_router: Ipv4Addr::from(reader.u32()?),
+                        neighbors: Vec::new(),
+                    },
+                    _ => OspfHello {
+                        network_mask: None,
+                        interface_id: Some(reader.u32()?),
+                        priority: reader.u8()?,
+                        options: reader.u24()?,
+                        hello_interval: reader.u16()?,
+                        dead_interval: u32::from(reader.u16()?),
+                        designated_router: Ipv4Addr::from(reader.u32()?),
+                        backup_router: Ipv4Addr::from(reader.u32()?),
+                        neighbors: Vec::new(),
+                    },
+                };
+                while !reader.is_empty() {
+                    hello.neighbors.push(Ipv4Addr::from(reader.u32()?));
+                }
+                OspfMessage::Hello(hello)
+            }
+            2 => {
+                let (mtu, options) = match version {
+                    2 => (reader.u16()?, u32::from(reader.u8()?)),
+                    _ => {
+                        reader.u8()?;
+                        let options = reader.u24()?;
+                        let mtu = reader.u16()?;
+                        reader.u8()?;
+                        (mtu, options)
+                    }
+                };
+                OspfMessage::DatabaseDescription {
+                    mtu,
+                    options,
+                    flags: reader.u8()? & 0x07,
+                    seq_num: reader.u32()?,
+                    lsas: read_lsa_headers(version, &mut reader)?,
+                }
+            }
+            3 => {
+                let mut requests = Vec::new();
+                while !reader.is_empty() {
+                    let ls_type = match version {
+                        2 => reader.u32()? as u16,
+                        _ => {
+                            reader.u16()?;
+                            reader.u16()?
+                        }
+                    };
+                    requests.push(LsaRequest {
+                        version,
+                        ls_type,
+                        link_state_id: Ipv4Addr::from(reader.u32()?),
+                        advertising_router: Ipv4Addr::from(reader.u32()?),
+                    });
+                }
+                OspfMessage::LinkStateRequest(requests)
+            }
+            4 => {
+                let count = reader.u32()?;
+                let mut lsas = Vec::new();
+                for _ in 0..count {
+                    let start = reader.pos();
+                    let lsa = LsaHeader::read(version, &mut reader)?;
+                    if usize::from(lsa.len) < LSA_HEADER_LEN {
+                        return Err(reader.malformed("LSA shorter than its header"));
+                    }
+                    reader.seek(start + usize::from(lsa.len))?;
+                    lsas.push(lsa);
+                }
+                OspfMessage::LinkStateUpdate(lsas)
+            }
+            5 => OspfMessage::LinkStateAck(read_lsa_headers(version, &mut reader)?),
+            other => OspfMessage::Other(other),
+        };
+        Ok(message)
+    }
+}
+
+fn read_lsa_headers(version: u8, reader: &mut ByteReader) -> Result<Vec<LsaHeader>, FrameError> {
+    let mut lsas = Vec::new();
+    while !reader.is_empty() {
+        lsas.push(LsaHeader::read(version, reader)?);
+    }
+    Ok(lsas)
+}
+
+/// Splits `payload` into the header and body of an OSPF packet of version `version`.
+fn split_packet(payload: &[u8], version: u8) -> Result<(&[u8], &[u8]), FrameError> {
+    let mut reader = ByteReader::new(payload, "OSPF header");
+    let header_len = match version {
+        2 => OSPFV2_HEADER_LEN,
+        _ => OSPFV3_HEADER_LEN,
+    };
+    let header = reader.bytes(header_len)?;
+    if header[0] != version {
+        return Err(reader.malformed("unsupported version"));
+    }
+    // Anything past the length, such as an authentication digest, is not part of the packet
+    let len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+    if len < header_len {
+        return Err(reader.malformed("length shorter than the header"));
+    }
+    Ok((header, reader.bytes(len - header_len)?))
+}
+
+impl<'a> TryFrom<Ipv4Frame<'a>> for OspfFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: Ipv4Frame<'a>) -> Result<Self, Self::Error> {
+        let (header, body) = split_packet(frame.payload(), 2)?;
+        Ok(Self {
+            pseudo_header_sum: None,
+            header,
+            body,
+        })
+    }
+}
+
+impl<'a> TryFrom<Ipv6Frame<'a>> for OspfFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(frame: Ipv6Frame<'a>) -> Result<Self, Self::Error> {
+        let (header, body) = split_packet(frame.payload(), 3)?;
+        let addr_sum = u8_slice_to_sum16(&frame.raw_header()[8..40]);
+        let len = (header.len() + body.len()) as u16;
+        Ok(Self {
+            pseudo_header_sum: Some(u16_slice_to_sum16(&[addr_sum, 89, len])),
+            header,
+            body,
+        })
+    }
+}
@@ -0,0 +1,339 @@
+use std::net::IpAddr;
+
+use crate::netframe::application::to_hex;
+
+use super::*;
+
+const PIM_HEADER_LEN: usize = 4;
+/// Length of the Register header, the only part of Register messages covered by the
+/// checksum (RFC 7761, section 4.9).
+const REGISTER_HEADER_LEN: usize = 8;
+const REGISTER: u8 = 1;
+
+/// Reads an encoded unicast address.
+fn read_unicast(reader: &mut ByteReader) -> Result<IpAddr, FrameError> {
+    let family = reader.u8()?;
+    reader.u8()?;
+    read_address(reader, family)
+}
+
+fn read_address(reader: &mut ByteReader, family: u8) -> Result<IpAddr, FrameError> {
+    match family {
+        1 => Ok(IpAddr::from(clone_into_array::<[u8; 4], u8>(
+            reader.bytes(4)?,
+        ))),
+        2 => Ok(IpAddr::from(clone_into_array::<[u8; 16], u8>(
+            reader.bytes(16)?,
+        ))),
+        _ => Err(reader.malformed("unsupported address family")),
+    }
+}
+
+/// Encoded group or source address, along with its mask length and flags.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PimAddress {
+    pub addr: IpAddr,
+    pub mask_len: u8,
+    /// Sparse, wildcard and RPT bits of source addresses, or bidirectional and
+    /// admin scope zone bits of group addresses.
+    pub flags: u8,
+}
+
+impl PimAddress {
+    fn read(reader: &mut ByteReader) -> Result<Self, FrameError> {
+        let family = reader.u8()?;
+        reader.u8()?;
+        let flags = reader.u8()?;
+        let mask_len = reader.u8()?;
+        Ok(Self {
+            addr: read_address(reader, family)?,
+            mask_len,
+            flags,
+        })
+    }
+
+    /// Whether a joined or pruned source stands for every source (the (*,G) entry).
+    pub fn is_wildcard(&self) -> bool {
+        self.flags & 0x02 != 0
+    }
+
+    /// Whether a joined or pruned source applies to the shared tree.
+    pub fn is_rpt(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+}
+
+impl fmt::Display for PimAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.mask_len)
+    }
+}
+
+pub enum PimHelloOption<'a> {
+    Holdtime(u16), // 1
+    LanPruneDelay {
+        tracking: bool,
+        propagation_delay: u16,
+        override_interval: u16,
+    }, // 2
+    DrPriority(u32), // 19
+    GenerationId(u32), // 20
+    AddressList(Vec<IpAddr>), // 24
+    Other(u16, &'a [u8]),
+}
+
+impl<'a> fmt::Display for PimHelloOption<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PimHelloOption::Holdtime(holdtime) => write!(f, "Holdtime: {} s", holdtime),
+            PimHelloOption::LanPruneDelay {
+                tracking,
+                propagation_delay,
+                override_interval,
+            } => write!(
+                f,
+                "LAN prune delay: {} ms, override interval {} ms{}",
+                propagation_delay,
+                override_interval,
+                if *tracking {
+                    ", join suppression off"
+                } else {
+                    ""
+                }
+            ),
+            PimHelloOption::DrPriority(priority) => write!(f, "DR priority: {}", priority),
+            PimHelloOption::GenerationId(id) => write!(f, "Generation ID: 0x{:08X}", id),
+            PimHelloOption::AddressList(addresses) => write!(
+                f,
+                "Secondary addresses: {}",
+                addresses
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            PimHelloOption::Other(typ, value) => {
+                write!(f, "Option {}: {}", typ, to_hex(value))
+            }
+        }
+    }
+}
+
+impl<'a> PimHelloOption<'a> {
+    fn read(typ: u16, value: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(value, "PIM hello option");
+        let option = match typ {
+            1 => PimHelloOption::Holdtime(reader.u16()?),
+            2 => {
+                let delay = reader.u16()?;
+                PimHelloOption::LanPruneDelay {
+                    tracking: delay & 0x8000 != 0,
+                    propagation_delay: delay & 0x7FFF,
+                    override_interval: reader.u16()?,
+                }
+            }
+            19 => PimHelloOption::DrPriority(reader.u32()?),
+            20 => PimHelloOption::GenerationId(reader.u32()?),
+            24 => {
+                let mut addresses = Vec::new();
+                while !reader.is_empty() {
+                    addresses.push(read_unicast(&mut reader)?);
+                }
+                PimHelloOption::AddressList(addresses)
+            }
+            other => PimHelloOption::Other(other, value),
+        };
+        Ok(option)
+    }
+}
+
+/// Sources joined and pruned for a group by a Join/Prune message.
+pub struct PimGroupSet {
+    pub group: PimAddress,
+    pub joined: Vec<PimAddress>,
+    pub pruned: Vec<PimAddress>,
+}
+
+pub enum PimMessage<'a> {
+    Hello(Vec<PimHelloOption<'a>>), // 0
+    /// Multicast packet tunneled to the rendezvous point, an IP header alone for a
+    /// null register.
+    Register {
+        border: bool,
+        null: bool,
+        packet: Encapsulated<'a>,
+    }, // 1
+    RegisterStop {
+        group: PimAddress,
+        source: IpAddr,
+    }, // 2
+    JoinPrune {
+        upstream_neighbor: IpAddr,
+        holdtime: u16,
+        groups: Vec<PimGroupSet>,
+    }, // 3
+    Assert {
+        group: PimAddress,
+        source: IpAddr,
+        rpt: bool,
+        metric_preference: u32,
+        metric: u32,
+    }, // 5
+    Other(u8, &'a [u8]),
+}
+
+impl<'a> fmt::Display for PimMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PimMessage::Hello(_) => write!(f, "Hello"),
+            PimMessage::Register { .. } => write!(f, "Register"),
+            PimMessage::RegisterStop { .. } => write!(f, "Register-Stop"),
+            PimMessage::JoinPrune { .. } => write!(f, "Join/Prune"),
+            PimMessage::Assert { .. } => write!(f, "Assert"),
+            PimMessage::Other(typ, _) => write!(f, "Unknown message ({})", typ),
+        }
+    }
+}
+
+impl<'a> PimMessage<'a> {
+    fn read(typ: u8, body: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = ByteReader::new(body, "PIM message");
+        let message = match typ {
+            0 => {
+                let mut options = Vec::new();
+                while !reader.is_empty() {
+                    let typ = reader.u16()?;
+                    let len = usize::from(reader.u16()?);
+                    options.push(PimHelloOption::read(typ, reader.bytes(len)?)?);
+                }
+                PimMessage::Hello(options)
+            }
+            REGISTER => {
+                let flags = reader.u32()?;
+                let packet = reader.rest();
+                PimMessage::Register {
+                    border: flags & 0x8000_0000 != 0,
+                    null: flags & 0x4000_0000 != 0,
+                    packet: match packet.first().map(|byte| byte >> 4) {
+                        Some(6) => Encapsulated::ipv6(packet),
+                        _ => Encapsulated::ipv4(packet),
+                    },
+                }
+            }
+            2 => PimMessage::RegisterStop {
+                group: PimAddress::read(&mut reader)?,
+                source: read_unicast(&mut reader)?,
+            },
+            3 => {
+                let upstream_neighbor = read_unicast(&mut reader)?;
+                reader.u8()?;
+                let count = reader.u8()?;
+                let holdtime = reader.u16()?;
+                let mut groups = Vec::new();
+                for _ in 0..count {
+                    let group = PimAddress::read(&mut reader)?;
+                    let joined = reader.u16()?;
+                    let pruned = reader.u16()?;
+                    let joined = (0..joined)
+                        .map(|_| PimAddress::read(&mut reader))
+                        .collect::<Result<_, _>>()?;
+                    let pruned = (0..pruned)
+                        .map(|_| PimAddress::read(&mut reader))
+                        .collect::<Result<_, _>>()?;
+                    groups.push(PimGroupSet {
+                        group,
+                        joined,
+                        pruned,
+                    });
+                }
+                PimMessage::JoinPrune {
+                    upstream_neighbor,
+                    holdtime,
+                    groups,
+                }
+            }
+            5 => {
+                let group = PimAddress::read(&mut reader)?;
+                let source = read_unicast(&mut reader)?;
+                let preference = reader.u32()?;
+                PimMessage::Assert {
+                    group,
+                    source,
+                    rpt: preference & 0x8000_0000 != 0,
+                    metric_preference: preference & 0x7FFF_FFFF,
+                    metric: reader.u32()?,
+                }
+            }
+            other => PimMessage::Other(other, body),
+        };
+        Ok(message)
+    }
+}
+
+/// Protocol Independent Multicast message, version 2 (RFC 7761).
+pub struct PimFrame<'a> {
+    pseudo_header_sum: u16,
+    header: &'a [u8],
+    body: &'a [u8],
+}
+
+impl<'a> PimFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0] >> 4
+    }
+
+    pub fn message_type(&self) -> u8 {
+        self.header[0] & 0x0F
+    }
+
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[2..4]))
+    }
+
+    /// Whether the checksum matches, the IPv6 pseudo-header being covered as well.
+    /// Some implementations of Register messages sum the whole packet instead of
+    /// the header only, and are accepted too.
+    pub fn has_integrity(&self) -> bool {
+        let sum = |body: &[u8]| {
+            u16_checksum16(&[
+                self.pseudo_header_sum,
+                u8_slice_to_sum16(self.header),
+                u8_slice_to_sum16(body),
+            ]) == 0
+        };
+        match self.message_type() {
+            REGISTER => {
+                let len = self.body.len().min(REGISTER_HEADER_LEN - PIM_HEADER_LEN);
+                sum(&self.body[..len]) || sum(self.body)
+            }
+            _ => sum(self.body),
+        }
+    }
+
+    pub fn message(&self) -> Result<PimMessage<'a>, FrameError> {
+        PimMessage::read(self.message_type(), self.body)
+    }
+}
+
+impl<'a> TryFrom<IpHeader<'a>> for PimFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(ip: IpHeader<'a>) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(ip.payload(), "PIM header");
+        let header = reader.bytes(PIM_HEADER_LEN)?;
+        if header[0] >> 4 != 2 {
+            return Err(reader.malformed("unsupported version"));
+        }
+        let body = reader.rest();
+        // Only PIM over IPv6 covers the pseudo-header
+        let pseudo_header_sum = match ip {
+            IpHeader::V4(_) => 0,
+            IpHeader::V6(_) => ip.pseudo_header_sum(0x67, PIM_HEADER_LEN + body.len()),
+        };
+        Ok(Self {
+            pseudo_header_sum,
+            header,
+            body,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use super::*;
+
+const VRRP_HEADER_LEN: usize = 8;
+/// Authentication data following the addresses of VRRPv2 advertisements.
+const VRRPV2_AUTH_LEN: usize = 8;
+
+/// Virtual Router Redundancy Protocol advertisement, version 2 (RFC 3768) over IPv4
+/// or version 3 (RFC 5798) over IPv4 and IPv6.
+pub struct VrrpFrame<'a> {
+    ipv6: bool,
+    /// Sum of the pseudo-header, covered by the checksum of VRRPv3 only.
+    pseudo_header_sum: u16,
+    header: &'a [u8],
+    addresses: &'a [u8],
+    auth: &'a [u8],
+}
+
+impl<'a> VrrpFrame<'a> {
+    pub fn version(&self) -> u8 {
+        self.header[0] >> 4
+    }
+
+    /// Type of the message, 1 being the only one defined (advertisement).
+    pub fn message_type(&self) -> u8 {
+        self.header[0] & 0x0F
+    }
+
+    /// Virtual router identifier.
+    pub fn vrid(&self) -> u8 {
+        self.header[1]
+    }
+
+    /// Priority of the sender, 255 for the owner of the addresses and 0 for a master
+    /// giving up its role.
+    pub fn priority(&self) -> u8 {
+        self.header[2]
+    }
+
+    /// Authentication type of VRRPv2, 0 for none and 1 for a simple text password.
+    pub fn auth_type(&self) -> Option<u8> {
+        match self.version() {
+            2 => Some(self.header[4]),
+            _ => None,
+        }
+    }
+
+    /// Authentication data of VRRPv2, such as a password.
+    pub fn auth_data(&self) -> &'a [u8] {
+        self.auth
+    }
+
+    /// Interval between advertisements, in seconds for VRRPv2 and centiseconds for
+    /// VRRPv3.
+    pub fn advert_interval(&self) -> Duration {
+        match self.version() {
+            2 => Duration::from_secs(u64::from(self.header[5])),
+            _ => {
+                let centis = u16::from_be_bytes([self.header[4] & 0x0F, self.header[5]]);
+                Duration::from_millis(u64::from(centis) * 10)
+            }
+        }
+    }
+
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.header[6..8]))
+    }
+
+    pub fn has_integrity(&self) -> bool {
+        let pseudo_header_sum = match self.version() {
+            2 => 0,
+            _ => self.pseudo_header_sum,
+        };
+        u16_checksum16(&[
+            pseudo_header_sum,
+            u8_slice_to_sum16(self.header),
+            u8_slice_to_sum16(self.addresses),
+            u8_slice_to_sum16(self.auth),
+        ]) == 0
+    }
+
+    /// Virtual addresses backed by the router.
+    pub fn addresses(&self) -> Vec<IpAddr> {
+        match self.ipv6 {
+            false => self
+                .addresses
+                .chunks_exact(4)
+                .map(|addr| IpAddr::from(clone_into_array::<[u8; 4], u8>(addr)))
+                .collect(),
+            true => self
+                .addresses
+                .chunks_exact(16)
+                .map(|addr| IpAddr::from(clone_into_array::<[u8; 16], u8>(addr)))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> TryFrom<IpHeader<'a>> for VrrpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(ip: IpHeader<'a>) -> Result<Self, Self::Error> {
+        let ipv6 = matches!(ip, IpHeader::V6(_));
+        let mut reader = ByteReader::new(ip.payload(), "VRRP header");
+        let header = reader.bytes(VRRP_HEADER_LEN)?;
+        let addr_len = match ipv6 {
+            false => 4,
+            true => 16,
+        };
+        let auth_len = match (header[0] >> 4, ipv6) {
+            (2, false) => VRRPV2_AUTH_LEN,
+            (3, _) => 0,
+            _ => return Err(reader.malformed("unsupported version")),
+        };
+        let addresses = reader.bytes(usize::from(header[3]) * addr_len)?;
+        let auth = reader.bytes(auth_len)?;
+        let len = VRRP_HEADER_LEN + addresses.len() + auth.len();
+        Ok(Self {
+            ipv6,
+            pseudo_header_sum: ip.pseudo_header_sum(0x70, len),
+            header,
+            addresses,
+            auth,
+        })
+    }
+}
@@ -1,7 +1,16 @@
+pub use ber::*;
+pub use error::*;
+pub use reader::*;
+
+pub mod application;
 pub mod datalink;
 pub mod internet;
 pub mod transport;
 
+mod ber;
+mod error;
+mod reader;
+
 pub struct UnknownFrame<'a> {
     type_id: u16,
     payload: &'a [u8],
@@ -0,0 +1,104 @@
+use super::FrameError;
+
+/// Bounds checked big endian reader over a byte slice.
+#[derive(Clone)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    what: &'static str,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Reader over `data`, naming `what` is being read in the errors it returns.
+    pub fn new(data: &'a [u8], what: &'static str) -> Self {
+        Self { data, pos: 0, what }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Name of what is being read, as given to `new`.
+    pub fn what(&self) -> &'static str {
+        self.what
+    }
+
+    pub fn seek(&mut self, pos: usize) -> Result<(), FrameError> {
+        if pos > self.data.len() {
+            return Err(self.truncated(pos - self.pos));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], FrameError> {
+        if len > self.remaining() {
+            return Err(self.truncated(len));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+
+    pub fn u8(&mut self) -> Result<u8, FrameError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, FrameError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u24(&mut self) -> Result<u32, FrameError> {
+        let bytes = self.bytes(3)?;
+        Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, FrameError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, FrameError> {
+        Ok(u64::from(self.u32()?) << 32 | u64::from(self.u32()?))
+    }
+
+    /// Reads a `len` bytes long sub-slice as a new reader.
+    pub fn sub(&mut self, len: usize) -> Result<ByteReader<'a>, FrameError> {
+        Ok(ByteReader::new(self.bytes(len)?, self.what))
+    }
+
+    pub fn malformed(&self, reason: &'static str) -> FrameError {
+        FrameError::Malformed {
+            what: self.what,
+            reason,
+        }
+    }
+
+    fn truncated(&self, needed: usize) -> FrameError {
+        FrameError::Truncated {
+            what: self.what,
+            offset: self.pos,
+            needed,
+            available: self.remaining(),
+        }
+    }
+}
@@ -1,7 +1,28 @@
+pub use sctp_frame::*;
 pub use tcp_frame::*;
 pub use udp_frame::*;
 
 use crate::netframe::internet::*;
+use crate::netframe::{ByteReader, FrameError};
 
+mod sctp_frame;
 mod tcp_frame;
 mod udp_frame;
+
+/// Checks that `payload` holds a whole TCP header, options included, before it is
+/// parsed by the infallible `TcpFrame::from`.
+pub(crate) fn check_tcp(payload: &[u8]) -> Result<(), FrameError> {
+    let mut reader = ByteReader::new(payload, "TCP header");
+    let data_offset = reader.bytes(20)?[12] >> 4;
+    if data_offset < 5 {
+        return Err(reader.malformed("header length below 20 bytes"));
+    }
+    reader.seek(usize::from(data_offset) * 4)
+}
+
+/// Checks that `payload` holds a whole UDP header, before it is parsed by the
+/// infallible `UdpFrame::from`.
+pub(crate) fn check_udp(payload: &[u8]) -> Result<(), FrameError> {
+    let mut reader = ByteReader::new(payload, "UDP header");
+    reader.bytes(8).map(|_| ())
+}
@@ -0,0 +1,465 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::clone_into_array;
+use crate::error_check::*;
+use crate::netframe::application::*;
+use crate::netframe::{ByteReader, FrameError, UnknownFrame};
+
+const SCTP_HEADER_LEN: usize = 12;
+
+/// Name of a payload protocol identifier assigned by IANA.
+pub fn sctp_ppid_name(ppid: u32) -> Option<&'static str> {
+    let name = match ppid {
+        1 => "IUA",
+        2 => "M2UA",
+        3 => "M3UA",
+        4 => "SUA",
+        5 => "M2PA",
+        6 => "V5UA",
+        7 => "H.248",
+        18 => "S1AP",
+        19 => "RUA",
+        20 => "HNBAP",
+        24 => "SBc-AP",
+        25 => "NBAP",
+        27 => "X2AP",
+        29 => "LCS-AP",
+        31 => "SABP",
+        43 => "M2AP",
+        44 => "M3AP",
+        46 => "Diameter",
+        47 => "Diameter over DTLS",
+        50 => "WebRTC DCEP",
+        51 => "WebRTC String",
+        53 => "WebRTC Binary",
+        60 => "NGAP",
+        61 => "XnAP",
+        62 => "F1AP",
+        63 => "HTTP",
+        64 => "E1AP",
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub fn sctp_cause_name(code: u16) -> Option<&'static str> {
+    let name = match code {
+        1 => "Invalid Stream Identifier",
+        2 => "Missing Mandatory Parameter",
+        3 => "Stale Cookie Error",
+        4 => "Out of Resource",
+        5 => "Unresolvable Address",
+        6 => "Unrecognized Chunk Type",
+        7 => "Invalid Mandatory Parameter",
+        8 => "Unrecognized Parameters",
+        9 => "No User Data",
+        10 => "Cookie Received While Shutting Down",
+        11 => "Restart of an Association with New Addresses",
+        12 => "User Initiated Abort",
+        13 => "Protocol Violation",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Reads the type-length-value fields following the fixed part of a chunk, each
+/// padded to 4 bytes.
+fn read_tlvs<'a>(reader: &mut ByteReader<'a>) -> Result<Vec<(u16, &'a [u8])>, FrameError> {
+    let mut tlvs = Vec::new();
+    while !reader.is_empty() {
+        let typ = reader.u16()?;
+        // The length includes the type and length fields, but not the padding
+        let len = usize::from(reader.u16()?);
+        if len < 4 {
+            return Err(reader.malformed("parameter shorter than its header"));
+        }
+        tlvs.push((typ, reader.bytes(len - 4)?));
+        let padding = (4 - len % 4) % 4;
+        reader.bytes(padding.min(reader.remaining()))?;
+    }
+    Ok(tlvs)
+}
+
+/// Parameter of an INIT or INIT ACK chunk.
+pub struct SctpParameter<'a> {
+    pub typ: u16,
+    pub value: &'a [u8],
+}
+
+impl<'a> fmt::Display for SctpParameter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.typ, self.value) {
+            (5, addr) if addr.len() == 4 => {
+                let addr = IpAddr::from(clone_into_array::<[u8; 4], u8>(addr));
+                write!(f, "IPv4 address: {}", addr)
+            }
+            (6, addr) if addr.len() == 16 => {
+                let addr = IpAddr::from(clone_into_array::<[u8; 16], u8>(addr));
+                write!(f, "IPv6 address: {}", addr)
+            }
+            (7, cookie) => write!(f, "State cookie ({} bytes)", cookie.len()),
+            (9, [a, b, c, d]) => write!(
+                f,
+                "Cookie preservative: {} ms",
+                u32::from_be_bytes([*a, *b, *c, *d])
+            ),
+            (11, name) => write!(f, "Host name: {}", String::from_utf8_lossy(name)),
+            (12, types) => {
+                let types: Vec<_> = types
+                    .chunks_exact(2)
+                    .map(|typ| match u16::from_be_bytes([typ[0], typ[1]]) {
+                        5 => String::from("IPv4"),
+                        6 => String::from("IPv6"),
+                        11 => String::from("Host name"),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                write!(f, "Supported address types: {}", types.join(", "))
+            }
+            (0x8000, _) => write!(f, "ECN capable"),
+            (0x8008, chunks) => write!(f, "Supported extensions: {}", to_hex(chunks)),
+            (0xC000, _) => write!(f, "Forward TSN supported"),
+            (0xC006, [a, b, c, d]) => write!(
+                f,
+                "Adaptation layer indication: 0x{:08X}",
+                u32::from_be_bytes([*a, *b, *c, *d])
+            ),
+            (typ, value) => write!(f, "0x{:04X}: {}", typ, to_hex(value)),
+        }
+    }
+}
+
+/// Error cause of an ABORT or ERROR chunk.
+pub struct SctpErrorCause<'a> {
+    pub code: u16,
+    pub info: &'a [u8],
+}
+
+impl<'a> fmt::Display for SctpErrorCause<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sctp_cause_name(self.code) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "Unknown cause ({})", self.code)?,
+        }
+        match self.code {
+            // Reasons given as text
+            12 | 13 if !self.info.is_empty() => {
+                write!(f, ": {}", String::from_utf8_lossy(self.info))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fixed part of an INIT or INIT ACK chunk.
+pub struct SctpInit<'a> {
+    pub initiate_tag: u32,
+    /// Advertised receiver window credit.
+    pub a_rwnd: u32,
+    pub outbound_streams: u16,
+    pub inbound_streams: u16,
+    pub initial_tsn: u32,
+    pub params: Vec<SctpParameter<'a>>,
+}
+
+impl<'a> SctpInit<'a> {
+    fn read(reader: &mut ByteReader<'a>) -> Result<Self, FrameError> {
+        Ok(Self {
+            initiate_tag: reader.u32()?,
+            a_rwnd: reader.u32()?,
+            outbound_streams: reader.u16()?,
+            inbound_streams: reader.u16()?,
+            initial_tsn: reader.u32()?,
+            params: read_tlvs(reader)?
+                .into_iter()
+                .map(|(typ, value)| SctpParameter { typ, value })
+                .collect(),
+        })
+    }
+}
+
+impl<'a> fmt::Display for SctpInit<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "initiate tag 0x{:08X}, a_rwnd {}, {} outbound and {} inbound streams, initial TSN {}",
+            self.initiate_tag,
+            self.a_rwnd,
+            self.outbound_streams,
+            self.inbound_streams,
+            self.initial_tsn
+        )
+    }
+}
+
+/// User data of a DATA chunk, or a fragment of it.
+pub struct SctpData<'a> {
+    flags: u8,
+    pub tsn: u32,
+    pub stream_id: u16,
+    pub stream_seq: u16,
+    /// Payload protocol identifier.
+    pub ppid: u32,
+    pub payload: &'a [u8],
+    ports: (u16, u16),
+}
+
+impl<'a> SctpData<'a> {
+    pub fn is_unordered(&self) -> bool {
+        self.flags & 0x04 != 0
+    }
+
+    /// Whether the chunk carries a whole user message rather than a fragment of it.
+    pub fn is_complete(&self) -> bool {
+        self.flags & 0x03 == 0x03
+    }
+
+    /// Decodes the user message by its payload protocol identifier, or by the ports
+    /// for protocols sent without one.
+    pub fn try_next_header(&self) -> AppProtocol<'a> {
+        let (src, dest) = self.ports;
+        match (self.ppid, src, dest) {
+            // Fragments are left undissected
+            (63, _, _) if self.is_complete() && HttpFrame::is_http(self.payload) => {
+                AppProtocol::parse("HTTP", HttpFrame::try_from(self.payload), AppProtocol::Http)
+            }
+            // SIP has no identifier of its own
+            (0, 5060, _) | (0, _, 5060) if self.is_complete() && SipFrame::is_sip(self.payload) => {
+                AppProtocol::parse("SIP", SipFrame::try_from(self.payload), AppProtocol::Sip)
+            }
+            _ => AppProtocol::Other(UnknownFrame::from_u8_slice(
+                self.payload,
+                service_port(src, dest),
+            )),
+        }
+    }
+}
+
+pub enum SctpChunk<'a> {
+    Data(SctpData<'a>),    // 0
+    Init(SctpInit<'a>),    // 1
+    InitAck(SctpInit<'a>), // 2
+    Sack {
+        cumulative_tsn_ack: u32,
+        a_rwnd: u32,
+        /// Start and end offsets from the cumulative TSN of the blocks received.
+        gap_blocks: Vec<(u16, u16)>,
+        duplicate_tsns: Vec<u32>,
+    }, // 3
+    Heartbeat(&'a [u8]),   // 4
+    HeartbeatAck(&'a [u8]), // 5
+    Abort {
+        /// Whether the sender had no state for the association.
+        no_tcb: bool,
+        causes: Vec<SctpErrorCause<'a>>,
+    }, // 6
+    Shutdown {
+        cumulative_tsn_ack: u32,
+    }, // 7
+    ShutdownAck,           // 8
+    Error(Vec<SctpErrorCause<'a>>), // 9
+    CookieEcho(&'a [u8]),  // 10
+    CookieAck,             // 11
+    ShutdownComplete,      // 14
+    Other {
+        typ: u8,
+        flags: u8,
+        value: &'a [u8],
+    },
+}
+
+impl<'a> fmt::Display for SctpChunk<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SctpChunk::Data(data) => {
+                write!(
+                    f,
+                    "DATA (TSN {}, stream {}, SSN {}, PPID ",
+                    data.tsn, data.stream_id, data.stream_seq
+                )?;
+                match sctp_ppid_name(data.ppid) {
+                    Some(name) => write!(f, "{} ({})", name, data.ppid)?,
+                    None => write!(f, "{}", data.ppid)?,
+                }
+                write!(f, ", {} bytes", data.payload.len())?;
+                if data.is_unordered() {
+                    write!(f, ", unordered")?;
+                }
+                match data.flags & 0x03 {
+                    0x02 => write!(f, ", first fragment)"),
+                    0x00 => write!(f, ", middle fragment)"),
+                    0x01 => write!(f, ", last fragment)"),
+                    _ => write!(f, ")"),
+                }
+            }
+            SctpChunk::Init(init) => write!(f, "INIT ({})", init),
+            SctpChunk::InitAck(init) => write!(f, "INIT ACK ({})", init),
+            SctpChunk::Sack {
+                cumulative_tsn_ack,
+                a_rwnd,
+                gap_blocks,
+                duplicate_tsns,
+            } => write!(
+                f,
+                "SACK (cumulative TSN ACK {}, a_rwnd {}, {} gap blocks, {} duplicate TSNs)",
+                cumulative_tsn_ack,
+                a_rwnd,
+                gap_blocks.len(),
+                duplicate_tsns.len()
+            ),
+            SctpChunk::Heartbeat(_) => write!(f, "HEARTBEAT"),
+            SctpChunk::HeartbeatAck(_) => write!(f, "HEARTBEAT ACK"),
+            SctpChunk::Abort { causes, .. } => {
+                write!(f, "ABORT")?;
+                for cause in causes {
+                    write!(f, " ({})", cause)?;
+                }
+                Ok(())
+            }
+            SctpChunk::Shutdown { cumulative_tsn_ack } => {
+                write!(f, "SHUTDOWN (cumulative TSN ACK {})", cumulative_tsn_ack)
+            }
+            SctpChunk::ShutdownAck => write!(f, "SHUTDOWN ACK"),
+            SctpChunk::Error(causes) => {
+                write!(f, "ERROR")?;
+                for cause in causes {
+                    write!(f, " ({})", cause)?;
+                }
+                Ok(())
+            }
+            SctpChunk::CookieEcho(cookie) => write!(f, "COOKIE ECHO ({} bytes)", cookie.len()),
+            SctpChunk::CookieAck => write!(f, "COOKIE ACK"),
+            SctpChunk::ShutdownComplete => write!(f, "SHUTDOWN COMPLETE"),
+            SctpChunk::Other { typ, value, .. } => {
+                write!(f, "Unknown ({}, {} bytes)", typ, value.len())
+            }
+        }
+    }
+}
+
+fn read_causes<'a>(reader: &mut ByteReader<'a>) -> Result<Vec<SctpErrorCause<'a>>, FrameError> {
+    Ok(read_tlvs(reader)?
+        .into_iter()
+        .map(|(code, info)| SctpErrorCause { code, info })
+        .collect())
+}
+
+impl<'a> SctpChunk<'a> {
+    fn read(reader: &mut ByteReader<'a>, ports: (u16, u16)) -> Result<Self, FrameError> {
+        let typ = reader.u8()?;
+        let flags = reader.u8()?;
+        // The length includes the chunk header, but not the padding
+        let len = usize::from(reader.u16()?);
+        if len < 4 {
+            return Err(reader.malformed("chunk shorter than its header"));
+        }
+        let mut body = reader.sub(len - 4)?;
+        let padding = (4 - len % 4) % 4;
+        reader.bytes(padding.min(reader.remaining()))?;
+        Ok(match typ {
+            0 => SctpChunk::Data(SctpData {
+                flags,
+                tsn: body.u32()?,
+                stream_id: body.u16()?,
+                stream_seq: body.u16()?,
+                ppid: body.u32()?,
+                payload: body.rest(),
+                ports,
+            }),
+            1 => SctpChunk::Init(SctpInit::read(&mut body)?),
+            2 => SctpChunk::InitAck(SctpInit::read(&mut body)?),
+            3 => {
+                let cumulative_tsn_ack = body.u32()?;
+                let a_rwnd = body.u32()?;
+                let gap_count = body.u16()?;
+                let duplicate_count = body.u16()?;
+                let mut gap_blocks = Vec::new();
+                for _ in 0..gap_count {
+                    gap_blocks.push((body.u16()?, body.u16()?));
+                }
+                let mut duplicate_tsns = Vec::new();
+                for _ in 0..duplicate_count {
+                    duplicate_tsns.push(body.u32()?);
+                }
+                SctpChunk::Sack {
+                    cumulative_tsn_ack,
+                    a_rwnd,
+                    gap_blocks,
+                    duplicate_tsns,
+                }
+            }
+            4 => SctpChunk::Heartbeat(body.rest()),
+            5 => SctpChunk::HeartbeatAck(body.rest()),
+            6 => SctpChunk::Abort {
+                no_tcb: flags & 0x01 != 0,
+                causes: read_causes(&mut body)?,
+            },
+            7 => SctpChunk::Shutdown {
+                cumulative_tsn_ack: body.u32()?,
+            },
+            8 => SctpChunk::ShutdownAck,
+            9 => SctpChunk::Error(read_causes(&mut body)?),
+            10 => SctpChunk::CookieEcho(body.rest()),
+            11 => SctpChunk::CookieAck,
+            14 => SctpChunk::ShutdownComplete,
+            typ => SctpChunk::Other {
+                typ,
+                flags,
+                value: body.rest(),
+            },
+        })
+    }
+}
+
+/// Stream Control Transmission Protocol packet (RFC 9260).
+pub struct SctpFrame<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> SctpFrame<'a> {
+    pub fn src_port(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.raw[0..2]))
+    }
+
+    pub fn dest_port(&self) -> u16 {
+        u16::from_be_bytes(clone_into_array(&self.raw[2..4]))
+    }
+
+    pub fn verification_tag(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.raw[4..8]))
+    }
+
+    pub fn checksum(&self) -> u32 {
+        u32::from_be_bytes(clone_into_array(&self.raw[8..12]))
+    }
+
+    /// Whether the CRC32c checksum covers the packet correctly. The checksum is
+    /// computed with the field zeroed, and sent least significant byte first.
+    pub fn has_integrity(&self) -> bool {
+        let mut packet = self.raw.to_vec();
+        packet[8..12].fill(0);
+        crc32c(&packet) == u32::from_le_bytes(clone_into_array(&self.raw[8..12]))
+    }
+
+    pub fn chunks(&self) -> Result<Vec<SctpChunk<'a>>, FrameError> {
+        let mut reader = ByteReader::new(&self.raw[SCTP_HEADER_LEN..], "SCTP chunk");
+        let ports = (self.src_port(), self.dest_port());
+        let mut chunks = Vec::new();
+        while !reader.is_empty() {
+            chunks.push(SctpChunk::read(&mut reader, ports)?);
+        }
+        Ok(chunks)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SctpFrame<'a> {
+    type Error = FrameError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = ByteReader::new(slice, "SCTP header");
+        reader.bytes(SCTP_HEADER_LEN)?;
+        Ok(Self { raw: slice })
+    }
+}
@@ -1,10 +1,109 @@
+use std::convert::TryFrom;
+use std::fmt;
+
 use ux::*;
 
 use crate::clone_into_array;
 use crate::error_check::*;
+use crate::netframe::application::*;
+use crate::netframe::UnknownFrame;
 
 use super::{Ipv4Frame, Ipv6Frame};
 
+pub enum TcpOption<'a> {
+    EndOfList,             // 0
+    Nop,                   // 1
+    Mss(u16),              // 2
+    WindowScale(u8),       // 3
+    SackPermitted,         // 4
+    Sack(Vec<(u32, u32)>), // 5
+    Timestamp(u32, u32),   // 8
+    Other(u8, &'a [u8]),
+    Malformed(&'a [u8]),
+}
+
+impl<'a> fmt::Display for TcpOption<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EndOfList => write!(f, "End of option list"),
+            Self::Nop => write!(f, "No-Operation"),
+            Self::Mss(mss) => write!(f, "Maximum segment size: {} bytes", mss),
+            Self::WindowScale(shift) => {
+                write!(f, "Window scale: {} (multiply by {})", shift, 1u32 << shift.min(&14))
+            }
+            Self::SackPermitted => write!(f, "SACK permitted"),
+            Self::Sack(blocks) => {
+                write!(f, "SACK:")?;
+                for (left, right) in blocks {
+                    write!(f, " {}-{}", left, right)?;
+                }
+                Ok(())
+            }
+            Self::Timestamp(val, ecr) => write!(f, "Timestamps: TSval {}, TSecr {}", val, ecr),
+            Self::Other(kind, _) => write!(f, "Unknown (0x{:02X})", kind),
+            Self::Malformed(_) => write!(f, "Malformed option"),
+        }
+    }
+}
+
+/// Iterator over the options of a TCP header. Stops after the end of option list
+/// or after yielding a `TcpOption::Malformed` with the unparsed remainder.
+pub struct TcpOptions<'a>(&'a [u8]);
+
+impl<'a> Iterator for TcpOptions<'a> {
+    type Item = TcpOption<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.0;
+        let kind = *data.first()?;
+        match kind {
+            0 => {
+                self.0 = &[];
+                return Some(TcpOption::EndOfList);
+            }
+            1 => {
+                self.0 = &data[1..];
+                return Some(TcpOption::Nop);
+            }
+            _ => {}
+        }
+
+        let len = usize::from(*data.get(1).unwrap_or(&0));
+        if len < 2 || len > data.len() {
+            self.0 = &[];
+            return Some(TcpOption::Malformed(data));
+        }
+        let (opt, rest) = data.split_at(len);
+        let value = &opt[2..];
+        self.0 = rest;
+        Some(match (kind, len) {
+            (2, 4) => TcpOption::Mss(u16::from_be_bytes(clone_into_array(value))),
+            (3, 3) => TcpOption::WindowScale(value[0]),
+            (4, 2) => TcpOption::SackPermitted,
+            (5, _) if value.len() % 8 == 0 => TcpOption::Sack(
+                value
+                    .chunks(8)
+                    .map(|block| {
+                        (
+                            u32::from_be_bytes(clone_into_array(&block[0..4])),
+                            u32::from_be_bytes(clone_into_array(&block[4..8])),
+                        )
+                    })
+                    .collect(),
+            ),
+            (8, 10) => TcpOption::Timestamp(
+                u32::from_be_bytes(clone_into_array(&value[0..4])),
+                u32::from_be_bytes(clone_into_array(&value[4..8])),
+            ),
+            (2, _) | (3, _) | (4, _) | (5, _) | (8, _) => {
+                self.0 = &[];
+                TcpOption::Malformed(data)
+            }
+            (other, _) => TcpOption::Other(other, value),
+        })
+    }
+}
+
 pub struct TcpFrame<'a> {
     pseudo_header_sum: u16,
     header: &'a [u8],
@@ -97,10 +196,51 @@ impl<'a> TcpFrame<'a> {
         self.opts
     }
 
+    pub fn options(&self) -> TcpOptions<'a> {
+        TcpOptions(self.opts.unwrap_or(&[]))
+    }
+
     pub fn payload(&self) -> &'a [u8] {
         self.payload
     }
 
+    pub fn try_next_header(self) -> AppProtocol<'a> {
+        match (self.src_port(), self.dest_port()) {
+            (src, dest) if self.payload.is_empty() => {
+                AppProtocol::Other(UnknownFrame::from_u8_slice(self.payload, service_port(src, dest)))
+            }
+            // Segments in the middle of a message are left undissected
+            (53, _) | (_, 53) if DnsFrame::is_dns_over_tcp(self.payload) => {
+                AppProtocol::parse("DNS", DnsFrame::try_from(self), AppProtocol::Dns)
+            }
+            // Segments in the middle of a body are left undissected
+            (80, _) | (_, 80) | (8000, _) | (_, 8000) | (8080, _) | (_, 8080)
+                if HttpFrame::is_http(self.payload) =>
+            {
+                AppProtocol::parse("HTTP", HttpFrame::try_from(self), AppProtocol::Http)
+            }
+            // Segments in the middle of a message are left undissected
+            (179, _) | (_, 179) if BgpFrame::is_bgp(self.payload) => {
+                AppProtocol::parse("BGP", BgpFrame::try_from(self), AppProtocol::Bgp)
+            }
+            (443, _) | (_, 443) | (8443, _) | (_, 8443) if TlsFrame::is_tls(self.payload) => {
+                AppProtocol::parse("TLS", TlsFrame::try_from(self), AppProtocol::Tls)
+            }
+            // Segments in the middle of a message are left undissected
+            (601, _) | (_, 601) if SyslogFrame::is_syslog(self.payload) => {
+                AppProtocol::parse("Syslog", SyslogFrame::try_from(self), AppProtocol::Syslog)
+            }
+            // Keep-alives and segments in the middle of a message are left undissected
+            (5060, _) | (_, 5060) if SipFrame::is_sip(self.payload) => {
+                AppProtocol::parse("SIP", SipFrame::try_from(self), AppProtocol::Sip)
+            }
+            (src, dest) => AppProtocol::Other(UnknownFrame::from_u8_slice(
+                self.payload,
+                service_port(src, dest),
+            )),
+        }
+    }
+
     pub fn has_integrity(&self) -> bool {
         u16_checksum16(&[
             self.pseudo_header_sum,
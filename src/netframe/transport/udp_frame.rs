@@ -1,5 +1,9 @@
+use std::convert::TryFrom;
+
 use crate::clone_into_array;
 use crate::error_check::*;
+use crate::netframe::application::*;
+use crate::netframe::UnknownFrame;
 
 use super::{Ipv4Frame, Ipv6Frame};
 
@@ -37,6 +41,76 @@ impl<'a> UdpFrame<'a> {
     pub fn payload(&self) -> &'a [u8] {
         self.payload
     }
+
+    /// Decodes the payload as RTCP or RTP regardless of the ports, for datagrams
+    /// sent to an endpoint negotiated by a session description.
+    pub fn try_media(self) -> AppProtocol<'a> {
+        if RtcpFrame::is_rtcp(self.payload) {
+            AppProtocol::parse("RTCP", RtcpFrame::try_from(self), AppProtocol::Rtcp)
+        } else {
+            AppProtocol::parse("RTP", RtpFrame::try_from(self), AppProtocol::Rtp)
+        }
+    }
+
+    pub fn try_next_header(self) -> AppProtocol<'a> {
+        match (self.src_port(), self.dest_port()) {
+            (53, _) | (_, 53) => AppProtocol::parse("DNS", DnsFrame::try_from(self), AppProtocol::Dns),
+            (67, 68) | (68, 67) | (67, 67) => {
+                AppProtocol::parse("DHCP", DhcpFrame::try_from(self), AppProtocol::Dhcp)
+            }
+            (123, _) | (_, 123) => {
+                AppProtocol::parse("NTP", NtpFrame::try_from(self), AppProtocol::Ntp)
+            }
+            (161, _) | (_, 161) | (162, _) | (_, 162) => {
+                AppProtocol::parse("SNMP", SnmpFrame::try_from(self), AppProtocol::Snmp)
+            }
+            (_, 514) => {
+                AppProtocol::parse("Syslog", SyslogFrame::try_from(self), AppProtocol::Syslog)
+            }
+            (520, 520) | (521, 521) => {
+                AppProtocol::parse("RIP", RipFrame::try_from(self), AppProtocol::Rip)
+            }
+            (546, 547) | (547, 546) | (547, 547) => {
+                AppProtocol::parse("DHCPv6", Dhcpv6Frame::try_from(self), AppProtocol::Dhcpv6)
+            }
+            (443, _) | (_, 443) if QuicFrame::is_quic(self.payload) => {
+                AppProtocol::parse("QUIC", QuicFrame::try_from(self), AppProtocol::Quic)
+            }
+            (1985, 1985) => {
+                AppProtocol::parse("HSRP", HsrpFrame::try_from(self), AppProtocol::Hsrp)
+            }
+            // Tunnels hash the inner flow into the source port, so only the
+            // destination port is meaningful
+            (_, 4789) => {
+                AppProtocol::parse("VXLAN", VxlanFrame::try_from(self), AppProtocol::Vxlan)
+            }
+            // Keep-alives are left undissected
+            (5060, _) | (_, 5060) if SipFrame::is_sip(self.payload) => {
+                AppProtocol::parse("SIP", SipFrame::try_from(self), AppProtocol::Sip)
+            }
+            (_, 6081) => {
+                AppProtocol::parse("Geneve", GeneveFrame::try_from(self), AppProtocol::Geneve)
+            }
+            // Media streams use dynamic ports, so they are recognized by their
+            // headers; RTP ports are even (RFC 3550, section 11)
+            (src, dest) if src >= 1024 && dest >= 1024 && RtcpFrame::is_rtcp(self.payload) => {
+                AppProtocol::parse("RTCP", RtcpFrame::try_from(self), AppProtocol::Rtcp)
+            }
+            (src, dest)
+                if src >= 1024
+                    && dest >= 1024
+                    && src % 2 == 0
+                    && dest % 2 == 0
+                    && RtpFrame::is_rtp(self.payload) =>
+            {
+                AppProtocol::parse("RTP", RtpFrame::try_from(self), AppProtocol::Rtp)
+            }
+            (src, dest) => AppProtocol::Other(UnknownFrame::from_u8_slice(
+                self.payload,
+                service_port(src, dest),
+            )),
+        }
+    }
 }
 
 impl<'a> From<Ipv4Frame<'a>> for UdpFrame<'a> {